@@ -0,0 +1,11 @@
+//! Feeds arbitrary bytes to the class file parser, which must reject malformed input with an error
+//!
+//! Run with `cargo +nightly fuzz run parse` from the repository root.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = jadis::classfile::parse(data);
+});
@@ -1,7 +1,69 @@
 //! Simplifies reading bytes from binary files
 //!
-//! This module contains all functionality necessary to read binary data from disk.
-//! It is essentially a wrapper around the low-level IO functions provided by Rust.
+//! This module contains all functionality necessary to read binary data from disk or from memory.
+//! It is essentially a wrapper around the low-level IO functions provided by Rust. Parsing only
+//! ever works on the bytes held by the reader, so class files do not have to come from disk.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Describes why bytes could not be read from the binary blob
+#[derive(Debug, PartialEq)]
+pub enum ByteReaderError {
+    /// The binary blob ended before the requested number of bytes could be read
+    UnexpectedEof {
+        offset: usize,
+        needed: usize,
+        length: usize,
+    },
+
+    /// The end of the requested range does not fit in a `usize`
+    Overflow { offset: usize, needed: usize },
+
+    /// A count declares more entries than the remaining bytes could possibly hold
+    ImplausibleCount {
+        offset: usize,
+        count: u16,
+        min_entry_size: usize,
+        remaining: usize,
+    },
+}
+
+impl fmt::Display for ByteReaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnexpectedEof {
+                offset,
+                needed,
+                length,
+            } => write!(
+                f,
+                "unexpected EOF at offset {:#X} (needed {} bytes, file is {:#X} bytes)",
+                offset, needed, length
+            ),
+            Self::Overflow { offset, needed } => write!(
+                f,
+                "cannot read {} bytes at offset {:#X}, the range does not fit in memory",
+                needed, offset
+            ),
+            Self::ImplausibleCount {
+                offset,
+                count,
+                min_entry_size,
+                remaining,
+            } => write!(
+                f,
+                "count of {} entries at offset {:#X} needs at least {} bytes, but only {} bytes remain",
+                count,
+                offset,
+                usize::from(*count) * min_entry_size,
+                remaining
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ByteReaderError {}
 
 /// Binary file reader
 pub struct ByteReader {
@@ -13,7 +75,7 @@ pub struct ByteReader {
 }
 
 impl ByteReader {
-    /// Create a new byte reader instance
+    /// Create a new byte reader instance that reads the entire file at the specified path
     pub fn new(path: &str) -> Self {
         let data = match std::fs::read(path) {
             Ok(file) => file,
@@ -23,22 +85,313 @@ impl ByteReader {
         Self { data, position: 0 }
     }
 
-    /// Read N bytes from the current position in the binary blob
-    pub fn read_n_bytes(&mut self, n: usize) -> Vec<u8> {
+    /// Create a new byte reader instance that reads from an in-memory binary blob
+    pub fn from_bytes(data: &[u8]) -> Self {
+        Self {
+            data: data.to_vec(),
+            position: 0,
+        }
+    }
+
+    /// Total number of bytes in the binary blob, regardless of the current position
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Indicates whether the binary blob does not contain any bytes at all
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Current read index into the binary blob
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Number of bytes between the current position and the end of the binary blob
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    /// Indicates whether every byte of the binary blob has been consumed
+    pub fn is_at_end(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Read every byte from the current position up to the end of the binary blob
+    ///
+    /// The bytes are borrowed from the reader, use `to_vec` to keep them around
+    pub fn read_remaining(&mut self) -> &[u8] {
         let from = self.position;
-        let to = self.position + n;
-        self.position += n;
+        self.position = self.data.len();
+        &self.data[from..]
+    }
 
-        let data = match self.data.get(from..to) {
-            Some(data) => data.to_vec(),
-            None => panic!("Unable to read {} bytes from the binary blob", n),
-        };
+    /// Read N bytes from the current position in the binary blob as an owned copy, which can be
+    /// stored without borrowing the reader
+    pub fn read_n_bytes(&mut self, n: usize) -> Result<Vec<u8>, ByteReaderError> {
+        let from = self.position;
+        let to = self.checked_end(n)?;
+
+        self.position = to;
+        Ok(self.data[from..to].to_vec())
+    }
+
+    /// Read a block of bytes whose length was stored in the file as a u32, e.g. an attribute's data
+    ///
+    /// A length that does not fit in a `usize` is reported as an overflow rather than truncated
+    pub fn read_u32_length(&mut self, length: u32) -> Result<Vec<u8>, ByteReaderError> {
+        self.read_n_bytes(usize::try_from(length).unwrap_or(usize::MAX))
+    }
+
+    /// Skip a block of bytes whose length was stored in the file as a u32, e.g. an attribute's data
+    ///
+    /// A length that does not fit in a `usize` is reported as an overflow rather than truncated
+    pub fn skip_u32_length(&mut self, length: u32) -> Result<(), ByteReaderError> {
+        self.skip_n_bytes(usize::try_from(length).unwrap_or(usize::MAX))
+    }
+
+    /// Read a single byte
+    pub fn read_u8(&mut self) -> Result<u8, ByteReaderError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    /// Read a big-endian u16
+    pub fn read_u16(&mut self) -> Result<u16, ByteReaderError> {
+        Ok(u16::from_be_bytes(self.read_array()?))
+    }
+
+    /// Read a big-endian u32
+    pub fn read_u32(&mut self) -> Result<u32, ByteReaderError> {
+        Ok(u32::from_be_bytes(self.read_array()?))
+    }
+
+    /// Read a big-endian i32
+    pub fn read_i32(&mut self) -> Result<i32, ByteReaderError> {
+        Ok(i32::from_be_bytes(self.read_array()?))
+    }
+
+    /// Read a big-endian i64
+    pub fn read_i64(&mut self) -> Result<i64, ByteReaderError> {
+        Ok(i64::from_be_bytes(self.read_array()?))
+    }
+
+    /// Read a big-endian IEEE 754 single-precision float
+    pub fn read_f32(&mut self) -> Result<f32, ByteReaderError> {
+        Ok(f32::from_be_bytes(self.read_array()?))
+    }
 
-        data
+    /// Read a big-endian IEEE 754 double-precision float
+    pub fn read_f64(&mut self) -> Result<f64, ByteReaderError> {
+        Ok(f64::from_be_bytes(self.read_array()?))
+    }
+
+    /// Read a big-endian u16 that counts the entries of a table, each entry being at least
+    /// `min_entry_size` bytes large
+    ///
+    /// Counts that could not possibly be backed by the remaining bytes are rejected up front, so a
+    /// crafted file cannot make the parser loop (and allocate) for entries that do not exist
+    pub fn read_count(&mut self, min_entry_size: usize) -> Result<u16, ByteReaderError> {
+        let offset = self.position;
+        let count = self.read_u16()?;
+        let remaining = self.remaining();
+
+        if usize::from(count) * min_entry_size > remaining {
+            self.position = offset;
+
+            return Err(ByteReaderError::ImplausibleCount {
+                offset,
+                count,
+                min_entry_size,
+                remaining,
+            });
+        }
+
+        Ok(count)
     }
 
     /// Skip the next N bytes relative to the current position in the binary blob
-    pub fn skip_n_bytes(&mut self, n: usize) {
-        self.position += n;
+    pub fn skip_n_bytes(&mut self, n: usize) -> Result<(), ByteReaderError> {
+        self.position = self.checked_end(n)?;
+        Ok(())
+    }
+
+    /// Read exactly N bytes, the width is part of the type so it cannot mismatch the conversion
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], ByteReaderError> {
+        let from = self.position;
+        let to = self.checked_end(N)?;
+        let mut bytes = [0; N];
+
+        bytes.copy_from_slice(&self.data[from..to]);
+        self.position = to;
+        Ok(bytes)
+    }
+
+    /// Compute the position right after the next N bytes, if those bytes exist
+    ///
+    /// The addition is checked, as N often comes straight from the (untrusted) input and could wrap
+    /// around on targets where `usize` is small
+    fn checked_end(&self, n: usize) -> Result<usize, ByteReaderError> {
+        let to = self
+            .position
+            .checked_add(n)
+            .ok_or(ByteReaderError::Overflow {
+                offset: self.position,
+                needed: n,
+            })?;
+
+        if to > self.data.len() {
+            return Err(ByteReaderError::UnexpectedEof {
+                offset: self.position,
+                needed: n,
+                length: self.data.len(),
+            });
+        }
+
+        Ok(to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ByteReader, ByteReaderError};
+
+    #[test]
+    fn test_unexpected_eof_message() {
+        let error = ByteReaderError::UnexpectedEof {
+            offset: 0x3F2,
+            needed: 4,
+            length: 0x3F0,
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "unexpected EOF at offset 0x3F2 (needed 4 bytes, file is 0x3F0 bytes)"
+        );
+    }
+
+    #[test]
+    fn test_read_past_end() {
+        let mut reader = ByteReader::from_bytes(&[0x00, 0x01, 0x02]);
+
+        assert_eq!(reader.read_n_bytes(2), Ok(vec![0x00, 0x01]));
+        assert_eq!(
+            reader.read_n_bytes(4),
+            Err(ByteReaderError::UnexpectedEof {
+                offset: 2,
+                needed: 4,
+                length: 3,
+            })
+        );
+        assert_eq!(
+            reader.read_n_bytes(1),
+            Ok(vec![0x02]),
+            "A failed read should not advance the position"
+        );
+    }
+
+    #[test]
+    fn test_read_overflow() {
+        let mut reader = ByteReader::from_bytes(&[0x00, 0x01]);
+        reader.read_n_bytes(1).unwrap();
+
+        assert_eq!(
+            reader.read_n_bytes(usize::MAX),
+            Err(ByteReaderError::Overflow {
+                offset: 1,
+                needed: usize::MAX,
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_remaining() {
+        let mut reader = ByteReader::from_bytes(&[0x00, 0x01, 0x02]);
+        reader.read_u8().unwrap();
+
+        assert_eq!(reader.read_remaining(), &[0x01, 0x02]);
+        assert!(reader.is_at_end());
+        assert!(
+            reader.read_remaining().is_empty(),
+            "Nothing remains after reading the rest"
+        );
+    }
+
+    #[test]
+    fn test_u32_length() {
+        let mut reader = ByteReader::from_bytes(&[0x00, 0x01, 0x02, 0x03]);
+
+        assert_eq!(reader.read_u32_length(2), Ok(vec![0x00, 0x01]));
+        assert_eq!(reader.skip_u32_length(1), Ok(()));
+        assert_eq!(
+            reader.skip_u32_length(u32::MAX),
+            Err(ByteReaderError::UnexpectedEof {
+                offset: 3,
+                needed: u32::MAX as usize,
+                length: 4,
+            })
+        );
+        assert_eq!(reader.read_u32_length(1), Ok(vec![0x03]));
+    }
+
+    #[test]
+    fn test_read_big_endian_numbers() {
+        let mut reader = ByteReader::from_bytes(&[
+            0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x3D, 0xFF, 0xFF, 0xFF, 0xFE, 0x07,
+        ]);
+
+        assert_eq!(reader.read_u32(), Ok(0xCAFEBABE));
+        assert_eq!(reader.read_u16(), Ok(61));
+        assert_eq!(reader.read_i32(), Ok(-2));
+        assert_eq!(reader.read_u8(), Ok(7));
+        assert_eq!(reader.position(), 11);
+        assert_eq!(
+            reader.read_u16(),
+            Err(ByteReaderError::UnexpectedEof {
+                offset: 11,
+                needed: 2,
+                length: 11,
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_implausible_count() {
+        let mut reader = ByteReader::from_bytes(&[0x00, 0x02, 0xAA, 0xBB, 0xCC, 0xDD]);
+
+        assert_eq!(
+            reader.read_count(4),
+            Err(ByteReaderError::ImplausibleCount {
+                offset: 0,
+                count: 2,
+                min_entry_size: 4,
+                remaining: 4,
+            })
+        );
+        assert_eq!(
+            reader.position(),
+            0,
+            "A rejected count should not advance the position"
+        );
+        assert_eq!(reader.read_count(2), Ok(2));
+    }
+
+    #[test]
+    fn test_remaining_and_is_at_end() {
+        let mut reader = ByteReader::from_bytes(&[0x01, 0x02, 0x03]);
+
+        assert_eq!(reader.remaining(), 3);
+        assert!(!reader.is_at_end());
+
+        reader.read_u16().unwrap();
+        assert_eq!(reader.remaining(), 1);
+
+        reader.read_u8().unwrap();
+        assert_eq!(reader.remaining(), 0);
+        assert!(reader.is_at_end());
+        assert!(
+            ByteReader::from_bytes(&[]).is_at_end(),
+            "Empty input is already at the end"
+        );
     }
 }
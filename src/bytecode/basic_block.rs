@@ -0,0 +1,140 @@
+//! Groups decoded instructions into basic blocks, the nodes of a control-flow graph
+//!
+//! A basic block is a run of instructions that is only entered through its first instruction and
+//! only left through its last one. Exception handlers are not taken into account, they live in the
+//! exception table of the code attribute rather than in the instructions themselves.
+
+use std::collections::BTreeSet;
+
+use super::Instruction;
+
+/// A run of instructions that always executes from start to end
+#[derive(Debug, PartialEq)]
+pub struct BasicBlock<'a> {
+    /// Offset of the first instruction in the code array
+    pub start_offset: u32,
+
+    /// Instructions in this block, in the order in which they appear in the code array
+    pub instructions: &'a [Instruction],
+
+    /// Start offsets of the blocks that may execute next, in ascending order
+    pub successors: Vec<u32>,
+}
+
+/// Split decoded instructions into basic blocks
+///
+/// A new block starts at every branch target and after every instruction that branches, returns,
+/// or throws. The instructions are expected to be in code array order, as returned by `decode`.
+pub fn basic_blocks(instructions: &[Instruction]) -> Vec<BasicBlock<'_>> {
+    let mut leaders = BTreeSet::new();
+
+    for (index, instruction) in instructions.iter().enumerate() {
+        let targets = instruction.branch_targets();
+        let ends_block = !targets.is_empty() || instruction.is_unconditional_transfer();
+
+        leaders.extend(targets);
+
+        if ends_block {
+            if let Some(next) = instructions.get(index + 1) {
+                leaders.insert(next.offset);
+            }
+        }
+    }
+
+    let mut blocks = vec![];
+    let mut start = 0;
+
+    for index in 0..instructions.len() {
+        let is_last = match instructions.get(index + 1) {
+            Some(next) => leaders.contains(&next.offset),
+            None => true,
+        };
+
+        if !is_last {
+            continue;
+        }
+
+        let block = &instructions[start..=index];
+        let last = &block[block.len() - 1];
+        let mut successors: BTreeSet<u32> = last.branch_targets().into_iter().collect();
+
+        if !last.is_unconditional_transfer() {
+            if let Some(next) = instructions.get(index + 1) {
+                successors.insert(next.offset);
+            }
+        }
+
+        blocks.push(BasicBlock {
+            start_offset: block[0].offset,
+            instructions: block,
+            successors: successors.into_iter().collect(),
+        });
+
+        start = index + 1;
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::basic_blocks;
+    use crate::bytecode::decode;
+
+    #[test]
+    fn test_if_else() {
+        // 0: iload_0, 1: ifeq 8, 4: iconst_1, 5: goto 9, 8: iconst_0, 9: ireturn
+        let instructions =
+            decode(&[0x1a, 0x99, 0x00, 0x07, 0x04, 0xa7, 0x00, 0x04, 0x03, 0xac]).unwrap();
+        let blocks = basic_blocks(&instructions);
+
+        let summary: Vec<(u32, usize, Vec<u32>)> = blocks
+            .iter()
+            .map(|block| {
+                (
+                    block.start_offset,
+                    block.instructions.len(),
+                    block.successors.clone(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (0, 2, vec![4, 8]),
+                (4, 2, vec![9]),
+                (8, 1, vec![9]),
+                (9, 1, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_straight_line_code() {
+        // aload_0, invokespecial #1, return
+        let instructions = decode(&[0x2a, 0xb7, 0x00, 0x01, 0xb1]).unwrap();
+        let blocks = basic_blocks(&instructions);
+
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].instructions.len(), 3);
+        assert!(
+            blocks[0].successors.is_empty(),
+            "Returning leaves the method"
+        );
+        assert!(basic_blocks(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_switch_successors() {
+        // iconst_0, tableswitch default=20 low=0 high=1 offsets=[16, 18], return
+        let instructions = decode(&[
+            0x03, 0xaa, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x12, 0xb1,
+        ])
+        .unwrap();
+        let blocks = basic_blocks(&instructions);
+
+        assert_eq!(blocks[0].successors, vec![17, 19, 21]);
+    }
+}
@@ -0,0 +1,532 @@
+//! Decodes the code array of a method into individual instructions
+//!
+//! Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-6.html
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::utils::{to_i16, to_i32, to_i8, to_u16};
+
+use super::{mnemonic, operand_bytes, OperandLayout};
+
+/// Opcode of the `iinc` instruction
+const OPCODE_IINC: u8 = 0x84;
+
+/// Element types of `newarray`, indexed by the `atype` operand minus four
+const ARRAY_TYPES: [&str; 8] = [
+    "boolean", "char", "float", "double", "byte", "short", "int", "long",
+];
+
+/// How the decoder treats opcodes that are reserved or not defined by the specification
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnknownOpcodes {
+    /// Decode the opcode as a single raw byte, rendered as `db 0xNN`, and continue with the next
+    /// byte
+    RawBytes,
+
+    /// Stop decoding with an error
+    Reject,
+}
+
+/// Describes why a code array could not be decoded
+#[derive(Debug, PartialEq)]
+pub enum DecodeError {
+    /// The opcode at the offset is reserved or not defined by the specification
+    UnknownOpcode { opcode: u8, offset: u32 },
+
+    /// The code array ends before the operands of the instruction at the offset do
+    Truncated { offset: u32 },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownOpcode { opcode, offset } => {
+                write!(f, "unknown opcode {:#04x} at offset {}", opcode, offset)
+            }
+            Self::Truncated { offset } => write!(f, "truncated instruction at offset {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A single Java Virtual Machine instruction
+#[derive(Debug, PartialEq)]
+pub struct Instruction {
+    /// Offset of the opcode in the code array
+    pub offset: u32,
+
+    /// Operation to be performed
+    pub opcode: u8,
+
+    /// Raw operand bytes that follow the opcode, including any alignment padding
+    pub operands: Vec<u8>,
+}
+
+impl Instruction {
+    /// Total number of bytes this instruction occupies in the code array
+    pub fn length(&self) -> usize {
+        1 + self.operands.len()
+    }
+
+    /// Fetch the constant pool index this instruction refers to, if it has one
+    ///
+    /// This covers the `ldc` family, field and method instructions, and the instructions that name
+    /// a class, such as `new` and `checkcast`
+    pub fn constant_pool_index(&self) -> Option<u16> {
+        match self.opcode {
+            0x12 => Some(u16::from(self.operands[0])),
+            0x13 | 0x14 | 0xb2..=0xbb | 0xbd | 0xc0 | 0xc1 | 0xc5 => {
+                Some(to_u16(&self.operands[0..2].to_vec()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Render the operands the way they are written in assembly, e.g. `#12` for a constant pool
+    /// index, `-1` for `bipush -1`, or `26` for a branch to offset 26
+    ///
+    /// Branch offsets are resolved to the offset of their target. Returns an empty string for
+    /// instructions without operands.
+    pub fn format_operands(&self) -> String {
+        let operands = &self.operands;
+        let u16_at = |index: usize| to_u16(&operands[index..index + 2].to_vec());
+        let i32_at = |index: usize| to_i32(&operands[index..index + 4].to_vec());
+
+        match self.opcode {
+            // bipush and sipush push a sign-extended byte and short
+            0x10 => to_i8(operands[0]).to_string(),
+            0x11 => to_i16(operands).to_string(),
+            0x12 => format!("#{}", operands[0]),
+            0x13 | 0x14 | 0xb2..=0xb8 | 0xbb | 0xbd | 0xc0 | 0xc1 => format!("#{}", u16_at(0)),
+            // Local variable indices of loads, stores, and ret
+            0x15..=0x19 | 0x36..=0x3a | 0xa9 => operands[0].to_string(),
+            0x84 => format!("{}, {}", operands[0], to_i8(operands[1])),
+            // Conditional and unconditional branches, and jsr
+            0x99..=0xa8 | 0xc6 | 0xc7 => self.target(i64::from(to_i16(operands))).to_string(),
+            0xaa | 0xab => self.format_switch(),
+            0xb9 => format!("#{}, {}", u16_at(0), operands[2]),
+            0xba => format!("#{}", u16_at(0)),
+            0xbc => match ARRAY_TYPES.get(usize::from(operands[0]).wrapping_sub(4)) {
+                Some(array_type) => array_type.to_string(),
+                None => format!("<invalid array type {}>", operands[0]),
+            },
+            0xc4 => {
+                let modified = mnemonic(operands[0]).unwrap_or("<unknown>");
+
+                if operands[0] == OPCODE_IINC {
+                    format!("{} {}, {}", modified, u16_at(1), to_i16(&operands[3..5]))
+                } else {
+                    format!("{} {}", modified, u16_at(1))
+                }
+            }
+            0xc5 => format!("#{}, {}", u16_at(0), operands[2]),
+            0xc8 | 0xc9 => self.target(i64::from(i32_at(0))).to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Offsets this instruction may transfer control to, other than the next instruction
+    ///
+    /// Covers conditional and unconditional branches, `jsr`, and both switches. The target of
+    /// `ret` is only known at run time, so it has none. Targets outside of the code array's
+    /// addressable range are left out.
+    pub fn branch_targets(&self) -> Vec<u32> {
+        let targets = match self.opcode {
+            0x99..=0xa8 | 0xc6 | 0xc7 => vec![self.target(i64::from(to_i16(&self.operands)))],
+            0xc8 | 0xc9 => vec![self.target(i64::from(to_i32(&self.operands[0..4].to_vec())))],
+            0xaa | 0xab => {
+                let (cases, default) = self.switch_cases();
+                cases
+                    .into_iter()
+                    .map(|(_, target)| target)
+                    .chain(Some(default))
+                    .collect()
+            }
+            _ => vec![],
+        };
+
+        targets
+            .into_iter()
+            .filter_map(|target| u32::try_from(target).ok())
+            .collect()
+    }
+
+    /// Check whether execution never continues with the next instruction, which is the case for
+    /// unconditional branches, switches, returns, `athrow`, and `ret`
+    pub fn is_unconditional_transfer(&self) -> bool {
+        matches!(self.opcode, 0xa7 | 0xa9..=0xb1 | 0xbf | 0xc8)
+    }
+
+    /// Resolve a branch offset relative to this instruction into an absolute offset
+    fn target(&self, relative: i64) -> i64 {
+        i64::from(self.offset) + relative
+    }
+
+    /// Read the `(key, target)` pairs and the default target of a `tableswitch` or `lookupswitch`
+    fn switch_cases(&self) -> (Vec<(i64, i64)>, i64) {
+        let padding = switch_padding(self.offset as usize);
+        let i32_at = |index: usize| i64::from(to_i32(&self.operands[index..index + 4].to_vec()));
+
+        let cases = if self.opcode == 0xaa {
+            let low = i32_at(padding + 4);
+            let high = i32_at(padding + 8);
+
+            (low..=high)
+                .enumerate()
+                .map(|(index, key)| (key, self.target(i32_at(padding + 12 + index * 4))))
+                .collect()
+        } else {
+            let pairs = i32_at(padding + 4).max(0) as usize;
+
+            (0..pairs)
+                .map(|pair| {
+                    let start = padding + 8 + pair * 8;
+                    (i32_at(start), self.target(i32_at(start + 4)))
+                })
+                .collect()
+        };
+
+        (cases, self.target(i32_at(padding)))
+    }
+
+    /// Render the cases of a `tableswitch` or `lookupswitch` as
+    /// `{ key: target, ..., default: target }`
+    fn format_switch(&self) -> String {
+        let (cases, default) = self.switch_cases();
+        let mut cases: Vec<String> = cases
+            .into_iter()
+            .map(|(key, target)| format!("{}: {}", key, target))
+            .collect();
+
+        cases.push(format!("default: {}", default));
+        format!("{{ {} }}", cases.join(", "))
+    }
+}
+
+impl fmt::Display for Instruction {
+    /// Render the instruction the way `javap -c` lists it, e.g. `    9: invokevirtual #12`
+    ///
+    /// Unknown opcodes are rendered as the raw byte, e.g. `    9: db 0xfe`. Width and alignment
+    /// apply to the instruction as a whole, which is used to line up comments.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mnemonic = match mnemonic(self.opcode) {
+            Some(mnemonic) => mnemonic,
+            None => return f.pad(&format!("{:>5}: db {:#04x}", self.offset, self.opcode)),
+        };
+        let operands = self.format_operands();
+        let mut text = format!("{:>5}: {}", self.offset, mnemonic);
+
+        if !operands.is_empty() {
+            text.push(' ');
+            text.push_str(&operands);
+        }
+
+        f.pad(&text)
+    }
+}
+
+/// Decode a code array into a list of instructions
+///
+/// Opcodes that are not defined by the specification are decoded as raw bytes without operands,
+/// see [`UnknownOpcodes::RawBytes`]. Fails if the code array ends in the middle of an instruction.
+pub fn decode(code: &[u8]) -> Result<Vec<Instruction>, DecodeError> {
+    decode_with(code, UnknownOpcodes::RawBytes)
+}
+
+/// Decode a code array into a list of instructions, treating opcodes that are not defined by the
+/// specification as configured
+///
+/// Fails if the code array ends in the middle of an instruction, including the jump table of a
+/// switch
+pub fn decode_with(
+    code: &[u8],
+    unknown_opcodes: UnknownOpcodes,
+) -> Result<Vec<Instruction>, DecodeError> {
+    let mut instructions = vec![];
+    let mut offset = 0;
+
+    while offset < code.len() {
+        let opcode = code[offset];
+
+        if unknown_opcodes == UnknownOpcodes::Reject && mnemonic(opcode).is_none() {
+            return Err(DecodeError::UnknownOpcode {
+                opcode,
+                offset: offset as u32,
+            });
+        }
+
+        let truncated = || DecodeError::Truncated {
+            offset: offset as u32,
+        };
+        let operand_length = operand_length(code, offset).ok_or_else(truncated)?;
+
+        let operands = code
+            .get(offset + 1..offset + 1 + operand_length)
+            .ok_or_else(truncated)?
+            .to_vec();
+
+        instructions.push(Instruction {
+            offset: offset as u32,
+            opcode,
+            operands,
+        });
+
+        offset += 1 + operand_length;
+    }
+
+    Ok(instructions)
+}
+
+/// Number of operand bytes following the opcode at the specified offset
+///
+/// Returns `None` if the code array ends before the size of a switch's jump table is known
+fn operand_length(code: &[u8], offset: usize) -> Option<usize> {
+    let length = match operand_bytes(code[offset]) {
+        Some(OperandLayout::Fixed(length)) => length,
+        Some(OperandLayout::TableSwitch) => {
+            let padding = switch_padding(offset);
+            let low = read_i32(code, offset + 1 + padding + 4)?;
+            let high = read_i32(code, offset + 1 + padding + 8)?;
+            let jump_offsets = (i64::from(high) - i64::from(low) + 1).max(0) as usize;
+
+            padding + 12 + jump_offsets * 4
+        }
+        Some(OperandLayout::LookupSwitch) => {
+            let padding = switch_padding(offset);
+            let npairs = read_i32(code, offset + 1 + padding + 4)?.max(0) as usize;
+
+            padding + 8 + npairs * 8
+        }
+        Some(OperandLayout::Wide) => match code.get(offset + 1) {
+            Some(&OPCODE_IINC) => 5,
+            _ => 3,
+        },
+        None => 0,
+    };
+
+    Some(length)
+}
+
+/// Number of padding bytes between a switch opcode and its first operand
+///
+/// The operands of `tableswitch` and `lookupswitch` start at an offset that is a multiple of four
+fn switch_padding(offset: usize) -> usize {
+    (4 - (offset + 1) % 4) % 4
+}
+
+/// Read a big-endian i32 at the specified offset in the code array, if the code array is long
+/// enough
+fn read_i32(code: &[u8], offset: usize) -> Option<i32> {
+    code.get(offset..offset + 4)
+        .map(|bytes| to_i32(&bytes.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, decode_with, DecodeError, Instruction, UnknownOpcodes};
+
+    #[test]
+    fn test_decode_simple_instructions() {
+        // aload_0, invokespecial #1, return
+        let instructions = decode(&[0x2a, 0xb7, 0x00, 0x01, 0xb1]).unwrap();
+
+        assert_eq!(
+            instructions,
+            vec![
+                Instruction {
+                    offset: 0,
+                    opcode: 0x2a,
+                    operands: vec![],
+                },
+                Instruction {
+                    offset: 1,
+                    opcode: 0xb7,
+                    operands: vec![0x00, 0x01],
+                },
+                Instruction {
+                    offset: 4,
+                    opcode: 0xb1,
+                    operands: vec![],
+                },
+            ],
+            "Incorrect instructions decoded"
+        );
+    }
+
+    #[test]
+    fn test_decode_unknown_opcodes() {
+        // aload_0, impdep1, return
+        let code = [0x2a, 0xfe, 0xb1];
+        let instructions = decode(&code).unwrap();
+
+        assert_eq!(
+            instructions.len(),
+            3,
+            "Decoding continues after an unknown opcode"
+        );
+        assert_eq!(instructions[1].to_string(), "    1: db 0xfe");
+        assert_eq!(instructions[2].to_string(), "    2: return");
+        assert_eq!(
+            decode_with(&code, UnknownOpcodes::RawBytes),
+            Ok(instructions)
+        );
+        assert_eq!(
+            decode_with(&code, UnknownOpcodes::Reject),
+            Err(DecodeError::UnknownOpcode {
+                opcode: 0xfe,
+                offset: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_truncated_instructions() {
+        // aload_0, invokespecial without its second operand byte
+        assert_eq!(
+            decode(&[0x2a, 0xb7, 0x00]),
+            Err(DecodeError::Truncated { offset: 1 })
+        );
+
+        // tableswitch that ends in the middle of its header, and one whose 16 jump offsets are
+        // missing
+        assert_eq!(
+            decode(&[0xaa, 0x00, 0x00, 0x00]),
+            Err(DecodeError::Truncated { offset: 0 })
+        );
+        assert_eq!(
+            decode(&[
+                0xaa, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00, 0x0f,
+            ]),
+            Err(DecodeError::Truncated { offset: 0 })
+        );
+
+        // wide as the last byte
+        assert_eq!(
+            decode(&[0xb1, 0xc4]),
+            Err(DecodeError::Truncated { offset: 1 })
+        );
+    }
+
+    #[test]
+    fn test_decode_wide_instructions() {
+        // wide iload 256, wide iinc 256 -1
+        let instructions =
+            decode(&[0xc4, 0x15, 0x01, 0x00, 0xc4, 0x84, 0x01, 0x00, 0xff, 0xff]).unwrap();
+
+        assert_eq!(instructions.len(), 2, "Expected two instructions");
+        assert_eq!(instructions[0].length(), 4, "Incorrect wide iload length");
+        assert_eq!(instructions[1].offset, 4, "Incorrect wide iinc offset");
+        assert_eq!(instructions[1].length(), 6, "Incorrect wide iinc length");
+    }
+
+    #[test]
+    fn test_decode_tableswitch_padding() {
+        // iconst_0, tableswitch (2 padding bytes) default=20 low=0 high=1 offsets=[16, 18], return
+        let code = vec![
+            0x03, 0xaa, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x12, 0xb1,
+        ];
+        let instructions = decode(&code).unwrap();
+
+        assert_eq!(instructions.len(), 3, "Expected three instructions");
+        assert_eq!(instructions[1].length(), 23, "Incorrect tableswitch length");
+        assert_eq!(
+            instructions[2].offset, 24,
+            "Incorrect offset after tableswitch"
+        );
+    }
+
+    #[test]
+    fn test_decode_lookupswitch_padding() {
+        // lookupswitch (3 padding bytes) default=12 npairs=1 match=5 offset=12, return
+        let code = vec![
+            0xab, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+            0x00, 0x05, 0x00, 0x00, 0x00, 0x0c, 0xb1,
+        ];
+        let instructions = decode(&code).unwrap();
+
+        assert_eq!(instructions.len(), 2, "Expected two instructions");
+        assert_eq!(
+            instructions[1].offset, 20,
+            "Incorrect offset after lookupswitch"
+        );
+    }
+
+    #[test]
+    fn test_format_negative_push_operands() {
+        // bipush -1, sipush -1000, iinc 1 -1
+        let instructions = decode(&[0x10, 0xff, 0x11, 0xfc, 0x18, 0x84, 0x01, 0xff]).unwrap();
+
+        assert_eq!(instructions[0].format_operands(), "-1");
+        assert_eq!(instructions[1].format_operands(), "-1000");
+        assert_eq!(instructions[2].format_operands(), "1, -1");
+    }
+
+    #[test]
+    fn test_format_operands() {
+        // getstatic #13, goto -3 (back to offset 0), newarray int, return
+        let instructions = decode(&[0xb2, 0x00, 0x0d, 0xa7, 0xff, 0xfd, 0xbc, 0x0a, 0xb1]).unwrap();
+
+        assert_eq!(instructions[0].format_operands(), "#13");
+        assert_eq!(
+            instructions[1].format_operands(),
+            "0",
+            "Branches show their target"
+        );
+        assert_eq!(instructions[2].format_operands(), "int");
+        assert_eq!(instructions[3].format_operands(), "");
+    }
+
+    #[test]
+    fn test_format_switch_operands() {
+        // iconst_0, tableswitch default=20 low=0 high=1 offsets=[16, 18], return
+        let code = vec![
+            0x03, 0xaa, 0x00, 0x00, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x12, 0xb1,
+        ];
+
+        assert_eq!(
+            decode(&code).unwrap()[1].format_operands(),
+            "{ 0: 17, 1: 19, default: 21 }"
+        );
+
+        // lookupswitch default=12 npairs=1 match=5 offset=12, return
+        let code = vec![
+            0xab, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+            0x00, 0x05, 0x00, 0x00, 0x00, 0x0c, 0xb1,
+        ];
+
+        assert_eq!(
+            decode(&code).unwrap()[0].format_operands(),
+            "{ 5: 12, default: 12 }"
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        // aload_0, invokevirtual #12, return
+        let instructions = decode(&[0x2a, 0xb6, 0x00, 0x0c, 0xb1]).unwrap();
+
+        assert_eq!(instructions[0].to_string(), "    0: aload_0");
+        assert_eq!(instructions[1].to_string(), "    1: invokevirtual #12");
+        assert_eq!(
+            format!("{:<27}|", instructions[1]),
+            "    1: invokevirtual #12   |",
+            "Padding applies to the whole instruction"
+        );
+    }
+
+    #[test]
+    fn test_constant_pool_index() {
+        // ldc #5, ldc2_w #300, new #8, iload_1
+        let instructions = decode(&[0x12, 0x05, 0x14, 0x01, 0x2c, 0xbb, 0x00, 0x08, 0x1b]).unwrap();
+
+        assert_eq!(instructions[0].constant_pool_index(), Some(5));
+        assert_eq!(instructions[1].constant_pool_index(), Some(300));
+        assert_eq!(instructions[2].constant_pool_index(), Some(8));
+        assert_eq!(instructions[3].constant_pool_index(), None);
+    }
+}
@@ -0,0 +1,11 @@
+//! Contains all functionality necessary to work with Java Virtual Machine instructions
+//! Instructions are stored in the code array of a method's code attribute
+
+// Re-export modules to make it easy to use for any code outside of this module
+pub use basic_block::*;
+pub use instruction::*;
+pub use opcode::*;
+
+mod basic_block;
+mod instruction;
+mod opcode;
@@ -0,0 +1,284 @@
+//! Maps opcodes onto their mnemonics and operand layouts
+//!
+//! Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-7.html
+
+/// Describes how many operand bytes follow an opcode in the code array
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OperandLayout {
+    /// A fixed number of operand bytes
+    Fixed(usize),
+
+    /// `tableswitch`: alignment padding, a default, a low and high bound, and a jump table
+    TableSwitch,
+
+    /// `lookupswitch`: alignment padding, a default, and a number of match-offset pairs
+    LookupSwitch,
+
+    /// `wide`: a modified opcode followed by two operand bytes, or four in case of `iinc`
+    Wide,
+}
+
+/// Mnemonic and operand layout of every opcode in use, indexed by opcode
+///
+/// The reserved opcodes `breakpoint` (0xca), `impdep1` (0xfe), and `impdep2` (0xff) are
+/// intentionally absent, they must never appear in a class file
+const OPCODES: [(&str, OperandLayout); 202] = [
+    ("nop", OperandLayout::Fixed(0)),              // 0x00
+    ("aconst_null", OperandLayout::Fixed(0)),      // 0x01
+    ("iconst_m1", OperandLayout::Fixed(0)),        // 0x02
+    ("iconst_0", OperandLayout::Fixed(0)),         // 0x03
+    ("iconst_1", OperandLayout::Fixed(0)),         // 0x04
+    ("iconst_2", OperandLayout::Fixed(0)),         // 0x05
+    ("iconst_3", OperandLayout::Fixed(0)),         // 0x06
+    ("iconst_4", OperandLayout::Fixed(0)),         // 0x07
+    ("iconst_5", OperandLayout::Fixed(0)),         // 0x08
+    ("lconst_0", OperandLayout::Fixed(0)),         // 0x09
+    ("lconst_1", OperandLayout::Fixed(0)),         // 0x0a
+    ("fconst_0", OperandLayout::Fixed(0)),         // 0x0b
+    ("fconst_1", OperandLayout::Fixed(0)),         // 0x0c
+    ("fconst_2", OperandLayout::Fixed(0)),         // 0x0d
+    ("dconst_0", OperandLayout::Fixed(0)),         // 0x0e
+    ("dconst_1", OperandLayout::Fixed(0)),         // 0x0f
+    ("bipush", OperandLayout::Fixed(1)),           // 0x10
+    ("sipush", OperandLayout::Fixed(2)),           // 0x11
+    ("ldc", OperandLayout::Fixed(1)),              // 0x12
+    ("ldc_w", OperandLayout::Fixed(2)),            // 0x13
+    ("ldc2_w", OperandLayout::Fixed(2)),           // 0x14
+    ("iload", OperandLayout::Fixed(1)),            // 0x15
+    ("lload", OperandLayout::Fixed(1)),            // 0x16
+    ("fload", OperandLayout::Fixed(1)),            // 0x17
+    ("dload", OperandLayout::Fixed(1)),            // 0x18
+    ("aload", OperandLayout::Fixed(1)),            // 0x19
+    ("iload_0", OperandLayout::Fixed(0)),          // 0x1a
+    ("iload_1", OperandLayout::Fixed(0)),          // 0x1b
+    ("iload_2", OperandLayout::Fixed(0)),          // 0x1c
+    ("iload_3", OperandLayout::Fixed(0)),          // 0x1d
+    ("lload_0", OperandLayout::Fixed(0)),          // 0x1e
+    ("lload_1", OperandLayout::Fixed(0)),          // 0x1f
+    ("lload_2", OperandLayout::Fixed(0)),          // 0x20
+    ("lload_3", OperandLayout::Fixed(0)),          // 0x21
+    ("fload_0", OperandLayout::Fixed(0)),          // 0x22
+    ("fload_1", OperandLayout::Fixed(0)),          // 0x23
+    ("fload_2", OperandLayout::Fixed(0)),          // 0x24
+    ("fload_3", OperandLayout::Fixed(0)),          // 0x25
+    ("dload_0", OperandLayout::Fixed(0)),          // 0x26
+    ("dload_1", OperandLayout::Fixed(0)),          // 0x27
+    ("dload_2", OperandLayout::Fixed(0)),          // 0x28
+    ("dload_3", OperandLayout::Fixed(0)),          // 0x29
+    ("aload_0", OperandLayout::Fixed(0)),          // 0x2a
+    ("aload_1", OperandLayout::Fixed(0)),          // 0x2b
+    ("aload_2", OperandLayout::Fixed(0)),          // 0x2c
+    ("aload_3", OperandLayout::Fixed(0)),          // 0x2d
+    ("iaload", OperandLayout::Fixed(0)),           // 0x2e
+    ("laload", OperandLayout::Fixed(0)),           // 0x2f
+    ("faload", OperandLayout::Fixed(0)),           // 0x30
+    ("daload", OperandLayout::Fixed(0)),           // 0x31
+    ("aaload", OperandLayout::Fixed(0)),           // 0x32
+    ("baload", OperandLayout::Fixed(0)),           // 0x33
+    ("caload", OperandLayout::Fixed(0)),           // 0x34
+    ("saload", OperandLayout::Fixed(0)),           // 0x35
+    ("istore", OperandLayout::Fixed(1)),           // 0x36
+    ("lstore", OperandLayout::Fixed(1)),           // 0x37
+    ("fstore", OperandLayout::Fixed(1)),           // 0x38
+    ("dstore", OperandLayout::Fixed(1)),           // 0x39
+    ("astore", OperandLayout::Fixed(1)),           // 0x3a
+    ("istore_0", OperandLayout::Fixed(0)),         // 0x3b
+    ("istore_1", OperandLayout::Fixed(0)),         // 0x3c
+    ("istore_2", OperandLayout::Fixed(0)),         // 0x3d
+    ("istore_3", OperandLayout::Fixed(0)),         // 0x3e
+    ("lstore_0", OperandLayout::Fixed(0)),         // 0x3f
+    ("lstore_1", OperandLayout::Fixed(0)),         // 0x40
+    ("lstore_2", OperandLayout::Fixed(0)),         // 0x41
+    ("lstore_3", OperandLayout::Fixed(0)),         // 0x42
+    ("fstore_0", OperandLayout::Fixed(0)),         // 0x43
+    ("fstore_1", OperandLayout::Fixed(0)),         // 0x44
+    ("fstore_2", OperandLayout::Fixed(0)),         // 0x45
+    ("fstore_3", OperandLayout::Fixed(0)),         // 0x46
+    ("dstore_0", OperandLayout::Fixed(0)),         // 0x47
+    ("dstore_1", OperandLayout::Fixed(0)),         // 0x48
+    ("dstore_2", OperandLayout::Fixed(0)),         // 0x49
+    ("dstore_3", OperandLayout::Fixed(0)),         // 0x4a
+    ("astore_0", OperandLayout::Fixed(0)),         // 0x4b
+    ("astore_1", OperandLayout::Fixed(0)),         // 0x4c
+    ("astore_2", OperandLayout::Fixed(0)),         // 0x4d
+    ("astore_3", OperandLayout::Fixed(0)),         // 0x4e
+    ("iastore", OperandLayout::Fixed(0)),          // 0x4f
+    ("lastore", OperandLayout::Fixed(0)),          // 0x50
+    ("fastore", OperandLayout::Fixed(0)),          // 0x51
+    ("dastore", OperandLayout::Fixed(0)),          // 0x52
+    ("aastore", OperandLayout::Fixed(0)),          // 0x53
+    ("bastore", OperandLayout::Fixed(0)),          // 0x54
+    ("castore", OperandLayout::Fixed(0)),          // 0x55
+    ("sastore", OperandLayout::Fixed(0)),          // 0x56
+    ("pop", OperandLayout::Fixed(0)),              // 0x57
+    ("pop2", OperandLayout::Fixed(0)),             // 0x58
+    ("dup", OperandLayout::Fixed(0)),              // 0x59
+    ("dup_x1", OperandLayout::Fixed(0)),           // 0x5a
+    ("dup_x2", OperandLayout::Fixed(0)),           // 0x5b
+    ("dup2", OperandLayout::Fixed(0)),             // 0x5c
+    ("dup2_x1", OperandLayout::Fixed(0)),          // 0x5d
+    ("dup2_x2", OperandLayout::Fixed(0)),          // 0x5e
+    ("swap", OperandLayout::Fixed(0)),             // 0x5f
+    ("iadd", OperandLayout::Fixed(0)),             // 0x60
+    ("ladd", OperandLayout::Fixed(0)),             // 0x61
+    ("fadd", OperandLayout::Fixed(0)),             // 0x62
+    ("dadd", OperandLayout::Fixed(0)),             // 0x63
+    ("isub", OperandLayout::Fixed(0)),             // 0x64
+    ("lsub", OperandLayout::Fixed(0)),             // 0x65
+    ("fsub", OperandLayout::Fixed(0)),             // 0x66
+    ("dsub", OperandLayout::Fixed(0)),             // 0x67
+    ("imul", OperandLayout::Fixed(0)),             // 0x68
+    ("lmul", OperandLayout::Fixed(0)),             // 0x69
+    ("fmul", OperandLayout::Fixed(0)),             // 0x6a
+    ("dmul", OperandLayout::Fixed(0)),             // 0x6b
+    ("idiv", OperandLayout::Fixed(0)),             // 0x6c
+    ("ldiv", OperandLayout::Fixed(0)),             // 0x6d
+    ("fdiv", OperandLayout::Fixed(0)),             // 0x6e
+    ("ddiv", OperandLayout::Fixed(0)),             // 0x6f
+    ("irem", OperandLayout::Fixed(0)),             // 0x70
+    ("lrem", OperandLayout::Fixed(0)),             // 0x71
+    ("frem", OperandLayout::Fixed(0)),             // 0x72
+    ("drem", OperandLayout::Fixed(0)),             // 0x73
+    ("ineg", OperandLayout::Fixed(0)),             // 0x74
+    ("lneg", OperandLayout::Fixed(0)),             // 0x75
+    ("fneg", OperandLayout::Fixed(0)),             // 0x76
+    ("dneg", OperandLayout::Fixed(0)),             // 0x77
+    ("ishl", OperandLayout::Fixed(0)),             // 0x78
+    ("lshl", OperandLayout::Fixed(0)),             // 0x79
+    ("ishr", OperandLayout::Fixed(0)),             // 0x7a
+    ("lshr", OperandLayout::Fixed(0)),             // 0x7b
+    ("iushr", OperandLayout::Fixed(0)),            // 0x7c
+    ("lushr", OperandLayout::Fixed(0)),            // 0x7d
+    ("iand", OperandLayout::Fixed(0)),             // 0x7e
+    ("land", OperandLayout::Fixed(0)),             // 0x7f
+    ("ior", OperandLayout::Fixed(0)),              // 0x80
+    ("lor", OperandLayout::Fixed(0)),              // 0x81
+    ("ixor", OperandLayout::Fixed(0)),             // 0x82
+    ("lxor", OperandLayout::Fixed(0)),             // 0x83
+    ("iinc", OperandLayout::Fixed(2)),             // 0x84
+    ("i2l", OperandLayout::Fixed(0)),              // 0x85
+    ("i2f", OperandLayout::Fixed(0)),              // 0x86
+    ("i2d", OperandLayout::Fixed(0)),              // 0x87
+    ("l2i", OperandLayout::Fixed(0)),              // 0x88
+    ("l2f", OperandLayout::Fixed(0)),              // 0x89
+    ("l2d", OperandLayout::Fixed(0)),              // 0x8a
+    ("f2i", OperandLayout::Fixed(0)),              // 0x8b
+    ("f2l", OperandLayout::Fixed(0)),              // 0x8c
+    ("f2d", OperandLayout::Fixed(0)),              // 0x8d
+    ("d2i", OperandLayout::Fixed(0)),              // 0x8e
+    ("d2l", OperandLayout::Fixed(0)),              // 0x8f
+    ("d2f", OperandLayout::Fixed(0)),              // 0x90
+    ("i2b", OperandLayout::Fixed(0)),              // 0x91
+    ("i2c", OperandLayout::Fixed(0)),              // 0x92
+    ("i2s", OperandLayout::Fixed(0)),              // 0x93
+    ("lcmp", OperandLayout::Fixed(0)),             // 0x94
+    ("fcmpl", OperandLayout::Fixed(0)),            // 0x95
+    ("fcmpg", OperandLayout::Fixed(0)),            // 0x96
+    ("dcmpl", OperandLayout::Fixed(0)),            // 0x97
+    ("dcmpg", OperandLayout::Fixed(0)),            // 0x98
+    ("ifeq", OperandLayout::Fixed(2)),             // 0x99
+    ("ifne", OperandLayout::Fixed(2)),             // 0x9a
+    ("iflt", OperandLayout::Fixed(2)),             // 0x9b
+    ("ifge", OperandLayout::Fixed(2)),             // 0x9c
+    ("ifgt", OperandLayout::Fixed(2)),             // 0x9d
+    ("ifle", OperandLayout::Fixed(2)),             // 0x9e
+    ("if_icmpeq", OperandLayout::Fixed(2)),        // 0x9f
+    ("if_icmpne", OperandLayout::Fixed(2)),        // 0xa0
+    ("if_icmplt", OperandLayout::Fixed(2)),        // 0xa1
+    ("if_icmpge", OperandLayout::Fixed(2)),        // 0xa2
+    ("if_icmpgt", OperandLayout::Fixed(2)),        // 0xa3
+    ("if_icmple", OperandLayout::Fixed(2)),        // 0xa4
+    ("if_acmpeq", OperandLayout::Fixed(2)),        // 0xa5
+    ("if_acmpne", OperandLayout::Fixed(2)),        // 0xa6
+    ("goto", OperandLayout::Fixed(2)),             // 0xa7
+    ("jsr", OperandLayout::Fixed(2)),              // 0xa8
+    ("ret", OperandLayout::Fixed(1)),              // 0xa9
+    ("tableswitch", OperandLayout::TableSwitch),   // 0xaa
+    ("lookupswitch", OperandLayout::LookupSwitch), // 0xab
+    ("ireturn", OperandLayout::Fixed(0)),          // 0xac
+    ("lreturn", OperandLayout::Fixed(0)),          // 0xad
+    ("freturn", OperandLayout::Fixed(0)),          // 0xae
+    ("dreturn", OperandLayout::Fixed(0)),          // 0xaf
+    ("areturn", OperandLayout::Fixed(0)),          // 0xb0
+    ("return", OperandLayout::Fixed(0)),           // 0xb1
+    ("getstatic", OperandLayout::Fixed(2)),        // 0xb2
+    ("putstatic", OperandLayout::Fixed(2)),        // 0xb3
+    ("getfield", OperandLayout::Fixed(2)),         // 0xb4
+    ("putfield", OperandLayout::Fixed(2)),         // 0xb5
+    ("invokevirtual", OperandLayout::Fixed(2)),    // 0xb6
+    ("invokespecial", OperandLayout::Fixed(2)),    // 0xb7
+    ("invokestatic", OperandLayout::Fixed(2)),     // 0xb8
+    ("invokeinterface", OperandLayout::Fixed(4)),  // 0xb9
+    ("invokedynamic", OperandLayout::Fixed(4)),    // 0xba
+    ("new", OperandLayout::Fixed(2)),              // 0xbb
+    ("newarray", OperandLayout::Fixed(1)),         // 0xbc
+    ("anewarray", OperandLayout::Fixed(2)),        // 0xbd
+    ("arraylength", OperandLayout::Fixed(0)),      // 0xbe
+    ("athrow", OperandLayout::Fixed(0)),           // 0xbf
+    ("checkcast", OperandLayout::Fixed(2)),        // 0xc0
+    ("instanceof", OperandLayout::Fixed(2)),       // 0xc1
+    ("monitorenter", OperandLayout::Fixed(0)),     // 0xc2
+    ("monitorexit", OperandLayout::Fixed(0)),      // 0xc3
+    ("wide", OperandLayout::Wide),                 // 0xc4
+    ("multianewarray", OperandLayout::Fixed(3)),   // 0xc5
+    ("ifnull", OperandLayout::Fixed(2)),           // 0xc6
+    ("ifnonnull", OperandLayout::Fixed(2)),        // 0xc7
+    ("goto_w", OperandLayout::Fixed(4)),           // 0xc8
+    ("jsr_w", OperandLayout::Fixed(4)),            // 0xc9
+];
+
+/// Fetch the mnemonic of an opcode, returns `None` for reserved and undefined opcodes
+pub fn mnemonic(opcode: u8) -> Option<&'static str> {
+    OPCODES
+        .get(usize::from(opcode))
+        .map(|(mnemonic, _)| *mnemonic)
+}
+
+/// Fetch the operand layout of an opcode, returns `None` for reserved and undefined opcodes
+pub fn operand_bytes(opcode: u8) -> Option<OperandLayout> {
+    OPCODES.get(usize::from(opcode)).map(|(_, layout)| *layout)
+}
+
+/// Fetch the opcode with the specified mnemonic, returns `None` if there is no such instruction
+pub fn opcode(mnemonic: &str) -> Option<u8> {
+    OPCODES
+        .iter()
+        .position(|(candidate, _)| *candidate == mnemonic)
+        .map(|index| index as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mnemonic, opcode, operand_bytes, OperandLayout};
+
+    #[test]
+    fn test_mnemonic() {
+        assert_eq!(mnemonic(0x00), Some("nop"));
+        assert_eq!(mnemonic(0x2a), Some("aload_0"));
+        assert_eq!(mnemonic(0xb6), Some("invokevirtual"));
+        assert_eq!(mnemonic(0xc9), Some("jsr_w"));
+        assert_eq!(mnemonic(0xca), None, "breakpoint is reserved");
+        assert_eq!(mnemonic(0xfe), None, "impdep1 is reserved");
+    }
+
+    #[test]
+    fn test_operand_bytes() {
+        assert_eq!(operand_bytes(0xb1), Some(OperandLayout::Fixed(0)));
+        assert_eq!(operand_bytes(0x10), Some(OperandLayout::Fixed(1)));
+        assert_eq!(operand_bytes(0x84), Some(OperandLayout::Fixed(2)));
+        assert_eq!(operand_bytes(0xc5), Some(OperandLayout::Fixed(3)));
+        assert_eq!(operand_bytes(0xb9), Some(OperandLayout::Fixed(4)));
+        assert_eq!(operand_bytes(0xaa), Some(OperandLayout::TableSwitch));
+        assert_eq!(operand_bytes(0xc4), Some(OperandLayout::Wide));
+        assert_eq!(operand_bytes(0xff), None);
+    }
+
+    #[test]
+    fn test_opcode_round_trip() {
+        for value in 0..=0xc9 {
+            assert_eq!(opcode(mnemonic(value).unwrap()), Some(value));
+        }
+
+        assert_eq!(opcode("impdep1"), None);
+    }
+}
@@ -0,0 +1,447 @@
+//! Structures shared by the annotation attributes
+//!
+//! Element values may contain arrays of element values and nested annotations, which in turn
+//! contain element values of their own. The nesting depth is limited while parsing, so a crafted
+//! class file cannot nest element values deep enough to overflow the stack.
+//!
+//! Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.16
+
+use crate::byte_reader::ByteReader;
+
+use super::ClassFileError;
+
+/// A single annotation, e.g. `@Retention(RetentionPolicy.RUNTIME)`
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.16
+#[derive(Debug, PartialEq)]
+pub struct Annotation {
+    /// Index into the constant pool pointing to the field descriptor of the annotation interface
+    pub type_index: u16,
+
+    /// Explicitly specified elements of the annotation, elements left at their default value are
+    /// not included
+    pub element_value_pairs: Vec<ElementValuePair>,
+}
+
+/// A single element of an annotation and its value
+#[derive(Debug, PartialEq)]
+pub struct ElementValuePair {
+    /// Index into the constant pool pointing to the name of the element
+    pub element_name_index: u16,
+
+    /// Value of the element
+    pub value: ElementValue,
+}
+
+/// Value of an annotation element, or the default value of an annotation interface element
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.16.1
+#[derive(Debug, PartialEq)]
+pub enum ElementValue {
+    /// Primitive or string constant, the tag is one of `B`, `C`, `D`, `F`, `I`, `J`, `S`, `Z`, or
+    /// `s` and determines the type of constant pool entry the index points to
+    Const { tag: u8, const_value_index: u16 },
+
+    /// Enum constant, both indices point to UTF-8 entries
+    EnumConst {
+        type_name_index: u16,
+        const_name_index: u16,
+    },
+
+    /// Class literal, the index points to a UTF-8 entry holding a return descriptor
+    Class { class_info_index: u16 },
+
+    /// Nested annotation
+    Annotation(Annotation),
+
+    /// Array of element values
+    Array(Vec<ElementValue>),
+}
+
+/// A single type annotation, e.g. `@NonNull` in `List<@NonNull String>`
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.20
+#[derive(Debug, PartialEq)]
+pub struct TypeAnnotation {
+    /// Kind of target the annotation appears on, see table 4.7.20-A and 4.7.20-B
+    pub target_type: u8,
+
+    /// Which type in a declaration or expression is annotated
+    pub target_info: TargetInfo,
+
+    /// Which part of the annotated type is annotated, e.g. a type argument
+    pub target_path: Vec<TypePathEntry>,
+
+    /// The annotation itself
+    pub annotation: Annotation,
+}
+
+/// Precise location of a type annotation, determined by the annotation's target type
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.20.1
+#[derive(Debug, PartialEq)]
+pub enum TargetInfo {
+    /// Type parameter of a generic class, interface, method, or constructor
+    TypeParameter { type_parameter_index: u8 },
+
+    /// Type in the `extends` or `implements` clause, 65535 refers to the superclass
+    Supertype { supertype_index: u16 },
+
+    /// Bound of a type parameter
+    TypeParameterBound {
+        type_parameter_index: u8,
+        bound_index: u8,
+    },
+
+    /// Type in a field or record component declaration, the return type of a method, or the
+    /// receiver type of a method
+    Empty,
+
+    /// Type in a formal parameter declaration
+    FormalParameter { formal_parameter_index: u8 },
+
+    /// Type in the `throws` clause, indexing into the exceptions attribute
+    Throws { throws_type_index: u16 },
+
+    /// Type in a local variable declaration, including resource variables
+    LocalVar { table: Vec<LocalVarTargetEntry> },
+
+    /// Type in an exception parameter declaration, indexing into the exception table
+    Catch { exception_table_index: u16 },
+
+    /// Type in an `instanceof`, `new`, or method reference expression
+    Offset { offset: u16 },
+
+    /// Type argument in a cast, constructor invocation, or method invocation expression
+    TypeArgument {
+        offset: u16,
+        type_argument_index: u8,
+    },
+}
+
+/// Range of code in which a local variable with an annotated type has a value
+#[derive(Debug, PartialEq)]
+pub struct LocalVarTargetEntry {
+    /// Start of the range in the code array
+    pub start_pc: u16,
+
+    /// Length of the range in the code array
+    pub length: u16,
+
+    /// Index of the local variable in the local variable array of the current frame
+    pub index: u16,
+}
+
+/// Single step on the path to the annotated part of a type
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.20.2
+#[derive(Debug, PartialEq)]
+pub struct TypePathEntry {
+    /// Kind of step, deeper in an array type, a nested type, a wildcard bound, or a type argument
+    pub type_path_kind: u8,
+
+    /// Which type argument is stepped into, zero for every other kind of step
+    pub type_argument_index: u8,
+}
+
+impl Annotation {
+    /// Read an annotation from a class file binary blob
+    ///
+    /// The depth is the nesting level of the annotation's element values, where the element
+    /// values of an annotation in an attribute are at depth one. Parsing fails once the depth
+    /// exceeds the maximum depth.
+    pub fn read(
+        reader: &mut ByteReader,
+        depth: u16,
+        max_depth: u16,
+    ) -> Result<Self, ClassFileError> {
+        let type_index = reader.read_u16()?;
+        let num_element_value_pairs = reader.read_count(5)?;
+
+        let mut element_value_pairs = vec![];
+        for _ in 0..num_element_value_pairs {
+            let element_name_index = reader.read_u16()?;
+            let value = ElementValue::read(reader, depth, max_depth)?;
+
+            element_value_pairs.push(ElementValuePair {
+                element_name_index,
+                value,
+            });
+        }
+
+        Ok(Self {
+            type_index,
+            element_value_pairs,
+        })
+    }
+
+    /// Read a table of annotations that starts with a u16 count, as used by the annotation
+    /// attributes
+    pub(super) fn read_table(
+        reader: &mut ByteReader,
+        max_depth: u16,
+    ) -> Result<Vec<Self>, ClassFileError> {
+        let num_annotations = reader.read_count(4)?;
+
+        let mut annotations = vec![];
+        for _ in 0..num_annotations {
+            annotations.push(Self::read(reader, 1, max_depth)?);
+        }
+
+        Ok(annotations)
+    }
+}
+
+impl ElementValue {
+    /// Read an element value from a class file binary blob
+    ///
+    /// Arrays and nested annotations read their element values one level deeper, parsing fails
+    /// once the depth exceeds the maximum depth
+    pub fn read(
+        reader: &mut ByteReader,
+        depth: u16,
+        max_depth: u16,
+    ) -> Result<Self, ClassFileError> {
+        if depth > max_depth {
+            return Err(ClassFileError::AnnotationNestingTooDeep {
+                max_depth,
+                offset: reader.position(),
+            });
+        }
+
+        let offset = reader.position();
+        let tag = reader.read_u8()?;
+
+        let value = match tag {
+            b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's' => Self::Const {
+                tag,
+                const_value_index: reader.read_u16()?,
+            },
+            b'e' => Self::EnumConst {
+                type_name_index: reader.read_u16()?,
+                const_name_index: reader.read_u16()?,
+            },
+            b'c' => Self::Class {
+                class_info_index: reader.read_u16()?,
+            },
+            b'@' => Self::Annotation(Annotation::read(reader, depth + 1, max_depth)?),
+            b'[' => {
+                let num_values = reader.read_count(3)?;
+
+                let mut values = vec![];
+                for _ in 0..num_values {
+                    values.push(Self::read(reader, depth + 1, max_depth)?);
+                }
+
+                Self::Array(values)
+            }
+            _ => return Err(ClassFileError::UnknownElementValueTag { tag, offset }),
+        };
+
+        Ok(value)
+    }
+}
+
+impl TypeAnnotation {
+    /// Read a type annotation from a class file binary blob
+    ///
+    /// The element values of the annotation share their depth limit with regular annotations
+    pub fn read(reader: &mut ByteReader, max_depth: u16) -> Result<Self, ClassFileError> {
+        let offset = reader.position();
+        let target_type = reader.read_u8()?;
+
+        let target_info = match target_type {
+            0x00 | 0x01 => TargetInfo::TypeParameter {
+                type_parameter_index: reader.read_u8()?,
+            },
+            0x10 => TargetInfo::Supertype {
+                supertype_index: reader.read_u16()?,
+            },
+            0x11 | 0x12 => TargetInfo::TypeParameterBound {
+                type_parameter_index: reader.read_u8()?,
+                bound_index: reader.read_u8()?,
+            },
+            0x13..=0x15 => TargetInfo::Empty,
+            0x16 => TargetInfo::FormalParameter {
+                formal_parameter_index: reader.read_u8()?,
+            },
+            0x17 => TargetInfo::Throws {
+                throws_type_index: reader.read_u16()?,
+            },
+            0x40 | 0x41 => {
+                let table_length = reader.read_count(6)?;
+
+                let mut table = vec![];
+                for _ in 0..table_length {
+                    table.push(LocalVarTargetEntry {
+                        start_pc: reader.read_u16()?,
+                        length: reader.read_u16()?,
+                        index: reader.read_u16()?,
+                    });
+                }
+
+                TargetInfo::LocalVar { table }
+            }
+            0x42 => TargetInfo::Catch {
+                exception_table_index: reader.read_u16()?,
+            },
+            0x43..=0x46 => TargetInfo::Offset {
+                offset: reader.read_u16()?,
+            },
+            0x47..=0x4B => TargetInfo::TypeArgument {
+                offset: reader.read_u16()?,
+                type_argument_index: reader.read_u8()?,
+            },
+            _ => {
+                return Err(ClassFileError::UnknownTypeAnnotationTarget {
+                    target_type,
+                    offset,
+                })
+            }
+        };
+
+        let path_length = reader.read_u8()?;
+
+        let mut target_path = vec![];
+        for _ in 0..path_length {
+            target_path.push(TypePathEntry {
+                type_path_kind: reader.read_u8()?,
+                type_argument_index: reader.read_u8()?,
+            });
+        }
+
+        Ok(Self {
+            target_type,
+            target_info,
+            target_path,
+            annotation: Annotation::read(reader, 1, max_depth)?,
+        })
+    }
+
+    /// Read a table of type annotations that starts with a u16 count, as used by the type
+    /// annotation attributes
+    pub(super) fn read_table(
+        reader: &mut ByteReader,
+        max_depth: u16,
+    ) -> Result<Vec<Self>, ClassFileError> {
+        let num_annotations = reader.read_count(6)?;
+
+        let mut annotations = vec![];
+        for _ in 0..num_annotations {
+            annotations.push(Self::read(reader, max_depth)?);
+        }
+
+        Ok(annotations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Annotation, ElementValue, TargetInfo, TypeAnnotation};
+    use crate::byte_reader::ByteReader;
+    use crate::classfile::ClassFileError;
+
+    /// Build an annotation with a single element whose value is nested in `depth - 1` arrays
+    fn nested_arrays(depth: u16) -> Vec<u8> {
+        // type_index, num_element_value_pairs, element_name_index
+        let mut bytes = vec![0x00, 0x01, 0x00, 0x01, 0x00, 0x02];
+
+        for _ in 1..depth {
+            bytes.extend([b'[', 0x00, 0x01]);
+        }
+
+        bytes.extend([b'I', 0x00, 0x03]);
+        bytes
+    }
+
+    #[test]
+    fn test_element_values() {
+        // @A(x = {E.V, String.class, @B})
+        let bytes = [
+            0x00, 0x01, 0x00, 0x01, 0x00, 0x02, b'[', 0x00, 0x03, b'e', 0x00, 0x03, 0x00, 0x04,
+            b'c', 0x00, 0x05, b'@', 0x00, 0x06, 0x00, 0x00,
+        ];
+        let annotation = Annotation::read(&mut ByteReader::from_bytes(&bytes), 1, 64).unwrap();
+
+        assert_eq!(annotation.type_index, 1);
+        assert_eq!(annotation.element_value_pairs[0].element_name_index, 2);
+        assert_eq!(
+            annotation.element_value_pairs[0].value,
+            ElementValue::Array(vec![
+                ElementValue::EnumConst {
+                    type_name_index: 3,
+                    const_name_index: 4,
+                },
+                ElementValue::Class {
+                    class_info_index: 5
+                },
+                ElementValue::Annotation(Annotation {
+                    type_index: 6,
+                    element_value_pairs: vec![],
+                }),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let bytes = nested_arrays(3);
+        assert!(
+            Annotation::read(&mut ByteReader::from_bytes(&bytes), 1, 3).is_ok(),
+            "Nesting up to the maximum depth should be accepted"
+        );
+        assert_eq!(
+            Annotation::read(&mut ByteReader::from_bytes(&bytes), 1, 2).err(),
+            Some(ClassFileError::AnnotationNestingTooDeep {
+                max_depth: 2,
+                offset: 12,
+            }),
+            "Nesting beyond the maximum depth should be rejected"
+        );
+
+        // Deep enough to overflow the stack if the depth was not limited
+        let bytes = nested_arrays(u16::MAX);
+        assert!(matches!(
+            Annotation::read(&mut ByteReader::from_bytes(&bytes), 1, 64),
+            Err(ClassFileError::AnnotationNestingTooDeep { max_depth: 64, .. })
+        ));
+    }
+
+    #[test]
+    fn test_unknown_element_value_tag() {
+        let bytes = [0x00, 0x01, 0x00, 0x01, 0x00, 0x02, b'x', 0x00, 0x03];
+
+        assert_eq!(
+            Annotation::read(&mut ByteReader::from_bytes(&bytes), 1, 64).err(),
+            Some(ClassFileError::UnknownElementValueTag {
+                tag: b'x',
+                offset: 6,
+            })
+        );
+    }
+
+    #[test]
+    fn test_type_annotation() {
+        // Type argument of a formal parameter, i.e. `List<@A String> parameter`
+        let bytes = [0x16, 0x00, 0x01, 0x03, 0x00, 0x00, 0x07, 0x00, 0x00];
+        let annotation = TypeAnnotation::read(&mut ByteReader::from_bytes(&bytes), 64).unwrap();
+
+        assert_eq!(
+            annotation.target_info,
+            TargetInfo::FormalParameter {
+                formal_parameter_index: 0
+            }
+        );
+        assert_eq!(annotation.target_path.len(), 1);
+        assert_eq!(annotation.target_path[0].type_path_kind, 3);
+        assert_eq!(annotation.annotation.type_index, 7);
+
+        assert_eq!(
+            TypeAnnotation::read(&mut ByteReader::from_bytes(&[0x20]), 64).err(),
+            Some(ClassFileError::UnknownTypeAnnotationTarget {
+                target_type: 0x20,
+                offset: 0,
+            })
+        );
+    }
+}
@@ -3,14 +3,14 @@
 //! Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7
 
 use std::any::Any;
+use std::fmt;
 
-use crate::{
-    byte_reader::ByteReader,
-    utils::{to_u16, to_u32},
-};
+use crate::byte_reader::ByteReader;
 use crate::flags::{Flags, MethodParameterAccessFlags, ModuleExportsFlags, ModuleFlags, ModuleOpensFlags, ModuleRequiresFlags, NestedClassAccessFlags};
 
-use super::ConstantPoolContainer;
+use super::class_file::{DEFAULT_MAX_ANNOTATION_DEPTH, DEFAULT_MAX_ATTRIBUTE_DEPTH};
+use super::resolve::utf8_at;
+use super::{Annotation, ClassFileError, ConstantPoolContainer, ElementValue, TypeAnnotation};
 
 /// Base trait to store specialised attributes
 trait Attribute {
@@ -20,7 +20,7 @@ trait Attribute {
 
 /// Attribute types
 // TODO: remove debug directive
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum AttributeType {
     /// See [§4.7.2](https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.2)
     ConstantValue,
@@ -111,6 +111,58 @@ pub enum AttributeType {
 
     /// See [§4.7.31](https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.31)
     PermittedSubclasses,
+
+    /// Attribute not defined by the specification, such as vendor-specific attributes
+    ///
+    /// See [§4.7.1](https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.1)
+    Unknown { name: String },
+}
+
+impl AttributeType {
+    /// Fetch the name of the attribute as it appears in the constant pool, e.g. `StackMapTable`
+    ///
+    /// Unknown attributes keep the name they were read with
+    pub fn name(&self) -> &str {
+        match self {
+            Self::ConstantValue => "ConstantValue",
+            Self::Code => "Code",
+            Self::StackMapTable => "StackMapTable",
+            Self::Exceptions => "Exceptions",
+            Self::InnerClasses => "InnerClasses",
+            Self::EnclosingMethod => "EnclosingMethod",
+            Self::Synthetic => "Synthetic",
+            Self::Signature => "Signature",
+            Self::SourceFile => "SourceFile",
+            Self::SourceDebugExtension => "SourceDebugExtension",
+            Self::LineNumberTable => "LineNumberTable",
+            Self::LocalVariableTable => "LocalVariableTable",
+            Self::LocalVariableTypeTable => "LocalVariableTypeTable",
+            Self::Deprecated => "Deprecated",
+            Self::RuntimeVisibleAnnotations => "RuntimeVisibleAnnotations",
+            Self::RuntimeInvisibleAnnotations => "RuntimeInvisibleAnnotations",
+            Self::RuntimeVisibleParameterAnnotations => "RuntimeVisibleParameterAnnotations",
+            Self::RuntimeInvisibleParameterAnnotations => "RuntimeInvisibleParameterAnnotations",
+            Self::RuntimeVisibleTypeAnnotations => "RuntimeVisibleTypeAnnotations",
+            Self::RuntimeInvisibleTypeAnnotations => "RuntimeInvisibleTypeAnnotations",
+            Self::AnnotationDefault => "AnnotationDefault",
+            Self::BootstrapMethods => "BootstrapMethods",
+            Self::MethodParameters => "MethodParameters",
+            Self::Module => "Module",
+            Self::ModulePackages => "ModulePackages",
+            Self::ModuleMainClass => "ModuleMainClass",
+            Self::NestHost => "NestHost",
+            Self::NestMembers => "NestMembers",
+            Self::Record => "Record",
+            Self::PermittedSubclasses => "PermittedSubclasses",
+            Self::Unknown { name } => name,
+        }
+    }
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
 }
 
 /// Represents an attribute
@@ -124,22 +176,32 @@ pub struct AttributeInfo {
 
 impl AttributeInfo {
     /// Create a new attribute from a class file binary blob
-    pub fn new(reader: &mut ByteReader, constant_pool: &ConstantPoolContainer) -> Self {
-        let attribute_name_index = to_u16(&reader.read_n_bytes(2));
-        let attribute_length = to_u32(&reader.read_n_bytes(4));
-        let name = constant_pool
-            .get(&attribute_name_index)
-            .expect(&format!(
-                "Unable to read the attribute's name from the constant pool at index {}",
-                attribute_name_index,
-            ))
-            .try_cast_into_utf8()
-            .expect("Attribute's name index does not refer to a valid UTF-8 constant pool entry")
-            .string
-            .as_str();
+    ///
+    /// Some attributes, such as the code attribute, contain attributes of their own. The depth is the
+    /// nesting level of this attribute, where attributes of classes, fields, and methods are at
+    /// depth one. Parsing fails once the depth exceeds the maximum depth, which protects against
+    /// maliciously crafted class files that nest attributes deep enough to overflow the stack.
+    /// Element values of annotations are limited by the maximum annotation depth in the same way.
+    pub fn new(
+        reader: &mut ByteReader,
+        constant_pool: &ConstantPoolContainer,
+        depth: u16,
+        max_depth: u16,
+        max_annotation_depth: u16,
+    ) -> Result<Self, ClassFileError> {
+        if depth > max_depth {
+            return Err(ClassFileError::AttributeNestingTooDeep {
+                max_depth,
+                offset: reader.position(),
+            });
+        }
+
+        let attribute_name_index = reader.read_u16()?;
+        let attribute_length = reader.read_u32()?;
+        let name = utf8_at(constant_pool, attribute_name_index)?;
 
         // Using the constant pool's UTF-8 string, match against all known attribute types
-        match name {
+        let attribute = match name {
             "ConstantValue" => {
                 let attribute_type = AttributeType::ConstantValue;
                 Self {
@@ -148,7 +210,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "Code" => {
@@ -160,7 +222,10 @@ impl AttributeInfo {
                         attribute_name_index,
                         attribute_length,
                         constant_pool,
-                    )),
+                        depth,
+                        max_depth,
+                        max_annotation_depth,
+                    )?),
                 }
             }
             "StackMapTable" => {
@@ -171,7 +236,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "Exceptions" => {
@@ -182,7 +247,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "InnerClasses" => {
@@ -193,7 +258,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "EnclosingMethod" => {
@@ -204,7 +269,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "Synthetic" => {
@@ -225,7 +290,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "SourceFile" => {
@@ -236,7 +301,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "SourceDebugExtension" => {
@@ -247,7 +312,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "LineNumberTable" => {
@@ -258,7 +323,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "LocalVariableTable" => {
@@ -269,7 +334,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "LocalVariableTypeTable" => {
@@ -280,7 +345,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "Deprecated" => {
@@ -301,7 +366,8 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                        max_annotation_depth,
+                    )?),
                 }
             }
             "RuntimeInvisibleAnnotations" => {
@@ -312,7 +378,8 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                        max_annotation_depth,
+                    )?),
                 }
             }
             "RuntimeVisibleParameterAnnotations" => {
@@ -323,7 +390,8 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                        max_annotation_depth,
+                    )?),
                 }
             }
             "RuntimeInvisibleParameterAnnotations" => {
@@ -334,7 +402,8 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                        max_annotation_depth,
+                    )?),
                 }
             }
             "RuntimeVisibleTypeAnnotations" => {
@@ -345,7 +414,8 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                        max_annotation_depth,
+                    )?),
                 }
             }
             "RuntimeInvisibleTypeAnnotations" => {
@@ -356,7 +426,8 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                        max_annotation_depth,
+                    )?),
                 }
             }
             "AnnotationDefault" => {
@@ -367,7 +438,8 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                        max_annotation_depth,
+                    )?),
                 }
             }
             "BootstrapMethods" => {
@@ -378,7 +450,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "MethodParameters" => {
@@ -389,7 +461,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "Module" => {
@@ -400,7 +472,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "ModulePackages" => {
@@ -411,7 +483,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "ModuleMainClass" => {
@@ -422,7 +494,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "NestHost" => {
@@ -433,7 +505,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "NestMembers" => {
@@ -444,7 +516,7 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
             "Record" => {
@@ -456,7 +528,10 @@ impl AttributeInfo {
                         attribute_name_index,
                         attribute_length,
                         constant_pool,
-                    )),
+                        depth,
+                        max_depth,
+                        max_annotation_depth,
+                    )?),
                 }
             }
             "PermittedSubclasses" => {
@@ -467,11 +542,145 @@ impl AttributeInfo {
                         reader,
                         attribute_name_index,
                         attribute_length,
-                    )),
+                    )?),
                 }
             }
-            _ => panic!("Unknown attribute: \"{}\"", name),
-        }
+            // Unknown attributes must be silently ignored
+            // See: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.1
+            _ => {
+                let attribute_type = AttributeType::Unknown {
+                    name: name.to_string(),
+                };
+                Self {
+                    attribute_type,
+                    data: Box::new(Self::read_data_as_unknown(
+                        reader,
+                        attribute_name_index,
+                        attribute_length,
+                    )?),
+                }
+            }
+        };
+
+        Ok(attribute)
+    }
+
+    /// Parse a single attribute from a binary blob that starts with the attribute's header
+    ///
+    /// The attribute is treated as an attribute of a class, field, or method, and the default
+    /// maximum attribute and annotation depths apply to anything nested inside of it. Bytes
+    /// following the attribute are ignored.
+    pub fn from_bytes(
+        bytes: &[u8],
+        constant_pool: &ConstantPoolContainer,
+    ) -> Result<Self, ClassFileError> {
+        Self::new(
+            &mut ByteReader::from_bytes(bytes),
+            constant_pool,
+            1,
+            DEFAULT_MAX_ATTRIBUTE_DEPTH,
+            DEFAULT_MAX_ANNOTATION_DEPTH,
+        )
+    }
+
+    /// Cast to a constant value attribute
+    pub fn try_cast_into_constant_value(&self) -> Option<&AttributeConstantValue> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeConstantValue>()
+    }
+
+    /// Cast to a code attribute
+    pub fn try_cast_into_code(&self) -> Option<&AttributeCode> {
+        self.data.as_concrete_type().downcast_ref::<AttributeCode>()
+    }
+
+    /// Cast to a signature attribute
+    pub fn try_cast_into_signature(&self) -> Option<&AttributeSignature> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeSignature>()
+    }
+
+    /// Cast to a module attribute
+    pub fn try_cast_into_module(&self) -> Option<&AttributeModule> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeModule>()
+    }
+
+    /// Cast to an exceptions attribute
+    pub fn try_cast_into_exceptions(&self) -> Option<&AttributeExceptions> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeExceptions>()
+    }
+
+    /// Cast to a permitted subclasses attribute
+    pub fn try_cast_into_permitted_subclasses(&self) -> Option<&AttributePermittedSubclasses> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributePermittedSubclasses>()
+    }
+
+    /// Cast to a bootstrap methods attribute
+    pub fn try_cast_into_bootstrap_methods(&self) -> Option<&AttributeBootstrapMethods> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeBootstrapMethods>()
+    }
+
+    /// Cast to a line number table attribute
+    pub fn try_cast_into_line_number_table(&self) -> Option<&AttributeLineNumberTable> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeLineNumberTable>()
+    }
+
+    /// Cast to a stack map table attribute
+    pub fn try_cast_into_stack_map_table(&self) -> Option<&AttributeStackMapTable> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeStackMapTable>()
+    }
+
+    /// Cast to a local variable table attribute
+    pub fn try_cast_into_local_variable_table(&self) -> Option<&AttributeLocalVariableTable> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeLocalVariableTable>()
+    }
+
+    /// Cast to a local variable type table attribute
+    pub fn try_cast_into_local_variable_type_table(
+        &self,
+    ) -> Option<&AttributeLocalVariableTypeTable> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeLocalVariableTypeTable>()
+    }
+
+    /// Cast to a runtime visible annotations attribute
+    pub fn try_cast_into_runtime_visible_annotations(
+        &self,
+    ) -> Option<&AttributeRuntimeVisibleAnnotations> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeRuntimeVisibleAnnotations>()
+    }
+
+    /// Cast to an annotation default attribute
+    pub fn try_cast_into_annotation_default(&self) -> Option<&AttributeAnnotationDefault> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeAnnotationDefault>()
+    }
+
+    /// Cast to an attribute that is not defined by the specification
+    pub fn try_cast_into_unknown(&self) -> Option<&AttributeUnknown> {
+        self.data
+            .as_concrete_type()
+            .downcast_ref::<AttributeUnknown>()
     }
 
     /// Read the data blob as a constant value attribute
@@ -479,19 +688,23 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeConstantValue {
-        assert_eq!(
-            attribute_length, 2,
-            "Constant value attributes should have a length of 2"
-        );
+    ) -> Result<AttributeConstantValue, ClassFileError> {
+        if attribute_length != 2 {
+            return Err(ClassFileError::InvalidAttributeLength {
+                name: "ConstantValue",
+                length: attribute_length,
+                expected: 2,
+                offset: reader.position(),
+            });
+        }
 
-        let constantvalue_index = to_u16(&reader.read_n_bytes(2));
+        let constantvalue_index = reader.read_u16()?;
 
-        AttributeConstantValue {
+        Ok(AttributeConstantValue {
             attribute_name_index,
             attribute_length,
             constantvalue_index,
-        }
+        })
     }
 
     /// Read the data blob as a code attribute
@@ -500,20 +713,23 @@ impl AttributeInfo {
         attribute_name_index: u16,
         attribute_length: u32,
         constant_pool: &ConstantPoolContainer,
-    ) -> AttributeCode {
-        let max_stack = to_u16(&reader.read_n_bytes(2));
-        let max_locals = to_u16(&reader.read_n_bytes(2));
-        let code_length = to_u32(&reader.read_n_bytes(4));
+        depth: u16,
+        max_depth: u16,
+        max_annotation_depth: u16,
+    ) -> Result<AttributeCode, ClassFileError> {
+        let max_stack = reader.read_u16()?;
+        let max_locals = reader.read_u16()?;
+        let code_length = reader.read_u32()?;
 
-        let code = reader.read_n_bytes(code_length as usize);
-        let exception_table_length = to_u16(&reader.read_n_bytes(2));
+        let code = reader.read_u32_length(code_length)?;
+        let exception_table_length = reader.read_count(8)?;
 
         let mut exception_table = vec![];
         for _ in 0..exception_table_length {
-            let start_pc = to_u16(&reader.read_n_bytes(2));
-            let end_pc = to_u16(&reader.read_n_bytes(2));
-            let handler_pc = to_u16(&reader.read_n_bytes(2));
-            let catch_type = to_u16(&reader.read_n_bytes(2));
+            let start_pc = reader.read_u16()?;
+            let end_pc = reader.read_u16()?;
+            let handler_pc = reader.read_u16()?;
+            let catch_type = reader.read_u16()?;
 
             exception_table.push(ExceptionTableEntry {
                 start_pc,
@@ -523,14 +739,20 @@ impl AttributeInfo {
             });
         }
 
-        let attributes_count = to_u16(&reader.read_n_bytes(2));
+        let attributes_count = reader.read_count(6)?;
 
         let mut attributes = vec![];
         for _ in 0..attributes_count {
-            attributes.push(AttributeInfo::new(reader, constant_pool));
+            attributes.push(AttributeInfo::new(
+                reader,
+                constant_pool,
+                depth + 1,
+                max_depth,
+                max_annotation_depth,
+            )?);
         }
 
-        AttributeCode {
+        Ok(AttributeCode {
             attribute_name_index,
             attribute_length,
             max_stack,
@@ -538,7 +760,7 @@ impl AttributeInfo {
             code: code.to_vec(),
             exception_table,
             attributes,
-        }
+        })
     }
 
     /// Read the data blob as a stack map table attribute
@@ -546,12 +768,11 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeStackMapTable {
-        todo!();
+    ) -> Result<AttributeStackMapTable, ClassFileError> {
         // TODO: implement attribute: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.4
         // Simply skip this attribute's data
-        reader.read_n_bytes(std::convert::TryInto::try_into(attribute_length as u32).unwrap());
-        AttributeStackMapTable {}
+        reader.skip_u32_length(attribute_length)?;
+        Ok(AttributeStackMapTable {})
     }
 
     /// Read the data blob as an exceptions attribute
@@ -559,20 +780,20 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeExceptions {
-        let number_of_exceptions = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeExceptions, ClassFileError> {
+        let number_of_exceptions = reader.read_count(2)?;
 
         let mut exception_index_table = vec![];
         for _ in 0..number_of_exceptions {
-            exception_index_table.push(to_u16(&reader.read_n_bytes(2)));
+            exception_index_table.push(reader.read_u16()?);
         }
 
-        AttributeExceptions {
+        Ok(AttributeExceptions {
             attribute_name_index,
             attribute_length,
             number_of_exceptions,
             exception_index_table,
-        }
+        })
     }
 
     /// Read the data blob as an inner classes attribute
@@ -580,15 +801,15 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeInnerClasses {
-        let number_of_classes = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeInnerClasses, ClassFileError> {
+        let number_of_classes = reader.read_count(8)?;
         let mut classes = vec![];
 
         for _ in 0..number_of_classes {
-            let inner_class_info_index = to_u16(&reader.read_n_bytes(2));
-            let outer_class_info_index = to_u16(&reader.read_n_bytes(2));
-            let inner_name_index = to_u16(&reader.read_n_bytes(2));
-            let inner_class_access_flags = NestedClassAccessFlags::from_u16(to_u16(&reader.read_n_bytes(2)));
+            let inner_class_info_index = reader.read_u16()?;
+            let outer_class_info_index = reader.read_u16()?;
+            let inner_name_index = reader.read_u16()?;
+            let inner_class_access_flags = NestedClassAccessFlags::from_u16(reader.read_u16()?);
 
             classes.push(InnerClassEntry {
                 inner_class_info_index,
@@ -598,11 +819,11 @@ impl AttributeInfo {
             })
         }
 
-        AttributeInnerClasses {
+        Ok(AttributeInnerClasses {
             attribute_name_index,
             attribute_length,
             classes,
-        }
+        })
     }
 
     /// Read the data blob as an enclosing method attribute
@@ -610,16 +831,16 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeEnclosingMethod {
-        let class_index = to_u16(&reader.read_n_bytes(2));
-        let method_index = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeEnclosingMethod, ClassFileError> {
+        let class_index = reader.read_u16()?;
+        let method_index = reader.read_u16()?;
 
-        AttributeEnclosingMethod {
+        Ok(AttributeEnclosingMethod {
             attribute_name_index,
             attribute_length,
             class_index,
             method_index,
-        }
+        })
     }
 
     /// Read the data blob as a synthetic attribute
@@ -638,14 +859,14 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeSignature {
-        let signature_index = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeSignature, ClassFileError> {
+        let signature_index = reader.read_u16()?;
 
-        AttributeSignature {
+        Ok(AttributeSignature {
             attribute_name_index,
             attribute_length,
             signature_index,
-        }
+        })
     }
 
     /// Read the data blob as a source file attribute
@@ -653,14 +874,14 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeSourceFile {
-        let sourcefile_index = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeSourceFile, ClassFileError> {
+        let sourcefile_index = reader.read_u16()?;
 
-        AttributeSourceFile {
+        Ok(AttributeSourceFile {
             attribute_name_index,
             attribute_length,
             sourcefile_index,
-        }
+        })
     }
 
     /// Read the data blob as a source debug extension attribute
@@ -668,14 +889,14 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeSourceDebugExtension {
-        let debug_extension = reader.read_n_bytes(attribute_length as usize);
+    ) -> Result<AttributeSourceDebugExtension, ClassFileError> {
+        let debug_extension = reader.read_u32_length(attribute_length)?;
 
-        AttributeSourceDebugExtension {
+        Ok(AttributeSourceDebugExtension {
             attribute_name_index,
             attribute_length,
             debug_extension,
-        }
+        })
     }
 
     /// Read the data blob as a line number table attribute
@@ -683,13 +904,13 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeLineNumberTable {
-        let line_number_table_length = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeLineNumberTable, ClassFileError> {
+        let line_number_table_length = reader.read_count(4)?;
 
         let mut line_number_table = vec![];
         for _ in 0..line_number_table_length {
-            let start_pc = to_u16(&reader.read_n_bytes(2));
-            let line_number = to_u16(&reader.read_n_bytes(2));
+            let start_pc = reader.read_u16()?;
+            let line_number = reader.read_u16()?;
 
             line_number_table.push(LineNumberTableEntry {
                 start_pc,
@@ -697,11 +918,11 @@ impl AttributeInfo {
             });
         }
 
-        AttributeLineNumberTable {
+        Ok(AttributeLineNumberTable {
             attribute_name_index,
             attribute_length,
             line_number_table,
-        }
+        })
     }
 
     /// Read the data blob as a local variable table attribute
@@ -709,15 +930,15 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeLocalVariableTable {
+    ) -> Result<AttributeLocalVariableTable, ClassFileError> {
         let mut local_variable_table = vec![];
-        let local_variable_table_length = to_u16(&reader.read_n_bytes(2));
+        let local_variable_table_length = reader.read_count(10)?;
         for _ in 0..local_variable_table_length {
-            let start_pc = to_u16(&reader.read_n_bytes(2));
-            let length = to_u16(&reader.read_n_bytes(2));
-            let name_index = to_u16(&reader.read_n_bytes(2));
-            let descriptor_index = to_u16(&reader.read_n_bytes(2));
-            let index = to_u16(&reader.read_n_bytes(2));
+            let start_pc = reader.read_u16()?;
+            let length = reader.read_u16()?;
+            let name_index = reader.read_u16()?;
+            let descriptor_index = reader.read_u16()?;
+            let index = reader.read_u16()?;
 
             local_variable_table.push(LocalVariableTableEntry {
                 start_pc,
@@ -728,11 +949,11 @@ impl AttributeInfo {
             });
         }
 
-        AttributeLocalVariableTable {
+        Ok(AttributeLocalVariableTable {
             attribute_name_index,
             attribute_length,
             local_variable_table,
-        }
+        })
     }
 
     /// Read the data blob as a local variable type table attribute
@@ -740,15 +961,15 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeLocalVariableTypeTable {
+    ) -> Result<AttributeLocalVariableTypeTable, ClassFileError> {
         let mut local_variable_type_table = vec![];
-        let local_variable_type_table_length = to_u16(&reader.read_n_bytes(2));
+        let local_variable_type_table_length = reader.read_count(10)?;
         for _ in 0..local_variable_type_table_length {
-            let start_pc = to_u16(&reader.read_n_bytes(2));
-            let length = to_u16(&reader.read_n_bytes(2));
-            let name_index = to_u16(&reader.read_n_bytes(2));
-            let signature_index = to_u16(&reader.read_n_bytes(2));
-            let index = to_u16(&reader.read_n_bytes(2));
+            let start_pc = reader.read_u16()?;
+            let length = reader.read_u16()?;
+            let name_index = reader.read_u16()?;
+            let signature_index = reader.read_u16()?;
+            let index = reader.read_u16()?;
 
             local_variable_type_table.push(LocalVariableTypeTableEntry {
                 start_pc,
@@ -759,11 +980,11 @@ impl AttributeInfo {
             });
         }
 
-        AttributeLocalVariableTypeTable {
+        Ok(AttributeLocalVariableTypeTable {
             attribute_name_index,
             attribute_length,
             local_variable_type_table,
-        }
+        })
     }
 
     /// Read the data blob as a deprecated attribute
@@ -782,12 +1003,15 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeRuntimeVisibleAnnotations {
-        todo!();
-        // TODO: implement attribute: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.16
-        // Simply skip this attribute's data
-        reader.read_n_bytes(std::convert::TryInto::try_into(attribute_length as u32).unwrap());
-        AttributeRuntimeVisibleAnnotations {}
+        max_annotation_depth: u16,
+    ) -> Result<AttributeRuntimeVisibleAnnotations, ClassFileError> {
+        let annotations = Annotation::read_table(reader, max_annotation_depth)?;
+
+        Ok(AttributeRuntimeVisibleAnnotations {
+            attribute_name_index,
+            attribute_length,
+            annotations,
+        })
     }
 
     /// Read the data blob as a runtime invisible annotations attribute
@@ -795,12 +1019,15 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeRuntimeInvisibleAnnotations {
-        todo!();
-        // TODO: implement attribute: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.17
-        // Simply skip this attribute's data
-        reader.read_n_bytes(std::convert::TryInto::try_into(attribute_length as u32).unwrap());
-        AttributeRuntimeInvisibleAnnotations {}
+        max_annotation_depth: u16,
+    ) -> Result<AttributeRuntimeInvisibleAnnotations, ClassFileError> {
+        let annotations = Annotation::read_table(reader, max_annotation_depth)?;
+
+        Ok(AttributeRuntimeInvisibleAnnotations {
+            attribute_name_index,
+            attribute_length,
+            annotations,
+        })
     }
 
     /// Read the data blob as a runtime visible parameter annotations attribute
@@ -808,12 +1035,15 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeRuntimeVisibleParameterAnnotations {
-        todo!();
-        // TODO: implement attribute: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.18
-        // Simply skip this attribute's data
-        reader.read_n_bytes(std::convert::TryInto::try_into(attribute_length as u32).unwrap());
-        AttributeRuntimeVisibleParameterAnnotations {}
+        max_annotation_depth: u16,
+    ) -> Result<AttributeRuntimeVisibleParameterAnnotations, ClassFileError> {
+        let parameter_annotations = Self::read_parameter_annotations(reader, max_annotation_depth)?;
+
+        Ok(AttributeRuntimeVisibleParameterAnnotations {
+            attribute_name_index,
+            attribute_length,
+            parameter_annotations,
+        })
     }
 
     /// Read the data blob as a runtime invisible parameter annotations attribute
@@ -821,12 +1051,15 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeRuntimeInvisibleParameterAnnotations {
-        todo!();
-        // TODO: implement attribute: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.19
-        // Simply skip this attribute's data
-        reader.read_n_bytes(std::convert::TryInto::try_into(attribute_length as u32).unwrap());
-        AttributeRuntimeInvisibleParameterAnnotations {}
+        max_annotation_depth: u16,
+    ) -> Result<AttributeRuntimeInvisibleParameterAnnotations, ClassFileError> {
+        let parameter_annotations = Self::read_parameter_annotations(reader, max_annotation_depth)?;
+
+        Ok(AttributeRuntimeInvisibleParameterAnnotations {
+            attribute_name_index,
+            attribute_length,
+            parameter_annotations,
+        })
     }
 
     /// Read the data blob as a runtime visible type annotations attribute
@@ -834,12 +1067,15 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeRuntimeVisibleTypeAnnotations {
-        todo!();
-        // TODO: implement attribute: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.20
-        // Simply skip this attribute's data
-        reader.read_n_bytes(std::convert::TryInto::try_into(attribute_length as u32).unwrap());
-        AttributeRuntimeVisibleTypeAnnotations {}
+        max_annotation_depth: u16,
+    ) -> Result<AttributeRuntimeVisibleTypeAnnotations, ClassFileError> {
+        let annotations = TypeAnnotation::read_table(reader, max_annotation_depth)?;
+
+        Ok(AttributeRuntimeVisibleTypeAnnotations {
+            attribute_name_index,
+            attribute_length,
+            annotations,
+        })
     }
 
     /// Read the data blob as a runtime invisible type annotations attribute
@@ -847,12 +1083,15 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeRuntimeInvisibleTypeAnnotations {
-        todo!();
-        // TODO: implement attribute: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.21
-        // Simply skip this attribute's data
-        reader.read_n_bytes(std::convert::TryInto::try_into(attribute_length as u32).unwrap());
-        AttributeRuntimeInvisibleTypeAnnotations {}
+        max_annotation_depth: u16,
+    ) -> Result<AttributeRuntimeInvisibleTypeAnnotations, ClassFileError> {
+        let annotations = TypeAnnotation::read_table(reader, max_annotation_depth)?;
+
+        Ok(AttributeRuntimeInvisibleTypeAnnotations {
+            attribute_name_index,
+            attribute_length,
+            annotations,
+        })
     }
 
     /// Read the data blob as an annotation default attribute
@@ -860,12 +1099,31 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeAnnotationDefault {
-        todo!();
-        // TODO: implement attribute: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.22
-        // Simply skip this attribute's data
-        reader.read_n_bytes(std::convert::TryInto::try_into(attribute_length as u32).unwrap());
-        AttributeAnnotationDefault {}
+        max_annotation_depth: u16,
+    ) -> Result<AttributeAnnotationDefault, ClassFileError> {
+        let default_value = ElementValue::read(reader, 1, max_annotation_depth)?;
+
+        Ok(AttributeAnnotationDefault {
+            attribute_name_index,
+            attribute_length,
+            default_value,
+        })
+    }
+
+    /// Read the annotations of each formal parameter, as used by both parameter annotations
+    /// attributes
+    fn read_parameter_annotations(
+        reader: &mut ByteReader,
+        max_annotation_depth: u16,
+    ) -> Result<Vec<Vec<Annotation>>, ClassFileError> {
+        let num_parameters = reader.read_u8()?;
+
+        let mut parameter_annotations = vec![];
+        for _ in 0..num_parameters {
+            parameter_annotations.push(Annotation::read_table(reader, max_annotation_depth)?);
+        }
+
+        Ok(parameter_annotations)
     }
 
     /// Read the data blob as a bootstrap methods attribute
@@ -873,27 +1131,27 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeBootstrapMethods {
-        let num_bootstrap_methods = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeBootstrapMethods, ClassFileError> {
+        let num_bootstrap_methods = reader.read_count(4)?;
 
         let mut bootstrap_methods = vec![];
         for _ in 0..num_bootstrap_methods {
-            let bootstrap_method_ref = to_u16(&reader.read_n_bytes(2));
-            let num_bootstrap_arguments = to_u16(&reader.read_n_bytes(2));
+            let bootstrap_method_ref = reader.read_u16()?;
+            let num_bootstrap_arguments = reader.read_count(2)?;
 
             let mut bootstrap_arguments = vec![];
             for _ in 0..num_bootstrap_arguments {
-                bootstrap_arguments.push(to_u16(&reader.read_n_bytes(2)));
+                bootstrap_arguments.push(reader.read_u16()?);
             }
 
             bootstrap_methods.push(BootstrapMethodEntry { bootstrap_method_ref, bootstrap_arguments });
         }
 
-        AttributeBootstrapMethods {
+        Ok(AttributeBootstrapMethods {
             attribute_name_index,
             attribute_length,
             bootstrap_methods,
-        }
+        })
     }
 
     /// Read the data blob as a method parameters attribute
@@ -901,22 +1159,25 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeMethodParameters {
-        let parameters_count = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeMethodParameters, ClassFileError> {
+        let parameters_count = reader.read_u16()?;
         let mut parameters = vec![];
 
         for _ in 0..parameters_count {
-            let name_index = to_u16(&reader.read_n_bytes(2));
-            let access_flags = MethodParameterAccessFlags::from_u16(to_u16(&reader.read_n_bytes(2)));
+            let name_index = reader.read_u16()?;
+            let access_flags = MethodParameterAccessFlags::from_u16(reader.read_u16()?);
 
-            parameters.push(MethodParameterEntry { name_index, access_flags });
+            parameters.push(MethodParameterEntry {
+                name_index,
+                access_flags,
+            });
         }
 
-        AttributeMethodParameters {
+        Ok(AttributeMethodParameters {
             attribute_name_index,
             attribute_length,
             parameters,
-        }
+        })
     }
 
     /// Read the data blob as a module attribute
@@ -924,17 +1185,17 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeModule {
-        let module_name_index = to_u16(&reader.read_n_bytes(2));
-        let module_flags = ModuleFlags::from_u16(to_u16(&reader.read_n_bytes(2)));
-        let module_version_index = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeModule, ClassFileError> {
+        let module_name_index = reader.read_u16()?;
+        let module_flags = ModuleFlags::from_u16(reader.read_u16()?);
+        let module_version_index = reader.read_u16()?;
 
         let mut requires = vec![];
-        let requires_count = to_u16(&reader.read_n_bytes(2));
+        let requires_count = reader.read_count(6)?;
         for _ in 0..requires_count {
-            let requires_index = to_u16(&reader.read_n_bytes(2));
-            let requires_flags = ModuleRequiresFlags::from_u16(to_u16(&reader.read_n_bytes(2)));
-            let requires_version_index = to_u16(&reader.read_n_bytes(2));
+            let requires_index = reader.read_u16()?;
+            let requires_flags = ModuleRequiresFlags::from_u16(reader.read_u16()?);
+            let requires_version_index = reader.read_u16()?;
 
             requires.push(ModuleRequiresEntry {
                 requires_index,
@@ -944,15 +1205,15 @@ impl AttributeInfo {
         }
 
         let mut exports = vec![];
-        let exports_count = to_u16(&reader.read_n_bytes(2));
+        let exports_count = reader.read_count(6)?;
         for _ in 0..exports_count {
-            let exports_index = to_u16(&reader.read_n_bytes(2));
-            let exports_flags = ModuleExportsFlags::from_u16(to_u16(&reader.read_n_bytes(2)));
+            let exports_index = reader.read_u16()?;
+            let exports_flags = ModuleExportsFlags::from_u16(reader.read_u16()?);
 
             let mut exports_to_index = vec![];
-            let exports_to_count = to_u16(&reader.read_n_bytes(2));
+            let exports_to_count = reader.read_count(2)?;
             for _ in 0..exports_to_count {
-                exports_to_index.push(to_u16(&reader.read_n_bytes(2)));
+                exports_to_index.push(reader.read_u16()?);
             }
 
             exports.push(ModuleExportsEntry {
@@ -963,15 +1224,15 @@ impl AttributeInfo {
         }
 
         let mut opens = vec![];
-        let opens_count = to_u16(&reader.read_n_bytes(2));
+        let opens_count = reader.read_count(6)?;
         for _ in 0..opens_count {
-            let opens_index = to_u16(&reader.read_n_bytes(2));
-            let opens_flags = ModuleOpensFlags::from_u16(to_u16(&reader.read_n_bytes(2)));
+            let opens_index = reader.read_u16()?;
+            let opens_flags = ModuleOpensFlags::from_u16(reader.read_u16()?);
 
             let mut opens_to_index = vec![];
-            let opens_to_count = to_u16(&reader.read_n_bytes(2));
+            let opens_to_count = reader.read_count(2)?;
             for _ in 0..opens_to_count {
-                opens_to_index.push(to_u16(&reader.read_n_bytes(2)));
+                opens_to_index.push(reader.read_u16()?);
             }
 
             opens.push(ModuleOpensEntry {
@@ -982,30 +1243,29 @@ impl AttributeInfo {
         }
 
         let mut uses_index = vec![];
-        let uses_count = to_u16(&reader.read_n_bytes(2));
+        let uses_count = reader.read_count(2)?;
         for _ in 0..uses_count {
-            uses_index.push(to_u16(&reader.read_n_bytes(2)));
+            uses_index.push(reader.read_u16()?);
         }
 
         let mut provides = vec![];
-        let mut provides_count = to_u16(&reader.read_n_bytes(2));
+        let provides_count = reader.read_count(4)?;
         for _ in 0..provides_count {
-            let provides_index = to_u16(&reader.read_n_bytes(2));
+            let provides_index = reader.read_u16()?;
 
             let mut provides_with_index = vec![];
-            let mut provides_with_count = to_u16(&reader.read_n_bytes(2));
+            let provides_with_count = reader.read_count(2)?;
             for _ in 0..provides_with_count {
-                provides_with_index.push(to_u16(&reader.read_n_bytes(2)));
+                provides_with_index.push(reader.read_u16()?);
             }
 
             provides.push(ModuleProvidesEntry {
                 provides_index,
-                provides_with_count,
                 provides_with_index,
             });
         }
 
-        AttributeModule {
+        Ok(AttributeModule {
             attribute_name_index,
             attribute_length,
             module_name_index,
@@ -1016,7 +1276,7 @@ impl AttributeInfo {
             opens,
             uses_index,
             provides,
-        }
+        })
     }
 
     /// Read the data blob as a module packages attribute
@@ -1024,18 +1284,18 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeModulePackages {
+    ) -> Result<AttributeModulePackages, ClassFileError> {
         let mut package_index = vec![];
-        let package_count = to_u16(&reader.read_n_bytes(2));
+        let package_count = reader.read_count(2)?;
         for _ in 0..package_count {
-            package_index.push(to_u16(&reader.read_n_bytes(2)));
+            package_index.push(reader.read_u16()?);
         }
 
-        AttributeModulePackages {
+        Ok(AttributeModulePackages {
             attribute_name_index,
             attribute_length,
             package_index,
-        }
+        })
     }
 
     /// Read the data blob as a module main class attribute
@@ -1043,14 +1303,14 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeModuleMainClass {
-        let main_class_index = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeModuleMainClass, ClassFileError> {
+        let main_class_index = reader.read_u16()?;
 
-        AttributeModuleMainClass {
+        Ok(AttributeModuleMainClass {
             attribute_name_index,
             attribute_length,
             main_class_index,
-        }
+        })
     }
 
     /// Read the data blob as a nest host attribute
@@ -1058,14 +1318,14 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeNestHost {
-        let host_class_index = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<AttributeNestHost, ClassFileError> {
+        let host_class_index = reader.read_u16()?;
 
-        AttributeNestHost {
+        Ok(AttributeNestHost {
             attribute_name_index,
             attribute_length,
             host_class_index,
-        }
+        })
     }
 
     /// Read the data blob as a nest members attribute
@@ -1073,18 +1333,18 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributeNestMembers {
+    ) -> Result<AttributeNestMembers, ClassFileError> {
         let mut classes = vec![];
-        let number_of_classes = to_u16(&reader.read_n_bytes(2));
+        let number_of_classes = reader.read_count(2)?;
         for _ in 0..number_of_classes {
-            classes.push(to_u16(&reader.read_n_bytes(2)));
+            classes.push(reader.read_u16()?);
         }
 
-        AttributeNestMembers {
+        Ok(AttributeNestMembers {
             attribute_name_index,
             attribute_length,
             classes,
-        }
+        })
     }
 
     /// Read the data blob as a record attribute
@@ -1093,17 +1353,26 @@ impl AttributeInfo {
         attribute_name_index: u16,
         attribute_length: u32,
         constant_pool: &ConstantPoolContainer,
-    ) -> AttributeRecord {
+        depth: u16,
+        max_depth: u16,
+        max_annotation_depth: u16,
+    ) -> Result<AttributeRecord, ClassFileError> {
         let mut components = vec![];
-        let components_count = to_u16(&reader.read_n_bytes(2));
+        let components_count = reader.read_count(6)?;
         for _ in 0..components_count {
-            let name_index = to_u16(&reader.read_n_bytes(2));
-            let descriptor_index = to_u16(&reader.read_n_bytes(2));
+            let name_index = reader.read_u16()?;
+            let descriptor_index = reader.read_u16()?;
 
             let mut attributes = vec![];
-            let attributes_count = to_u16(&reader.read_n_bytes(2));
+            let attributes_count = reader.read_count(6)?;
             for _ in 0..attributes_count {
-                attributes.push(AttributeInfo::new(reader, constant_pool));
+                attributes.push(AttributeInfo::new(
+                    reader,
+                    constant_pool,
+                    depth + 1,
+                    max_depth,
+                    max_annotation_depth,
+                )?);
             }
 
             components.push(RecordComponentInfo {
@@ -1113,11 +1382,11 @@ impl AttributeInfo {
             });
         }
 
-        AttributeRecord {
+        Ok(AttributeRecord {
             attribute_name_index,
             attribute_length,
             components,
-        }
+        })
     }
 
     /// Read the data blob as a permitted subclasses attribute
@@ -1125,18 +1394,34 @@ impl AttributeInfo {
         reader: &mut ByteReader,
         attribute_name_index: u16,
         attribute_length: u32,
-    ) -> AttributePermittedSubclasses {
+    ) -> Result<AttributePermittedSubclasses, ClassFileError> {
         let mut classes = vec![];
-        let number_of_classes = to_u16(&reader.read_n_bytes(2));
+        let number_of_classes = reader.read_count(2)?;
         for _ in 0..number_of_classes {
-            classes.push(to_u16(&reader.read_n_bytes(2)));
+            classes.push(reader.read_u16()?);
         }
 
-        AttributePermittedSubclasses {
+        Ok(AttributePermittedSubclasses {
             attribute_name_index,
             attribute_length,
             classes,
-        }
+        })
+    }
+
+    /// Read the data blob of an attribute that is not defined by the specification
+    fn read_data_as_unknown(
+        reader: &mut ByteReader,
+        attribute_name_index: u16,
+        attribute_length: u32,
+    ) -> Result<AttributeUnknown, ClassFileError> {
+        // The data cannot be interpreted, but is kept around for tools that can
+        let info = reader.read_u32_length(attribute_length)?;
+
+        Ok(AttributeUnknown {
+            attribute_name_index,
+            attribute_length,
+            info,
+        })
     }
 }
 
@@ -1151,7 +1436,7 @@ pub struct AttributeConstantValue {
     attribute_length: u32,
 
     /// Index into the constant pool which gives the value represented by this attribute
-    constantvalue_index: u16,
+    pub constantvalue_index: u16,
 }
 
 impl Attribute for AttributeConstantValue {
@@ -1161,19 +1446,19 @@ impl Attribute for AttributeConstantValue {
 }
 
 /// Describes an exception handler in the code array
-struct ExceptionTableEntry {
+pub struct ExceptionTableEntry {
     /// Start of the range in the code array at which the exception handler is active
-    start_pc: u16,
+    pub start_pc: u16,
 
     /// End of the range in the code array at which the exception handler is active
-    end_pc: u16,
+    pub end_pc: u16,
 
     /// Indicates the start of the exception handler
-    handler_pc: u16,
+    pub handler_pc: u16,
 
     /// The entry in the constant pool at this index represents a class of exceptions that this exception handler is designated
     /// to catch
-    catch_type: u16,
+    pub catch_type: u16,
 }
 
 /// A code attribute contains the Java Virtual Machine instructions and auxilary information for a method, including an instance
@@ -1188,19 +1473,54 @@ pub struct AttributeCode {
     attribute_length: u32,
 
     /// Maximum depth of the operand stack of this method
-    max_stack: u16,
+    pub max_stack: u16,
 
     /// Maximum number of local variables in the local variable array allocated upon invocation of this method
-    max_locals: u16,
+    pub max_locals: u16,
 
     /// Java Virtual Machine code that implements this method
-    code: Vec<u8>,
+    pub code: Vec<u8>,
 
     /// Described exceptions handles in the code array
-    exception_table: Vec<ExceptionTableEntry>,
+    pub exception_table: Vec<ExceptionTableEntry>,
 
     /// Attributes associated with this code attribute
-    attributes: Vec<AttributeInfo>,
+    pub attributes: Vec<AttributeInfo>,
+}
+
+impl AttributeCode {
+    /// Fetch the first line number table of this method body, if it was compiled with line numbers
+    ///
+    /// Compilers may split the table over several attributes, see
+    /// `MethodInfo::source_line_for_offset` to look up a line in all of them
+    pub fn line_number_table(&self) -> Option<&AttributeLineNumberTable> {
+        self.attributes
+            .iter()
+            .find_map(AttributeInfo::try_cast_into_line_number_table)
+    }
+
+    /// Fetch the local variable table of this method body, if it was compiled with debug
+    /// information
+    pub fn local_variable_table(&self) -> Option<&AttributeLocalVariableTable> {
+        self.attributes
+            .iter()
+            .find_map(AttributeInfo::try_cast_into_local_variable_table)
+    }
+
+    /// Fetch the local variable type table of this method body, which describes the generic types
+    /// of its local variables
+    pub fn local_variable_type_table(&self) -> Option<&AttributeLocalVariableTypeTable> {
+        self.attributes
+            .iter()
+            .find_map(AttributeInfo::try_cast_into_local_variable_type_table)
+    }
+
+    /// Fetch the stack map table of this method body, which is present as soon as it branches
+    pub fn stack_map_table(&self) -> Option<&AttributeStackMapTable> {
+        self.attributes
+            .iter()
+            .find_map(AttributeInfo::try_cast_into_stack_map_table)
+    }
 }
 
 impl Attribute for AttributeCode {
@@ -1224,7 +1544,7 @@ pub struct AttributeExceptions {
     attribute_name_index: u16,
     attribute_length: u32,
     number_of_exceptions: u16,
-    exception_index_table: Vec<u16>,
+    pub exception_index_table: Vec<u16>,
 }
 
 impl Attribute for AttributeExceptions {
@@ -1293,7 +1613,7 @@ impl Attribute for AttributeSynthetic {
 pub struct AttributeSignature {
     attribute_name_index: u16,
     attribute_length: u32,
-    signature_index: u16,
+    pub signature_index: u16,
 }
 
 impl Attribute for AttributeSignature {
@@ -1333,12 +1653,12 @@ impl Attribute for AttributeSourceDebugExtension {
 }
 
 /// Represents an entry in the line number table in a line number table attribute
-struct LineNumberTableEntry {
+pub struct LineNumberTableEntry {
     /// Indicates the index into the code array at which the code for a new line in the original source file begins
-    start_pc: u16,
+    pub start_pc: u16,
 
     /// Gives the corresponding line number in the original source file
-    line_number: u16,
+    pub line_number: u16,
 }
 
 /// A line number table attribute may be used by debuggers to determine which part of the code array corresponds to a given
@@ -1348,7 +1668,20 @@ struct LineNumberTableEntry {
 pub struct AttributeLineNumberTable {
     attribute_name_index: u16,
     attribute_length: u32,
-    line_number_table: Vec<LineNumberTableEntry>,
+    pub line_number_table: Vec<LineNumberTableEntry>,
+}
+
+impl AttributeLineNumberTable {
+    /// Find the entry that covers a bytecode offset, which is the entry with the greatest start
+    /// offset that does not exceed the offset
+    ///
+    /// Entries are not required to be sorted, nor to start at offset zero
+    pub fn entry_for_offset(&self, offset: u16) -> Option<&LineNumberTableEntry> {
+        self.line_number_table
+            .iter()
+            .filter(|entry| entry.start_pc <= offset)
+            .max_by_key(|entry| entry.start_pc)
+    }
 }
 
 impl Attribute for AttributeLineNumberTable {
@@ -1360,12 +1693,12 @@ impl Attribute for AttributeLineNumberTable {
 /// Indicates a range of code array offsets within which a local variable has a value, and indicates
 /// the index into the local variable array of the current frame at which that local variable can be
 /// found
-struct LocalVariableTableEntry {
-    start_pc: u16,
-    length: u16,
-    name_index: u16,
-    descriptor_index: u16,
-    index: u16,
+pub struct LocalVariableTableEntry {
+    pub start_pc: u16,
+    pub length: u16,
+    pub name_index: u16,
+    pub descriptor_index: u16,
+    pub index: u16,
 }
 
 /// May be used by debuggers to determine the value of a given local variable during the execution
@@ -1375,7 +1708,7 @@ struct LocalVariableTableEntry {
 pub struct AttributeLocalVariableTable {
     attribute_name_index: u16,
     attribute_length: u32,
-    local_variable_table: Vec<LocalVariableTableEntry>,
+    pub local_variable_table: Vec<LocalVariableTableEntry>,
 }
 
 impl Attribute for AttributeLocalVariableTable {
@@ -1387,12 +1720,12 @@ impl Attribute for AttributeLocalVariableTable {
 /// Indicates a range of code array offsets within which a local variable has a value, and indicates
 /// the index into the local variable array of the current frame at which that local variable can be
 /// found
-struct LocalVariableTypeTableEntry {
-    start_pc: u16,
-    length: u16,
-    name_index: u16,
-    signature_index: u16,
-    index: u16,
+pub struct LocalVariableTypeTableEntry {
+    pub start_pc: u16,
+    pub length: u16,
+    pub name_index: u16,
+    pub signature_index: u16,
+    pub index: u16,
 }
 
 /// May be used by debuggers to determine the value of a given local variable during the execution
@@ -1402,7 +1735,7 @@ struct LocalVariableTypeTableEntry {
 pub struct AttributeLocalVariableTypeTable {
     attribute_name_index: u16,
     attribute_length: u32,
-    local_variable_type_table: Vec<LocalVariableTypeTableEntry>,
+    pub local_variable_type_table: Vec<LocalVariableTypeTableEntry>,
 }
 
 impl Attribute for AttributeLocalVariableTypeTable {
@@ -1425,7 +1758,14 @@ impl Attribute for AttributeDeprecated {
     }
 }
 
-pub struct AttributeRuntimeVisibleAnnotations {}
+/// Records the annotations that are visible to reflection at run time
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.16
+pub struct AttributeRuntimeVisibleAnnotations {
+    attribute_name_index: u16,
+    attribute_length: u32,
+    pub annotations: Vec<Annotation>,
+}
 
 impl Attribute for AttributeRuntimeVisibleAnnotations {
     fn as_concrete_type(&self) -> &dyn Any {
@@ -1433,7 +1773,14 @@ impl Attribute for AttributeRuntimeVisibleAnnotations {
     }
 }
 
-pub struct AttributeRuntimeInvisibleAnnotations {}
+/// Records the annotations that are retained in the class file, but not visible to reflection
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.17
+pub struct AttributeRuntimeInvisibleAnnotations {
+    attribute_name_index: u16,
+    attribute_length: u32,
+    pub annotations: Vec<Annotation>,
+}
 
 impl Attribute for AttributeRuntimeInvisibleAnnotations {
     fn as_concrete_type(&self) -> &dyn Any {
@@ -1441,7 +1788,15 @@ impl Attribute for AttributeRuntimeInvisibleAnnotations {
     }
 }
 
-pub struct AttributeRuntimeVisibleParameterAnnotations {}
+/// Records the annotations on the formal parameters of a method that are visible to reflection
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.18
+pub struct AttributeRuntimeVisibleParameterAnnotations {
+    attribute_name_index: u16,
+    attribute_length: u32,
+    /// Annotations of each formal parameter, in the order of the parameters
+    pub parameter_annotations: Vec<Vec<Annotation>>,
+}
 
 impl Attribute for AttributeRuntimeVisibleParameterAnnotations {
     fn as_concrete_type(&self) -> &dyn Any {
@@ -1449,7 +1804,16 @@ impl Attribute for AttributeRuntimeVisibleParameterAnnotations {
     }
 }
 
-pub struct AttributeRuntimeInvisibleParameterAnnotations {}
+/// Records the annotations on the formal parameters of a method that are not visible to
+/// reflection
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.19
+pub struct AttributeRuntimeInvisibleParameterAnnotations {
+    attribute_name_index: u16,
+    attribute_length: u32,
+    /// Annotations of each formal parameter, in the order of the parameters
+    pub parameter_annotations: Vec<Vec<Annotation>>,
+}
 
 impl Attribute for AttributeRuntimeInvisibleParameterAnnotations {
     fn as_concrete_type(&self) -> &dyn Any {
@@ -1457,7 +1821,14 @@ impl Attribute for AttributeRuntimeInvisibleParameterAnnotations {
     }
 }
 
-pub struct AttributeRuntimeVisibleTypeAnnotations {}
+/// Records the annotations on types that are visible to reflection at run time
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.20
+pub struct AttributeRuntimeVisibleTypeAnnotations {
+    attribute_name_index: u16,
+    attribute_length: u32,
+    pub annotations: Vec<TypeAnnotation>,
+}
 
 impl Attribute for AttributeRuntimeVisibleTypeAnnotations {
     fn as_concrete_type(&self) -> &dyn Any {
@@ -1465,7 +1836,14 @@ impl Attribute for AttributeRuntimeVisibleTypeAnnotations {
     }
 }
 
-pub struct AttributeRuntimeInvisibleTypeAnnotations {}
+/// Records the annotations on types that are not visible to reflection
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.21
+pub struct AttributeRuntimeInvisibleTypeAnnotations {
+    attribute_name_index: u16,
+    attribute_length: u32,
+    pub annotations: Vec<TypeAnnotation>,
+}
 
 impl Attribute for AttributeRuntimeInvisibleTypeAnnotations {
     fn as_concrete_type(&self) -> &dyn Any {
@@ -1473,7 +1851,14 @@ impl Attribute for AttributeRuntimeInvisibleTypeAnnotations {
     }
 }
 
-pub struct AttributeAnnotationDefault {}
+/// Records the default value of an element of an annotation interface
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.22
+pub struct AttributeAnnotationDefault {
+    attribute_name_index: u16,
+    attribute_length: u32,
+    pub default_value: ElementValue,
+}
 
 impl Attribute for AttributeAnnotationDefault {
     fn as_concrete_type(&self) -> &dyn Any {
@@ -1482,12 +1867,12 @@ impl Attribute for AttributeAnnotationDefault {
 }
 
 /// Represents a bootstrap method information entry
-struct BootstrapMethodEntry {
+pub struct BootstrapMethodEntry {
     /// Index into the constant pool pointing to a method handle information structure
-    bootstrap_method_ref: u16,
+    pub bootstrap_method_ref: u16,
 
     /// Indices into the constant pool that point to bootstrap method arguments
-    bootstrap_arguments: Vec<u16>,
+    pub bootstrap_arguments: Vec<u16>,
 }
 
 /// Records bootstrap methods used to produce dynamically-computed constants and dynamically-computed call sites
@@ -1495,7 +1880,7 @@ struct BootstrapMethodEntry {
 pub struct AttributeBootstrapMethods {
     attribute_name_index: u16,
     attribute_length: u32,
-    bootstrap_methods: Vec<BootstrapMethodEntry>,
+    pub bootstrap_methods: Vec<BootstrapMethodEntry>,
 }
 
 impl Attribute for AttributeBootstrapMethods {
@@ -1553,8 +1938,11 @@ struct ModuleOpensEntry {
 
 /// Represents a service implementation for a given service interface
 struct ModuleProvidesEntry {
+    /// Index into the constant pool that represents the service interface
     provides_index: u16,
-    provides_with_count: u16,
+
+    /// Indices into the constant pool that represent the service implementations, the number of
+    /// implementations is the length of this list
     provides_with_index: Vec<u16>,
 }
 
@@ -1565,7 +1953,10 @@ struct ModuleProvidesEntry {
 pub struct AttributeModule {
     attribute_name_index: u16,
     attribute_length: u32,
-    module_name_index: u16,
+
+    /// Index into the constant pool that represents the name of the module
+    pub module_name_index: u16,
+
     module_flags: Vec<ModuleFlags>,
     module_version_index: u16,
     requires: Vec<ModuleRequiresEntry>,
@@ -1678,7 +2069,7 @@ impl Attribute for AttributeRecord {
 pub struct AttributePermittedSubclasses {
     attribute_name_index: u16,
     attribute_length: u32,
-    classes: Vec<u16>,
+    pub classes: Vec<u16>,
 }
 
 impl Attribute for AttributePermittedSubclasses {
@@ -1686,3 +2077,245 @@ impl Attribute for AttributePermittedSubclasses {
         self
     }
 }
+
+/// Represents an attribute that is not defined by the specification
+///
+/// https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.1
+pub struct AttributeUnknown {
+    attribute_name_index: u16,
+    attribute_length: u32,
+    info: Vec<u8>,
+}
+
+impl AttributeUnknown {
+    /// Fetch the attribute's raw data, excluding the attribute name index and attribute length
+    pub fn info(&self) -> &[u8] {
+        &self.info
+    }
+}
+
+impl Attribute for AttributeUnknown {
+    fn as_concrete_type(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::byte_reader::ByteReader;
+    use crate::classfile::{ClassFileError, ConstantPoolContainer, ConstantPoolInfo, ElementValue};
+
+    use super::{AttributeInfo, AttributeType};
+
+    /// Create a constant pool that only contains the UTF-8 string at index 1
+    fn constant_pool_with_name(name: &str) -> ConstantPoolContainer {
+        let mut bytes = vec![1, 0, name.len() as u8];
+        bytes.extend_from_slice(name.as_bytes());
+
+        let mut constant_pool = ConstantPoolContainer::new();
+        let entry = ConstantPoolInfo::new(&mut ByteReader::from_bytes(&bytes), 1).unwrap();
+        constant_pool.insert(1, entry);
+        constant_pool
+    }
+
+    #[test]
+    fn test_module_with_multiple_provides() {
+        let constant_pool = constant_pool_with_name("Module");
+        let bytes = vec![
+            0x00, 0x01, // attribute_name_index
+            0x00, 0x00, 0x00, 0x22, // attribute_length
+            0x00, 0x02, // module_name_index
+            0x00, 0x20, // module_flags (ACC_OPEN)
+            0x00, 0x00, // module_version_index
+            0x00, 0x00, // requires_count
+            0x00, 0x00, // exports_count
+            0x00, 0x00, // opens_count
+            0x00, 0x00, // uses_count
+            0x00, 0x02, // provides_count
+            0x00, 0x03, // provides_index
+            0x00, 0x02, // provides_with_count
+            0x00, 0x04, 0x00, 0x05, // provides_with_index
+            0x00, 0x06, // provides_index
+            0x00, 0x03, // provides_with_count
+            0x00, 0x07, 0x00, 0x08, 0x00, 0x09, // provides_with_index
+        ];
+
+        let attribute = AttributeInfo::from_bytes(&bytes, &constant_pool).unwrap();
+        assert_eq!(attribute.attribute_type, AttributeType::Module);
+
+        let module = attribute
+            .try_cast_into_module()
+            .expect("Attribute should be a module attribute");
+
+        assert_eq!(module.provides.len(), 2, "Expected two provides entries");
+        assert_eq!(module.provides[0].provides_index, 3);
+        assert_eq!(module.provides[0].provides_with_index, vec![4, 5]);
+        assert_eq!(module.provides[1].provides_index, 6);
+        assert_eq!(module.provides[1].provides_with_index, vec![7, 8, 9]);
+    }
+
+    #[test]
+    fn test_unknown_attribute_keeps_raw_bytes() {
+        let constant_pool = constant_pool_with_name("ScalaSig");
+        let bytes = vec![
+            0x00, 0x01, // attribute_name_index
+            0x00, 0x00, 0x00, 0x03, // attribute_length
+            0x05, 0x00, 0x00, // info
+        ];
+
+        let mut reader = ByteReader::from_bytes(&bytes);
+        let attribute = AttributeInfo::new(&mut reader, &constant_pool, 1, 64, 64).unwrap();
+        assert_eq!(
+            attribute.attribute_type,
+            AttributeType::Unknown {
+                name: String::from("ScalaSig")
+            }
+        );
+
+        let unknown = attribute
+            .try_cast_into_unknown()
+            .expect("Attribute should be an unknown attribute");
+
+        assert_eq!(unknown.info(), &[0x05, 0x00, 0x00]);
+        assert_eq!(
+            reader.position(),
+            bytes.len(),
+            "The data should be consumed"
+        );
+    }
+
+    #[test]
+    fn test_attribute_type_names() {
+        assert_eq!(AttributeType::StackMapTable.name(), "StackMapTable");
+        assert_eq!(
+            AttributeType::RuntimeVisibleAnnotations.to_string(),
+            "RuntimeVisibleAnnotations"
+        );
+
+        let unknown = AttributeType::Unknown {
+            name: String::from("ScalaSig"),
+        };
+        assert_eq!(
+            unknown.to_string(),
+            "ScalaSig",
+            "Unknown attributes keep their name"
+        );
+
+        // The names are the ones the parser recognizes
+        for name in ["Signature", "SourceFile", "Deprecated", "NestHost"] {
+            let bytes = [0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01];
+            let attribute =
+                AttributeInfo::from_bytes(&bytes, &constant_pool_with_name(name)).unwrap();
+
+            assert!(!matches!(
+                attribute.attribute_type,
+                AttributeType::Unknown { .. }
+            ));
+            assert_eq!(attribute.attribute_type.name(), name);
+        }
+    }
+
+    #[test]
+    fn test_annotation_default() {
+        let constant_pool = constant_pool_with_name("AnnotationDefault");
+        let bytes = vec![
+            0x00, 0x01, // attribute_name_index
+            0x00, 0x00, 0x00, 0x06, // attribute_length
+            b'[', 0x00, 0x01, // array_value with one value
+            b's', 0x00, 0x02, // const_value_index
+        ];
+
+        let attribute = AttributeInfo::from_bytes(&bytes, &constant_pool).unwrap();
+        let default = attribute
+            .try_cast_into_annotation_default()
+            .expect("Attribute should be an annotation default attribute");
+
+        assert_eq!(
+            default.default_value,
+            ElementValue::Array(vec![ElementValue::Const {
+                tag: b's',
+                const_value_index: 2,
+            }])
+        );
+
+        let mut reader = ByteReader::from_bytes(&bytes);
+        assert!(
+            matches!(
+                AttributeInfo::new(&mut reader, &constant_pool, 1, 64, 1),
+                Err(ClassFileError::AnnotationNestingTooDeep { max_depth: 1, .. })
+            ),
+            "The annotation depth is limited separately from the attribute depth"
+        );
+    }
+
+    /// Build a code attribute without instructions that contains `depth - 1` nested code attributes
+    fn nested_code_attributes(depth: u16) -> Vec<u8> {
+        let mut bytes = vec![
+            0x00, 0x01, // attribute_name_index
+            0x00, 0x00, 0x00, 0x00, // attribute_length (not validated)
+            0x00, 0x00, // max_stack
+            0x00, 0x00, // max_locals
+            0x00, 0x00, 0x00, 0x00, // code_length
+            0x00, 0x00, // exception_table_length
+        ];
+
+        if depth > 1 {
+            bytes.extend_from_slice(&[0x00, 0x01]);
+            bytes.extend(nested_code_attributes(depth - 1));
+        } else {
+            bytes.extend_from_slice(&[0x00, 0x00]);
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn test_attribute_nesting_depth() {
+        let constant_pool = constant_pool_with_name("Code");
+        let bytes = nested_code_attributes(3);
+
+        let mut reader = ByteReader::from_bytes(&bytes);
+        assert!(
+            AttributeInfo::new(&mut reader, &constant_pool, 1, 3, 64).is_ok(),
+            "Nesting up to the maximum depth should be accepted"
+        );
+
+        let mut reader = ByteReader::from_bytes(&bytes);
+        assert_eq!(
+            AttributeInfo::new(&mut reader, &constant_pool, 1, 2, 64).err(),
+            Some(ClassFileError::AttributeNestingTooDeep {
+                max_depth: 2,
+                offset: 36,
+            }),
+            "Nesting beyond the maximum depth should be rejected"
+        );
+    }
+
+    #[test]
+    fn test_line_number_table_entry_for_offset() {
+        let constant_pool = constant_pool_with_name("LineNumberTable");
+        let bytes = vec![
+            0x00, 0x01, // attribute_name_index
+            0x00, 0x00, 0x00, 0x0A, // attribute_length
+            0x00, 0x02, // line_number_table_length
+            0x00, 0x0C, 0x00, 0x07, // start_pc 12, line 7
+            0x00, 0x04, 0x00, 0x05, // start_pc 4, line 5
+        ];
+
+        let attribute = AttributeInfo::from_bytes(&bytes, &constant_pool).unwrap();
+        let table = attribute
+            .try_cast_into_line_number_table()
+            .expect("Attribute should be a line number table attribute");
+        let line = |offset| {
+            table
+                .entry_for_offset(offset)
+                .map(|entry| entry.line_number)
+        };
+
+        assert_eq!(line(0), None, "Offsets before the first entry have no line");
+        assert_eq!(line(4), Some(5));
+        assert_eq!(line(11), Some(5));
+        assert_eq!(line(12), Some(7), "Entries do not have to be sorted");
+        assert_eq!(line(200), Some(7));
+    }
+}
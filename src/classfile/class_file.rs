@@ -3,17 +3,101 @@
 //! This module is used to add class format parsing functionality to Jadis
 //! Do note that the actual file IO is not handled by this module
 
+use std::collections::BTreeSet;
+use std::fmt;
+
 use crate::byte_reader::ByteReader;
-use crate::flags::{ClassAccessFlags, Flags};
-use crate::utils::{to_u16, to_u32};
+use crate::flags::{ClassAccessFlags, Flags, MethodAccessFlags};
 
-use super::{ConstantClassInfo, ConstantPoolContainer, ConstantPoolInfo, Tag};
-use super::AttributeInfo;
 use super::FieldInfo;
 use super::MethodInfo;
+use super::{parse_field_descriptor, parse_method_descriptor, FieldType};
+use super::{
+    resolve_class, resolve_optional_class, resolve_signature, resolve_utf8, ClassFileError,
+};
+use super::{
+    AttributeBootstrapMethods, AttributeInfo, AttributePermittedSubclasses, AttributeType,
+};
+use super::{ConstantClassInfo, ConstantPoolContainer, ConstantPoolInfo, Tag};
 
 const MAGIC_NUMBER: u32 = 0xCAFEBABE;
 
+/// Size of the magic number, the minor and major version, and the constant pool count
+///
+/// Any input shorter than this cannot possibly be a class file
+const MIN_CLASS_FILE_LENGTH: usize = 10;
+
+/// Offset of the major version in a class file, which follows the magic number and minor version
+const MAJOR_VERSION_OFFSET: usize = 6;
+
+/// Oldest major version accepted by default (JDK 1.1)
+const DEFAULT_MIN_MAJOR_VERSION: u16 = 45;
+
+/// Newest major version accepted by default (Java SE 21)
+const DEFAULT_MAX_MAJOR_VERSION: u16 = 65;
+
+/// Minor version of class files that depend on the preview features of their Java SE release
+const PREVIEW_MINOR_VERSION: u16 = 0xFFFF;
+
+/// Oldest major version that may depend on preview features (Java SE 12)
+const FIRST_PREVIEW_MAJOR_VERSION: u16 = 56;
+
+/// Maximum nesting level of attributes accepted by default
+pub(super) const DEFAULT_MAX_ATTRIBUTE_DEPTH: u16 = 64;
+
+/// Maximum nesting level of annotation element values accepted by default
+pub(super) const DEFAULT_MAX_ANNOTATION_DEPTH: u16 = 64;
+
+/// Data needed to parse a class file
+pub struct ClassFileConfig {
+    /// Oldest class file major version the parser accepts
+    min_major_version: u16,
+
+    /// Newest class file major version the parser accepts
+    max_major_version: u16,
+
+    /// Deepest nesting level of attributes the parser accepts, e.g. attributes of a code attribute
+    /// are nested two levels deep
+    max_attribute_depth: u16,
+
+    /// Deepest nesting level of annotation element values the parser accepts, e.g. the values of
+    /// an array element value are nested two levels deep
+    max_annotation_depth: u16,
+}
+
+/// Kind of type declared by a class file, derived from its access flags
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClassKind {
+    /// Ordinary class, possibly abstract
+    Class,
+
+    /// Interface that is not an annotation interface
+    Interface,
+
+    /// Annotation interface, declared as `@interface` in the Java programming language
+    Annotation,
+
+    /// Enum class
+    Enum,
+
+    /// Module declaration (`module-info.class`)
+    Module,
+}
+
+impl fmt::Display for ClassKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keyword = match self {
+            Self::Class => "class",
+            Self::Interface => "interface",
+            Self::Annotation => "@interface",
+            Self::Enum => "enum",
+            Self::Module => "module",
+        };
+
+        write!(f, "{}", keyword)
+    }
+}
+
 /// JVM class file representation
 pub struct ClassFile {
     /// Magic number - should always equal 0xCAFEBABE
@@ -25,12 +109,22 @@ pub struct ClassFile {
     /// Bytecode major version
     pub major_version: u16,
 
+    /// Number of entries in the constant pool plus one, as stored in the class file
+    ///
+    /// Long and double constants count as two entries, see `ClassFile::constant_pool_len` for the
+    /// number of entries that are actually stored in the constant pool
+    pub constant_pool_count: u16,
+
     /// Constant pool
     pub constant_pool: ConstantPoolContainer,
 
     /// Class access and property modifiers
     pub access_flags: Vec<ClassAccessFlags>,
 
+    /// Class access and property modifiers exactly as stored in the class file, including bits
+    /// that do not correspond to a known flag
+    pub access_flags_raw: u16,
+
     /// Represents the class defined by this class file
     pub this_class: ConstantClassInfo,
 
@@ -50,57 +144,321 @@ pub struct ClassFile {
     pub attributes: Vec<AttributeInfo>,
 }
 
+impl Default for ClassFileConfig {
+    fn default() -> Self {
+        Self {
+            min_major_version: DEFAULT_MIN_MAJOR_VERSION,
+            max_major_version: DEFAULT_MAX_MAJOR_VERSION,
+            max_attribute_depth: DEFAULT_MAX_ATTRIBUTE_DEPTH,
+            max_annotation_depth: DEFAULT_MAX_ANNOTATION_DEPTH,
+        }
+    }
+}
+
+impl ClassFileConfig {
+    /// Create a new class file configuration that accepts major versions 45 up to and including 65,
+    /// and attributes and annotation element values nested up to 64 levels deep
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reject class files with a major version older than the specified version
+    pub fn with_min_major_version(&mut self, min_major_version: u16) {
+        self.min_major_version = min_major_version;
+    }
+
+    /// Reject class files with a major version newer than the specified version
+    pub fn with_max_major_version(&mut self, max_major_version: u16) {
+        self.max_major_version = max_major_version;
+    }
+
+    /// Reject class files with attributes nested deeper than the specified depth
+    pub fn with_max_attribute_depth(&mut self, max_attribute_depth: u16) {
+        self.max_attribute_depth = max_attribute_depth;
+    }
+
+    /// Reject class files with annotation element values nested deeper than the specified depth,
+    /// this applies to both regular and type annotations
+    pub fn with_max_annotation_depth(&mut self, max_annotation_depth: u16) {
+        self.max_annotation_depth = max_annotation_depth;
+    }
+
+    /// Verify whether the specified major version falls within the accepted range
+    fn verify_major_version(&self, major_version: u16) -> Result<(), ClassFileError> {
+        if major_version < self.min_major_version || major_version > self.max_major_version {
+            return Err(ClassFileError::UnsupportedVersion {
+                major_version,
+                min_major_version: self.min_major_version,
+                max_major_version: self.max_major_version,
+                offset: MAJOR_VERSION_OFFSET,
+            });
+        }
+
+        Ok(())
+    }
+}
+
 impl ClassFile {
     /// Create a new class file structure from a class file binary blob
-    pub fn new(reader: &mut ByteReader) -> Self {
-        let magic = Self::read_magic_number(reader);
-        let minor_version = Self::read_u16(reader);
-        let major_version = Self::read_u16(reader);
-        let constant_pool = Self::read_constant_pool(reader);
-        let access_flags = Self::read_access_flags(reader);
-        let this_class = Self::read_this_class(reader, &constant_pool);
-        let super_class = Self::read_super_class(reader, &constant_pool);
-        let interfaces = Self::read_interfaces(reader, &constant_pool);
-        let fields = Self::read_fields(reader, &constant_pool);
-        let methods = Self::read_methods(reader, &constant_pool);
-        let attributes = Self::read_attributes(reader, &constant_pool);
+    ///
+    /// Parsing stops with an error as soon as the major version turns out to be unsupported, as
+    /// the layout of the remaining structures cannot be relied upon in that case
+    pub fn new(reader: &mut ByteReader, config: &ClassFileConfig) -> Result<Self, ClassFileError> {
+        let mut class = Self::parse_header_only(reader, config)?;
+        class.fields = Self::read_fields(reader, &class.constant_pool, config)?;
+        class.methods = Self::read_methods(reader, &class.constant_pool, config)?;
+        class.attributes = Self::read_attributes(reader, &class.constant_pool, config)?;
+
+        Ok(class)
+    }
 
-        Self {
+    /// Create a new class file structure from a class file binary blob, but stop parsing right
+    /// after the class' direct superinterfaces
+    ///
+    /// Only the version, constant pool, access flags, and class references are populated, the
+    /// fields, methods, and attributes are left empty. This is considerably faster for callers that
+    /// are only interested in the constant pool.
+    pub fn parse_header_only(
+        reader: &mut ByteReader,
+        config: &ClassFileConfig,
+    ) -> Result<Self, ClassFileError> {
+        if reader.len() < MIN_CLASS_FILE_LENGTH {
+            return Err(ClassFileError::TooShort {
+                length: reader.len(),
+                min_length: MIN_CLASS_FILE_LENGTH,
+            });
+        }
+
+        let magic = Self::read_magic_number(reader)?;
+        let minor_version = Self::read_u16(reader)?;
+        let major_version = Self::read_u16(reader)?;
+        config.verify_major_version(major_version)?;
+
+        let (constant_pool_count, constant_pool) = Self::read_constant_pool(reader)?;
+        let access_flags_raw = reader.read_u16()?;
+        let access_flags = ClassAccessFlags::from_u16(access_flags_raw);
+        let this_class = Self::read_this_class(reader, &constant_pool)?;
+        let super_class = Self::read_super_class(reader, &constant_pool)?;
+        let interfaces = Self::read_interfaces(reader, &constant_pool)?;
+
+        Ok(Self {
             magic,
             minor_version,
             major_version,
+            constant_pool_count,
             constant_pool,
             access_flags,
+            access_flags_raw,
             this_class,
             super_class,
             interfaces,
-            fields,
-            methods,
-            attributes,
+            fields: vec![],
+            methods: vec![],
+            attributes: vec![],
+        })
+    }
+
+    /// Number of entries stored in the constant pool
+    ///
+    /// Unlike `constant_pool_count`, this does not include the unusable index zero, nor the unusable
+    /// index that follows each long and double constant
+    pub fn constant_pool_len(&self) -> usize {
+        self.constant_pool.len()
+    }
+
+    /// Check whether this class file depends on preview features
+    ///
+    /// Such class files are compiled with `--enable-preview` and can only be loaded by the exact
+    /// Java SE release that compiled them, with preview features enabled
+    pub fn is_preview(&self) -> bool {
+        self.major_version >= FIRST_PREVIEW_MAJOR_VERSION
+            && self.minor_version == PREVIEW_MINOR_VERSION
+    }
+
+    /// Determine what kind of type this class file declares
+    pub fn kind(&self) -> ClassKind {
+        class_kind(&self.access_flags)
+    }
+
+    /// Collect every attribute of the specified type in this class
+    ///
+    /// This includes the attributes of the class itself, of its fields and methods, and the
+    /// attributes nested inside code attributes, such as line number tables
+    pub fn attributes_of_type(&self, attribute_type: &AttributeType) -> Vec<&AttributeInfo> {
+        let mut matches = vec![];
+
+        collect_attributes(&self.attributes, attribute_type, &mut matches);
+
+        for field in &self.fields {
+            collect_attributes(&field.attributes, attribute_type, &mut matches);
+        }
+
+        for method in &self.methods {
+            collect_attributes(&method.attributes, attribute_type, &mut matches);
         }
+
+        matches
+    }
+
+    /// Collect the dotted names of all classes this class file refers to, excluding itself
+    ///
+    /// Names come from the class entries in the constant pool and from the classes mentioned in
+    /// field and method descriptors, both those of this class' members and those of the members it
+    /// refers to. Array types contribute their element class. The result is sorted and contains no
+    /// duplicates.
+    pub fn referenced_classes(&self) -> Vec<String> {
+        let mut names = BTreeSet::new();
+        let mut descriptor_indices = vec![];
+
+        for entry in self.constant_pool.values() {
+            match entry.tag {
+                Tag::ConstantClass => {
+                    let name_index = entry.try_cast_into_class().unwrap().name_index;
+
+                    match resolve_utf8(&self.constant_pool, name_index) {
+                        Some(name) if name.starts_with('[') => {
+                            add_descriptor_classes(name, &mut names)
+                        }
+                        Some(name) => {
+                            names.insert(name.to_owned());
+                        }
+                        None => {}
+                    }
+                }
+                Tag::ConstantNameAndType => descriptor_indices.push(
+                    entry
+                        .try_cast_into_name_and_type()
+                        .unwrap()
+                        .descriptor_index,
+                ),
+                Tag::ConstantMethodType => descriptor_indices
+                    .push(entry.try_cast_into_method_type().unwrap().descriptor_index),
+                _ => {}
+            }
+        }
+
+        for index in descriptor_indices {
+            if let Some(descriptor) = resolve_utf8(&self.constant_pool, index) {
+                add_descriptor_classes(descriptor, &mut names);
+            }
+        }
+
+        for field in &self.fields {
+            add_type_classes(field.descriptor(&self.constant_pool), &mut names);
+        }
+
+        for method in &self.methods {
+            if let Some(descriptor) = method.descriptor(&self.constant_pool) {
+                add_type_classes(descriptor.types(), &mut names);
+            }
+        }
+
+        if let Some(this_class) = resolve_utf8(&self.constant_pool, self.this_class.name_index) {
+            names.remove(this_class);
+        }
+
+        names
+            .into_iter()
+            .map(|name| name.replace('/', "."))
+            .collect()
+    }
+
+    /// Collect the text of every string literal in the constant pool, in constant pool order
+    ///
+    /// These are the UTF-8 entries referenced by string constants, e.g. the operands of `ldc` and
+    /// the values of `static final String` fields. Other UTF-8 entries, such as names and
+    /// descriptors, are not string literals and are left out.
+    pub fn string_literals(&self) -> Vec<&str> {
+        self.constant_pool
+            .values()
+            .filter_map(|entry| entry.try_cast_into_string())
+            .filter_map(|string| resolve_utf8(&self.constant_pool, string.string_index))
+            .collect()
+    }
+
+    /// Find the entry point of this class, a `public static void main(String[])` method
+    ///
+    /// Returns `None` if the class cannot be launched, e.g. because it has no main method or
+    /// because its main method is not both public and static
+    pub fn main_method(&self) -> Option<&MethodInfo> {
+        self.methods.iter().find(|method| {
+            resolve_utf8(&self.constant_pool, method.name_index) == Some("main")
+                && resolve_utf8(&self.constant_pool, method.descriptor_index)
+                    == Some("([Ljava/lang/String;)V")
+                && method.access_flags.contains(&MethodAccessFlags::AccPublic)
+                && method.access_flags.contains(&MethodAccessFlags::AccStatic)
+        })
+    }
+
+    /// Fetch the bootstrap methods attribute of this class, if it has one
+    pub fn bootstrap_methods(&self) -> Option<&AttributeBootstrapMethods> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.attribute_type == AttributeType::BootstrapMethods)
+            .and_then(|attribute| attribute.try_cast_into_bootstrap_methods())
+    }
+
+    /// Fetch the permitted subclasses attribute of this class, only sealed classes have one
+    pub fn permitted_subclasses(&self) -> Option<&AttributePermittedSubclasses> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.attribute_type == AttributeType::PermittedSubclasses)
+            .and_then(|attribute| attribute.try_cast_into_permitted_subclasses())
+    }
+
+    /// Fetch the name of the module declared by this class file, e.g. `java.base`
+    ///
+    /// Only `module-info` class files declare a module
+    pub fn module_name(&self) -> Option<&str> {
+        let module = self
+            .attributes
+            .iter()
+            .find_map(AttributeInfo::try_cast_into_module)?;
+        let name_index = self
+            .constant_pool
+            .get(&module.module_name_index)?
+            .try_cast_into_module()?
+            .name_index;
+
+        resolve_utf8(&self.constant_pool, name_index)
+    }
+
+    /// Fetch the generic signature of this class, if it has one
+    pub fn signature(&self) -> Option<&str> {
+        resolve_signature(&self.attributes, &self.constant_pool)
     }
 
     /// Read the magic number (always 0xCAFEBABE)
-    fn read_magic_number(reader: &mut ByteReader) -> u32 {
-        let magic_number = to_u32(&reader.read_n_bytes(4));
+    fn read_magic_number(reader: &mut ByteReader) -> Result<u32, ClassFileError> {
+        let magic_number = reader.read_u32()?;
 
-        assert_eq!(
-            magic_number, MAGIC_NUMBER,
-            "Invalid class file - magic number did not equal {}",
-            MAGIC_NUMBER
-        );
+        if magic_number != MAGIC_NUMBER {
+            return Err(ClassFileError::InvalidMagic {
+                found: magic_number,
+            });
+        }
 
-        magic_number
+        Ok(magic_number)
     }
 
     /// Read a number (u16) from a binary blob
-    fn read_u16(reader: &mut ByteReader) -> u16 {
-        to_u16(&reader.read_n_bytes(2))
+    fn read_u16(reader: &mut ByteReader) -> Result<u16, ClassFileError> {
+        Ok(reader.read_u16()?)
     }
 
-    /// Read the entire constant pool
-    fn read_constant_pool(reader: &mut ByteReader) -> ConstantPoolContainer {
-        let constant_pool_count = to_u16(&reader.read_n_bytes(2));
+    /// Read the entire constant pool, returns the constant pool count alongside the constant pool
+    fn read_constant_pool(
+        reader: &mut ByteReader,
+    ) -> Result<(u16, ConstantPoolContainer), ClassFileError> {
+        // The count is one larger than the number of entries, each entry is at least three bytes
+        // large, and the rest of the class file more than makes up for the extra entry
+        let offset = reader.position();
+        let constant_pool_count = reader.read_count(3)?;
+
+        // A count of one is an empty pool, but zero does not describe any pool at all
+        if constant_pool_count == 0 {
+            return Err(ClassFileError::InvalidConstantPoolCount { offset });
+        }
         let mut constant_pool = ConstantPoolContainer::new();
 
         // Index into the constant pool
@@ -109,7 +467,7 @@ impl ClassFile {
 
         // Read the entire constant pool
         while index < constant_pool_count {
-            let info = ConstantPoolInfo::new(reader, index);
+            let info = ConstantPoolInfo::new(reader, index)?;
 
             // Long and double "occupy" two indices
             // See: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.4.5
@@ -122,128 +480,434 @@ impl ClassFile {
             constant_pool.insert(index, info);
 
             // Once the entry has been stored, the index can safely be updated to the next index
-            index += offset;
+            // A long or double in the very last slot would step past the largest index, saturating
+            // ends the loop instead of overflowing
+            index = index.saturating_add(offset);
         }
 
-        constant_pool
-    }
-
-    /// Read the class access and property modifiers
-    fn read_access_flags(reader: &mut ByteReader) -> Vec<ClassAccessFlags> {
-        let bitmask = to_u16(&reader.read_n_bytes(2));
-        ClassAccessFlags::from_u16(bitmask)
+        Ok((constant_pool_count, constant_pool))
     }
 
     /// Read information from the constant pool about the class represented by this class file
     fn read_this_class(
         reader: &mut ByteReader,
         constant_pool: &ConstantPoolContainer,
-    ) -> ConstantClassInfo {
-        let constant_pool_index = to_u16(&reader.read_n_bytes(2));
-
-        let constant_pool_entry = constant_pool.get(&constant_pool_index).expect(&format!(
-            "Unable to fetch entry from constant pool at index {}",
-            constant_pool_index
-        ));
+    ) -> Result<ConstantClassInfo, ClassFileError> {
+        let constant_pool_index = reader.read_u16()?;
 
-        match constant_pool_entry.try_cast_into_class() {
-            Some(class) => class.clone(),
-            None => panic!(
-                "Unable to fetch \"this class\" information from constant pool at index {}",
-                constant_pool_index
-            ),
+        // Every index is missing from an empty pool, which is a less helpful thing to report
+        if constant_pool.is_empty() {
+            return Err(ClassFileError::EmptyConstantPool);
         }
+
+        Ok(resolve_class(constant_pool, constant_pool_index)?.clone())
     }
 
     /// Read information from the constant pool about the direct super class of the class represented by this class file
     fn read_super_class(
         reader: &mut ByteReader,
         constant_pool: &ConstantPoolContainer,
-    ) -> Option<ConstantClassInfo> {
-        let constant_pool_index = to_u16(&reader.read_n_bytes(2));
-
-        if constant_pool_index == 0 {
-            return None;
-        }
+    ) -> Result<Option<ConstantClassInfo>, ClassFileError> {
+        let constant_pool_index = reader.read_u16()?;
 
-        let constant_pool_entry = constant_pool.get(&constant_pool_index).expect(&format!(
-            "Unable to fetch entry from constant pool at index {}",
-            constant_pool_index
-        ));
-
-        match constant_pool_entry.try_cast_into_class() {
-            Some(class) => Some(class.clone()),
-            None => None,
-        }
+        Ok(resolve_optional_class(constant_pool, constant_pool_index)?.cloned())
     }
 
     /// Read information about all direct superinterfaces of this class or interface type from the constant pool
     fn read_interfaces(
         reader: &mut ByteReader,
         constant_pool: &ConstantPoolContainer,
-    ) -> Vec<ConstantClassInfo> {
-        let interfaces_count = to_u16(&reader.read_n_bytes(2));
+    ) -> Result<Vec<ConstantClassInfo>, ClassFileError> {
+        let interfaces_count = reader.read_count(2)?;
         let mut interfaces = vec![];
 
         for _ in 0..interfaces_count {
-            let constant_pool_index = to_u16(&reader.read_n_bytes(2));
-
-            let constant_pool_entry = constant_pool.get(&constant_pool_index).expect(&format!(
-                "Unable to fetch entry from constant pool at index {}",
-                constant_pool_index
-            ));
-
-            match constant_pool_entry.try_cast_into_class() {
-                Some(class) => interfaces.push(class.clone()),
-                None => panic!("Unable to fetch a class entry from the constant pool, error at constant pool index {}", constant_pool_index)
-            };
+            let constant_pool_index = reader.read_u16()?;
+            interfaces.push(resolve_class(constant_pool, constant_pool_index)?.clone());
         }
 
-        interfaces
+        Ok(interfaces)
     }
 
     /// Read information about the fields in this class or interface represented by this class file
     fn read_fields(
         reader: &mut ByteReader,
         constant_pool: &ConstantPoolContainer,
-    ) -> Vec<FieldInfo> {
-        let fields_count = to_u16(&reader.read_n_bytes(2));
+        config: &ClassFileConfig,
+    ) -> Result<Vec<FieldInfo>, ClassFileError> {
+        let fields_count = reader.read_count(8)?;
         let mut fields = vec![];
 
         for _ in 0..fields_count {
-            fields.push(FieldInfo::new(reader, constant_pool));
+            fields.push(FieldInfo::new(
+                reader,
+                constant_pool,
+                config.max_attribute_depth,
+                config.max_annotation_depth,
+            )?);
         }
 
-        fields
+        Ok(fields)
     }
 
     /// Read information about the methods
     fn read_methods(
         reader: &mut ByteReader,
         constant_pool: &ConstantPoolContainer,
-    ) -> Vec<MethodInfo> {
-        let methods_count = to_u16(&reader.read_n_bytes(2));
+        config: &ClassFileConfig,
+    ) -> Result<Vec<MethodInfo>, ClassFileError> {
+        let methods_count = reader.read_count(8)?;
         let mut methods = vec![];
 
         for _ in 0..methods_count {
-            methods.push(MethodInfo::new(reader, constant_pool));
+            methods.push(MethodInfo::new(
+                reader,
+                constant_pool,
+                config.max_attribute_depth,
+                config.max_annotation_depth,
+            )?);
         }
 
-        methods
+        Ok(methods)
     }
 
     /// Read information about the class attributes
     fn read_attributes(
         reader: &mut ByteReader,
         constant_pool: &ConstantPoolContainer,
-    ) -> Vec<AttributeInfo> {
-        let attributes_count = to_u16(&reader.read_n_bytes(2));
+        config: &ClassFileConfig,
+    ) -> Result<Vec<AttributeInfo>, ClassFileError> {
+        let attributes_count = reader.read_count(6)?;
         let mut attributes = vec![];
 
         for _ in 0..attributes_count {
-            attributes.push(AttributeInfo::new(reader, constant_pool));
+            attributes.push(AttributeInfo::new(
+                reader,
+                constant_pool,
+                1,
+                config.max_attribute_depth,
+                config.max_annotation_depth,
+            )?);
+        }
+
+        Ok(attributes)
+    }
+}
+
+/// Parse a complete class file from a binary blob using the default configuration
+///
+/// This is the entry point for untrusted input: malformed data of any kind results in an error
+/// rather than a panic, which makes it suitable as a fuzzing target.
+pub fn parse(data: &[u8]) -> Result<ClassFile, ClassFileError> {
+    ClassFile::new(&mut ByteReader::from_bytes(data), &ClassFileConfig::new())
+}
+
+/// Parse every class file in a set of binary blobs using the default configuration
+///
+/// The results are in the same order as the blobs, a malformed blob does not stop the others from
+/// being parsed. No file IO is involved, which makes this suitable for measuring the throughput of
+/// the parser.
+pub fn parse_all<I>(blobs: I) -> Vec<Result<ClassFile, ClassFileError>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    parse_all_with_progress(blobs, |_, _| {})
+}
+
+/// Parse every class file in a set of binary blobs like [`parse_all`], reporting progress after
+/// each blob
+///
+/// The callback receives the number of blobs and the number of bytes processed so far, e.g. to
+/// keep a user interface responsive while the entries of a large JAR archive are being parsed.
+pub fn parse_all_with_progress<I, F>(
+    blobs: I,
+    mut progress: F,
+) -> Vec<Result<ClassFile, ClassFileError>>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+    F: FnMut(usize, usize),
+{
+    let config = ClassFileConfig::new();
+    let mut bytes_processed = 0;
+
+    blobs
+        .into_iter()
+        .enumerate()
+        .map(|(index, data)| {
+            let data = data.as_ref();
+            let class = ClassFile::new(&mut ByteReader::from_bytes(data), &config);
+
+            bytes_processed += data.len();
+            progress(index + 1, bytes_processed);
+
+            class
+        })
+        .collect()
+}
+
+/// Add the internal names of the classes mentioned in a field or method descriptor, malformed
+/// descriptors are ignored
+fn add_descriptor_classes(descriptor: &str, names: &mut BTreeSet<String>) {
+    if descriptor.starts_with('(') {
+        if let Some(descriptor) = parse_method_descriptor(descriptor) {
+            add_type_classes(descriptor.types(), names);
+        }
+    } else {
+        add_type_classes(parse_field_descriptor(descriptor), names);
+    }
+}
+
+/// Add the internal names of the classes mentioned in a list of types, array types contribute
+/// their element class
+fn add_type_classes(
+    field_types: impl IntoIterator<Item = FieldType>,
+    names: &mut BTreeSet<String>,
+) {
+    for mut field_type in field_types {
+        while let FieldType::Array(component_type) = field_type {
+            field_type = *component_type;
+        }
+
+        if let FieldType::Object(name) = field_type {
+            names.insert(name);
+        }
+    }
+}
+
+/// Add all attributes of the specified type to the list of matches, including the attributes that
+/// are nested inside code attributes
+fn collect_attributes<'a>(
+    attributes: &'a [AttributeInfo],
+    attribute_type: &AttributeType,
+    matches: &mut Vec<&'a AttributeInfo>,
+) {
+    for attribute in attributes {
+        if attribute.attribute_type == *attribute_type {
+            matches.push(attribute);
+        }
+
+        if let Some(code) = attribute.try_cast_into_code() {
+            collect_attributes(&code.attributes, attribute_type, matches);
+        }
+    }
+}
+
+/// Derive the kind of type from a set of class access flags
+///
+/// `AccAnnotation` is always accompanied by `AccInterface`, which is why annotation interfaces have
+/// to be detected before plain interfaces
+fn class_kind(access_flags: &[ClassAccessFlags]) -> ClassKind {
+    if access_flags.contains(&ClassAccessFlags::AccModule) {
+        ClassKind::Module
+    } else if access_flags.contains(&ClassAccessFlags::AccAnnotation) {
+        ClassKind::Annotation
+    } else if access_flags.contains(&ClassAccessFlags::AccInterface) {
+        ClassKind::Interface
+    } else if access_flags.contains(&ClassAccessFlags::AccEnum) {
+        ClassKind::Enum
+    } else {
+        ClassKind::Class
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{class_kind, parse, ClassFile, ClassFileConfig, ClassFileError, ClassKind};
+    use crate::byte_reader::ByteReader;
+    use crate::classfile::{PoolError, Tag};
+    use crate::flags::{ClassAccessFlags, Flags};
+
+    #[test]
+    fn test_default_major_version_range() {
+        let config = ClassFileConfig::new();
+
+        assert!(config.verify_major_version(45).is_ok());
+        assert!(config.verify_major_version(61).is_ok());
+        assert!(config.verify_major_version(65).is_ok());
+        assert!(config.verify_major_version(44).is_err());
+        assert!(config.verify_major_version(66).is_err());
+    }
+
+    #[test]
+    fn test_custom_major_version_range() {
+        let mut config = ClassFileConfig::new();
+        config.with_min_major_version(52);
+        config.with_max_major_version(55);
+
+        assert!(config.verify_major_version(52).is_ok());
+        assert!(config.verify_major_version(55).is_ok());
+        assert_eq!(
+            config.verify_major_version(61),
+            Err(ClassFileError::UnsupportedVersion {
+                major_version: 61,
+                min_major_version: 52,
+                max_major_version: 55,
+                offset: 6,
+            }),
+            "Major version outside of the range should be rejected"
+        );
+    }
+
+    #[test]
+    fn test_class_kind() {
+        assert_eq!(
+            class_kind(&ClassAccessFlags::from_u16(0x0021)),
+            ClassKind::Class
+        );
+        assert_eq!(
+            class_kind(&ClassAccessFlags::from_u16(0x0601)),
+            ClassKind::Interface
+        );
+        assert_eq!(
+            class_kind(&ClassAccessFlags::from_u16(0x2601)),
+            ClassKind::Annotation
+        );
+        assert_eq!(
+            class_kind(&ClassAccessFlags::from_u16(0x4031)),
+            ClassKind::Enum
+        );
+        assert_eq!(
+            class_kind(&ClassAccessFlags::from_u16(0x8000)),
+            ClassKind::Module
+        );
+    }
+
+    #[test]
+    fn test_input_too_short() {
+        for input in [
+            &[][..],
+            &[0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x3D, 0x00],
+        ] {
+            let mut reader = ByteReader::from_bytes(input);
+
+            assert_eq!(
+                ClassFile::new(&mut reader, &ClassFileConfig::new()).err(),
+                Some(ClassFileError::TooShort {
+                    length: input.len(),
+                    min_length: 10,
+                }),
+                "Inputs shorter than ten bytes should be rejected"
+            );
         }
+    }
+
+    #[test]
+    fn test_this_class_wrong_type() {
+        #[rustfmt::skip]
+        let input = [
+            0xCA, 0xFE, 0xBA, 0xBE, // Magic
+            0x00, 0x00, 0x00, 0x3D, // Version 61.0
+            0x00, 0x02,             // Constant pool count
+            0x01, 0x00, 0x01, 0x41, // #1 = Utf8 "A"
+            0x00, 0x21,             // Access flags
+            0x00, 0x01,             // This class refers to a UTF-8 entry instead of a class entry
+        ];
+        let mut reader = ByteReader::from_bytes(&input);
+
+        assert_eq!(
+            ClassFile::new(&mut reader, &ClassFileConfig::new()).err(),
+            Some(ClassFileError::Pool(PoolError::WrongType {
+                index: 1,
+                expected: "Class",
+                found: Tag::ConstantUtf8,
+            })),
+            "This class must refer to a class entry"
+        );
+    }
+
+    #[test]
+    fn test_long_occupies_two_slots() {
+        #[rustfmt::skip]
+        let input = [
+            0x00, 0x08,                                     // Constant pool count
+            0x01, 0x00, 0x01, 0x41,                         // #1 = Utf8 "A"
+            0x01, 0x00, 0x01, 0x42,                         // #2 = Utf8 "B"
+            0x01, 0x00, 0x01, 0x43,                         // #3 = Utf8 "C"
+            0x01, 0x00, 0x01, 0x44,                         // #4 = Utf8 "D"
+            0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // #5 = Long, which also takes #6
+            0x2A,
+            0x01, 0x00, 0x01, 0x45,                         // #7 = Utf8 "E"
+        ];
+        let mut reader = ByteReader::from_bytes(&input);
+        let (count, constant_pool) = ClassFile::read_constant_pool(&mut reader).unwrap();
+
+        assert_eq!(count, 8);
+        assert_eq!(constant_pool.len(), 6);
+        assert_eq!(constant_pool[&5].tag, Tag::ConstantLong);
+        assert!(
+            !constant_pool.contains_key(&6),
+            "The slot after a long is unusable"
+        );
+        assert_eq!(constant_pool[&7].try_cast_into_utf8().unwrap().string, "E");
+        assert!(reader.is_at_end());
+    }
 
-        attributes
+    #[test]
+    fn test_malformed_input_is_an_error() {
+        #[rustfmt::skip]
+        let invalid_magic = [
+            0xCA, 0xFE, 0xD0, 0x0D, // Magic
+            0x00, 0x00, 0x00, 0x3D, // Version 61.0
+            0x00, 0x01,             // Constant pool count
+        ];
+        #[rustfmt::skip]
+        let unknown_tag = [
+            0xCA, 0xFE, 0xBA, 0xBE, // Magic
+            0x00, 0x00, 0x00, 0x3D, // Version 61.0
+            0x00, 0x02,             // Constant pool count
+            0x02, 0x00, 0x00, 0x00, // #1 uses the unassigned tag 2
+            0x00, 0x21,             // Access flags
+        ];
+        #[rustfmt::skip]
+        let unknown_handle_kind = [
+            0xCA, 0xFE, 0xBA, 0xBE, // Magic
+            0x00, 0x00, 0x00, 0x3D, // Version 61.0
+            0x00, 0x02,             // Constant pool count
+            0x0F, 0x0A, 0x00, 0x01, // #1 = MethodHandle with reference kind 10
+            0x00, 0x21,             // Access flags
+        ];
+
+        #[rustfmt::skip]
+        let zero_pool_count = [
+            0xCA, 0xFE, 0xBA, 0xBE, // Magic
+            0x00, 0x00, 0x00, 0x3D, // Version 61.0
+            0x00, 0x00,             // Constant pool count
+            0x00, 0x21,             // Access flags
+            0x00, 0x01,             // This class
+        ];
+        #[rustfmt::skip]
+        let empty_pool = [
+            0xCA, 0xFE, 0xBA, 0xBE, // Magic
+            0x00, 0x00, 0x00, 0x3D, // Version 61.0
+            0x00, 0x01,             // Constant pool count
+            0x00, 0x21,             // Access flags
+            0x00, 0x01,             // This class
+        ];
+
+        assert_eq!(
+            parse(&zero_pool_count).err(),
+            Some(ClassFileError::InvalidConstantPoolCount { offset: 8 })
+        );
+        assert_eq!(
+            parse(&empty_pool).err(),
+            Some(ClassFileError::EmptyConstantPool)
+        );
+        assert_eq!(
+            parse(&invalid_magic).err(),
+            Some(ClassFileError::InvalidMagic { found: 0xCAFED00D })
+        );
+        assert_eq!(
+            parse(&unknown_tag).err(),
+            Some(ClassFileError::UnknownConstantTag { tag: 2, offset: 10 })
+        );
+        assert_eq!(
+            parse(&unknown_handle_kind).err(),
+            Some(ClassFileError::UnknownMethodHandleKind {
+                kind: 10,
+                offset: 11
+            })
+        );
     }
 }
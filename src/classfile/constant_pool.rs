@@ -2,12 +2,11 @@
 //!
 //! This module contains all information necessary to parse constant pool entities from class files
 
-use std::{any::Any, collections::BTreeMap, panic};
+use std::{any::Any, collections::BTreeMap, fmt};
 
-use crate::{
-    byte_reader::ByteReader,
-    utils::{to_f32, to_f64, to_i32, to_i64, to_u16},
-};
+use crate::byte_reader::ByteReader;
+
+use super::ClassFileError;
 
 /// Constant pool container type
 pub type ConstantPoolContainer = BTreeMap<u16, ConstantPoolInfo>;
@@ -19,8 +18,7 @@ trait ConstantPoolInfoData {
 }
 
 /// Constant pool tags
-// TODO: remove debug directive
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Tag {
     /// UTF-8 string
     ConstantUtf8,
@@ -75,9 +73,9 @@ pub enum Tag {
 }
 
 impl Tag {
-    /// Convert a "tag" (u8) into its matching enum type, panics if no matching value could be found
-    fn from_tag(tag: &u8) -> Self {
-        match tag {
+    /// Convert a "tag" (u8) into its matching enum type, if there is one
+    fn from_tag(tag: &u8) -> Option<Self> {
+        Some(match tag {
             1 => Self::ConstantUtf8,
             3 => Self::ConstantInteger,
             4 => Self::ConstantFloat,
@@ -95,12 +93,36 @@ impl Tag {
             18 => Self::ConstantInvokeDynamic,
             19 => Self::ConstantModule,
             20 => Self::ConstantPackage,
-            _ => panic!("Unknown tag: {}", tag),
+            _ => return None,
+        })
+    }
+
+    /// Name of the entry type as used in the specification, e.g. `Utf8` or `Methodref`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ConstantUtf8 => "Utf8",
+            Self::ConstantInteger => "Integer",
+            Self::ConstantFloat => "Float",
+            Self::ConstantLong => "Long",
+            Self::ConstantDouble => "Double",
+            Self::ConstantClass => "Class",
+            Self::ConstantString => "String",
+            Self::ConstantFieldRef => "Fieldref",
+            Self::ConstantMethodRef => "Methodref",
+            Self::ConstantInterfaceMethodRef => "InterfaceMethodref",
+            Self::ConstantNameAndType => "NameAndType",
+            Self::ConstantMethodHandle => "MethodHandle",
+            Self::ConstantMethodType => "MethodType",
+            Self::ConstantDynamic => "Dynamic",
+            Self::ConstantInvokeDynamic => "InvokeDynamic",
+            Self::ConstantModule => "Module",
+            Self::ConstantPackage => "Package",
         }
     }
 }
 
 /// Bytecode behaviours for method handles
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MethodHandleType {
     /// getfield C.f:T
     RefGetField,
@@ -131,9 +153,9 @@ pub enum MethodHandleType {
 }
 
 impl MethodHandleType {
-    /// Convert a "kind" (u8) into its matching enum type, panics if no matching value could be found
-    fn from_kind(kind: &u8) -> Self {
-        match kind {
+    /// Convert a "kind" (u8) into its matching enum type, if there is one
+    fn from_kind(kind: &u8) -> Option<Self> {
+        Some(match kind {
             1 => Self::RefGetField,
             2 => Self::RefGetStatic,
             3 => Self::RefPutField,
@@ -143,11 +165,62 @@ impl MethodHandleType {
             7 => Self::RefInvokeSpecial,
             8 => Self::RefNewInvokeSpecial,
             9 => Self::RefInvokeInterface,
-            _ => panic!("Unknown method handle type: {}", kind),
+            _ => return None,
+        })
+    }
+
+    /// Fetch the "kind" (u8) as stored in the class file
+    pub fn kind(&self) -> u8 {
+        match self {
+            Self::RefGetField => 1,
+            Self::RefGetStatic => 2,
+            Self::RefPutField => 3,
+            Self::RefPutStatic => 4,
+            Self::RefInvokeVirtual => 5,
+            Self::RefInvokeStatic => 6,
+            Self::RefInvokeSpecial => 7,
+            Self::RefNewInvokeSpecial => 8,
+            Self::RefInvokeInterface => 9,
+        }
+    }
+
+    /// Fetch the bytecode behaviour as it would be written in assembly, e.g. `invokestatic`
+    pub fn to_str(&self) -> &'static str {
+        match self {
+            Self::RefGetField => "getfield",
+            Self::RefGetStatic => "getstatic",
+            Self::RefPutField => "putfield",
+            Self::RefPutStatic => "putstatic",
+            Self::RefInvokeVirtual => "invokevirtual",
+            Self::RefInvokeStatic => "invokestatic",
+            Self::RefInvokeSpecial => "invokespecial",
+            Self::RefNewInvokeSpecial => "newinvokespecial",
+            Self::RefInvokeInterface => "invokeinterface",
+        }
+    }
+
+    /// Fetch the name of the reference kind as used in the specification, e.g. `REF_invokeStatic`
+    pub fn spec_name(&self) -> &'static str {
+        match self {
+            Self::RefGetField => "REF_getField",
+            Self::RefGetStatic => "REF_getStatic",
+            Self::RefPutField => "REF_putField",
+            Self::RefPutStatic => "REF_putStatic",
+            Self::RefInvokeVirtual => "REF_invokeVirtual",
+            Self::RefInvokeStatic => "REF_invokeStatic",
+            Self::RefInvokeSpecial => "REF_invokeSpecial",
+            Self::RefNewInvokeSpecial => "REF_newInvokeSpecial",
+            Self::RefInvokeInterface => "REF_invokeInterface",
         }
     }
 }
 
+impl fmt::Display for MethodHandleType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
 /// Represents an entity in the constant pool
 pub struct ConstantPoolInfo {
     /// Identifies the type of data this entity represents
@@ -159,383 +232,452 @@ pub struct ConstantPoolInfo {
 
 impl ConstantPoolInfo {
     /// Create a new constant pool entity from a class file binary blob
-    pub fn new(reader: &mut ByteReader, index: u16) -> Self {
-        let tag = reader.read_n_bytes(1);
+    pub fn new(reader: &mut ByteReader, index: u16) -> Result<Self, ClassFileError> {
+        let offset = reader.position();
+        let tag = reader.read_u8()?;
+        let tag = Tag::from_tag(&tag).ok_or(ClassFileError::UnknownConstantTag { tag, offset })?;
 
-        match Tag::from_tag(&tag[0]) {
+        Ok(match tag {
             Tag::ConstantUtf8 => Self {
                 tag: Tag::ConstantUtf8,
-                data: Box::new(Self::read_data_as_utf8(reader, index)),
+                data: Box::new(Self::read_data_as_utf8(reader, index)?),
             },
             Tag::ConstantInteger => Self {
                 tag: Tag::ConstantInteger,
-                data: Box::new(Self::read_data_as_integer(reader, index)),
+                data: Box::new(Self::read_data_as_integer(reader, index)?),
             },
             Tag::ConstantFloat => Self {
                 tag: Tag::ConstantFloat,
-                data: Box::new(Self::read_data_as_float(reader, index)),
+                data: Box::new(Self::read_data_as_float(reader, index)?),
             },
             Tag::ConstantLong => Self {
                 tag: Tag::ConstantLong,
-                data: Box::new(Self::read_data_as_long(reader, index)),
+                data: Box::new(Self::read_data_as_long(reader, index)?),
             },
             Tag::ConstantDouble => Self {
                 tag: Tag::ConstantDouble,
-                data: Box::new(Self::read_data_as_double(reader, index)),
+                data: Box::new(Self::read_data_as_double(reader, index)?),
             },
             Tag::ConstantClass => Self {
                 tag: Tag::ConstantClass,
-                data: Box::new(Self::read_data_as_class(reader, index)),
+                data: Box::new(Self::read_data_as_class(reader, index)?),
             },
             Tag::ConstantString => Self {
                 tag: Tag::ConstantString,
-                data: Box::new(Self::read_data_as_string(reader, index)),
+                data: Box::new(Self::read_data_as_string(reader, index)?),
             },
             Tag::ConstantFieldRef => Self {
                 tag: Tag::ConstantFieldRef,
-                data: Box::new(Self::read_data_as_field_ref(reader, index)),
+                data: Box::new(Self::read_data_as_field_ref(reader, index)?),
             },
             Tag::ConstantMethodRef => Self {
                 tag: Tag::ConstantMethodRef,
-                data: Box::new(Self::read_data_as_method_ref(reader, index)),
+                data: Box::new(Self::read_data_as_method_ref(reader, index)?),
             },
             Tag::ConstantInterfaceMethodRef => Self {
                 tag: Tag::ConstantInterfaceMethodRef,
-                data: Box::new(Self::read_data_as_interface_method_ref(reader, index)),
+                data: Box::new(Self::read_data_as_interface_method_ref(reader, index)?),
             },
             Tag::ConstantNameAndType => Self {
                 tag: Tag::ConstantNameAndType,
-                data: Box::new(Self::read_data_as_name_and_type(reader, index)),
+                data: Box::new(Self::read_data_as_name_and_type(reader, index)?),
             },
             Tag::ConstantMethodHandle => Self {
                 tag: Tag::ConstantMethodHandle,
-                data: Box::new(Self::read_data_as_method_handle(reader, index)),
+                data: Box::new(Self::read_data_as_method_handle(reader, index)?),
             },
             Tag::ConstantMethodType => Self {
                 tag: Tag::ConstantMethodType,
-                data: Box::new(Self::read_data_as_method_type(reader, index)),
+                data: Box::new(Self::read_data_as_method_type(reader, index)?),
             },
             Tag::ConstantDynamic => Self {
                 tag: Tag::ConstantDynamic,
-                data: Box::new(Self::read_data_as_dynamic(reader, index)),
+                data: Box::new(Self::read_data_as_dynamic(reader, index)?),
             },
             Tag::ConstantInvokeDynamic => Self {
                 tag: Tag::ConstantInvokeDynamic,
-                data: Box::new(Self::read_data_as_invoke_dynamic(reader, index)),
+                data: Box::new(Self::read_data_as_invoke_dynamic(reader, index)?),
             },
             Tag::ConstantModule => Self {
                 tag: Tag::ConstantModule,
-                data: Box::new(Self::read_data_as_module(reader, index)),
+                data: Box::new(Self::read_data_as_module(reader, index)?),
             },
             Tag::ConstantPackage => Self {
                 tag: Tag::ConstantPackage,
-                data: Box::new(Self::read_data_as_package(reader, index)),
+                data: Box::new(Self::read_data_as_package(reader, index)?),
             },
-        }
+        })
     }
 
     /// Read the data blob as an UTF-8 constant pool entry
-    fn read_data_as_utf8(reader: &mut ByteReader, constant_pool_index: u16) -> ConstantUtf8Info {
-        let length = to_u16(&reader.read_n_bytes(2));
+    fn read_data_as_utf8(
+        reader: &mut ByteReader,
+        constant_pool_index: u16,
+    ) -> Result<ConstantUtf8Info, ClassFileError> {
+        let length = reader.read_u16()?;
+        let raw = reader.read_n_bytes(usize::from(length))?;
 
-        ConstantUtf8Info {
+        Ok(ConstantUtf8Info {
             constant_pool_index,
             length,
-            string: String::from_utf8_lossy(&reader.read_n_bytes(usize::from(length))).to_string(),
-        }
+            string: String::from_utf8_lossy(&raw).to_string(),
+            raw,
+        })
     }
 
     /// Read the data blob as an integer constant pool entry
     fn read_data_as_integer(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantIntegerInfo {
-        ConstantIntegerInfo {
+    ) -> Result<ConstantIntegerInfo, ClassFileError> {
+        Ok(ConstantIntegerInfo {
             constant_pool_index,
-            value: to_i32(&reader.read_n_bytes(4)),
-        }
+            value: reader.read_i32()?,
+        })
     }
 
     /// Read the data blob as a float constant pool entry
-    fn read_data_as_float(reader: &mut ByteReader, constant_pool_index: u16) -> ConstantFloatInfo {
-        ConstantFloatInfo {
+    fn read_data_as_float(
+        reader: &mut ByteReader,
+        constant_pool_index: u16,
+    ) -> Result<ConstantFloatInfo, ClassFileError> {
+        Ok(ConstantFloatInfo {
             constant_pool_index,
-            value: to_f32(&reader.read_n_bytes(4)),
-        }
+            value: reader.read_f32()?,
+        })
     }
 
     /// Read the data blob as a long constant pool entry
-    fn read_data_as_long(reader: &mut ByteReader, constant_pool_index: u16) -> ConstantLongInfo {
-        ConstantLongInfo {
+    fn read_data_as_long(
+        reader: &mut ByteReader,
+        constant_pool_index: u16,
+    ) -> Result<ConstantLongInfo, ClassFileError> {
+        Ok(ConstantLongInfo {
             constant_pool_index,
-            value: to_i64(&reader.read_n_bytes(8)),
-        }
+            value: reader.read_i64()?,
+        })
     }
 
     /// Read the data blob as a double constant pool entry
     fn read_data_as_double(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantDoubleInfo {
-        ConstantDoubleInfo {
+    ) -> Result<ConstantDoubleInfo, ClassFileError> {
+        Ok(ConstantDoubleInfo {
             constant_pool_index,
-            value: to_f64(&reader.read_n_bytes(8)),
-        }
+            value: reader.read_f64()?,
+        })
     }
 
     /// Read the data blob as a class constant pool entry
-    fn read_data_as_class(reader: &mut ByteReader, constant_pool_index: u16) -> ConstantClassInfo {
-        ConstantClassInfo {
+    fn read_data_as_class(
+        reader: &mut ByteReader,
+        constant_pool_index: u16,
+    ) -> Result<ConstantClassInfo, ClassFileError> {
+        Ok(ConstantClassInfo {
             constant_pool_index,
-            name_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            name_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as a string constant pool entry
     fn read_data_as_string(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantStringInfo {
-        ConstantStringInfo {
+    ) -> Result<ConstantStringInfo, ClassFileError> {
+        Ok(ConstantStringInfo {
             constant_pool_index,
-            string_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            string_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as a field reference constant pool entry
     fn read_data_as_field_ref(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantFieldRefInfo {
-        ConstantFieldRefInfo {
+    ) -> Result<ConstantFieldRefInfo, ClassFileError> {
+        Ok(ConstantFieldRefInfo {
             constant_pool_index,
-            class_index: to_u16(&reader.read_n_bytes(2)),
-            name_and_type_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            class_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as a method reference constant pool entry
     fn read_data_as_method_ref(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantMethodRefInfo {
-        ConstantMethodRefInfo {
+    ) -> Result<ConstantMethodRefInfo, ClassFileError> {
+        Ok(ConstantMethodRefInfo {
             constant_pool_index,
-            class_index: to_u16(&reader.read_n_bytes(2)),
-            name_and_type_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            class_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as an interface method reference constant pool entry
     fn read_data_as_interface_method_ref(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantInterfaceMethodRefInfo {
-        ConstantInterfaceMethodRefInfo {
+    ) -> Result<ConstantInterfaceMethodRefInfo, ClassFileError> {
+        Ok(ConstantInterfaceMethodRefInfo {
             constant_pool_index,
-            class_index: to_u16(&reader.read_n_bytes(2)),
-            name_and_type_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            class_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as a name and type constant pool entry
     fn read_data_as_name_and_type(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantNameAndTypeInfo {
-        ConstantNameAndTypeInfo {
+    ) -> Result<ConstantNameAndTypeInfo, ClassFileError> {
+        Ok(ConstantNameAndTypeInfo {
             constant_pool_index,
-            name_index: to_u16(&reader.read_n_bytes(2)),
-            descriptor_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            name_index: reader.read_u16()?,
+            descriptor_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as a method handle constant pool entry
     fn read_data_as_method_handle(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantMethodHandleInfo {
-        ConstantMethodHandleInfo {
+    ) -> Result<ConstantMethodHandleInfo, ClassFileError> {
+        let offset = reader.position();
+        let kind = reader.read_u8()?;
+
+        Ok(ConstantMethodHandleInfo {
             constant_pool_index,
-            reference_kind: MethodHandleType::from_kind(&reader.read_n_bytes(1)[0]),
-            reference_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            reference_kind: MethodHandleType::from_kind(&kind)
+                .ok_or(ClassFileError::UnknownMethodHandleKind { kind, offset })?,
+            reference_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as a method type constant pool entry
     fn read_data_as_method_type(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantMethodTypeInfo {
-        ConstantMethodTypeInfo {
+    ) -> Result<ConstantMethodTypeInfo, ClassFileError> {
+        Ok(ConstantMethodTypeInfo {
             constant_pool_index,
-            descriptor_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            descriptor_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as a dynamic constant pool entry
     fn read_data_as_dynamic(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantDynamicInfo {
-        ConstantDynamicInfo {
+    ) -> Result<ConstantDynamicInfo, ClassFileError> {
+        Ok(ConstantDynamicInfo {
             constant_pool_index,
-            bootstrap_method_attr_index: to_u16(&reader.read_n_bytes(2)),
-            name_and_type_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            bootstrap_method_attr_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as an invoke dynamic constant pool entry
     fn read_data_as_invoke_dynamic(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantInvokeDynamicInfo {
-        ConstantInvokeDynamicInfo {
+    ) -> Result<ConstantInvokeDynamicInfo, ClassFileError> {
+        Ok(ConstantInvokeDynamicInfo {
             constant_pool_index,
-            bootstrap_method_attr_index: to_u16(&reader.read_n_bytes(2)),
-            name_and_type_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            bootstrap_method_attr_index: reader.read_u16()?,
+            name_and_type_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as a module constant pool entry
     fn read_data_as_module(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantModuleInfo {
-        ConstantModuleInfo {
+    ) -> Result<ConstantModuleInfo, ClassFileError> {
+        Ok(ConstantModuleInfo {
             constant_pool_index,
-            name_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            name_index: reader.read_u16()?,
+        })
     }
 
     /// Read the data blob as a package constant pool entry
     fn read_data_as_package(
         reader: &mut ByteReader,
         constant_pool_index: u16,
-    ) -> ConstantPackageInfo {
-        ConstantPackageInfo {
+    ) -> Result<ConstantPackageInfo, ClassFileError> {
+        Ok(ConstantPackageInfo {
             constant_pool_index,
-            name_index: to_u16(&reader.read_n_bytes(2)),
-        }
+            name_index: reader.read_u16()?,
+        })
+    }
+
+    /// Cast the entry's data to a concrete type, the tag is only used as a sanity check
+    ///
+    /// Debug builds panic if the tag and the boxed data disagree, as that means the entry was
+    /// parsed into the wrong structure
+    fn try_cast<T: 'static>(&self, tag: Tag) -> Option<&T> {
+        let concrete = self.data.as_concrete_type().downcast_ref::<T>();
+
+        debug_assert_eq!(
+            concrete.is_some(),
+            self.tag == tag,
+            "Constant pool entry with tag {} was cast into a {} entry, but its data does not match its tag",
+            self.tag.name(),
+            tag.name()
+        );
+
+        concrete
     }
 
     /// Cast to as an UTF-8 constant pool entry
     pub fn try_cast_into_utf8(&self) -> Option<&ConstantUtf8Info> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantUtf8Info>()
+        self.try_cast::<ConstantUtf8Info>(Tag::ConstantUtf8)
     }
 
     /// Cast to an integer constant pool entry
     pub fn try_cast_into_integer(&self) -> Option<&ConstantIntegerInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantIntegerInfo>()
+        self.try_cast::<ConstantIntegerInfo>(Tag::ConstantInteger)
     }
 
     /// Cast to a float constant pool entry
     pub fn try_cast_into_float(&self) -> Option<&ConstantFloatInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantFloatInfo>()
+        self.try_cast::<ConstantFloatInfo>(Tag::ConstantFloat)
     }
 
     /// Cast to a long constant pool entry
     pub fn try_cast_into_long(&self) -> Option<&ConstantLongInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantLongInfo>()
+        self.try_cast::<ConstantLongInfo>(Tag::ConstantLong)
     }
 
     /// Cast to a double constant pool entry
     pub fn try_cast_into_double(&self) -> Option<&ConstantDoubleInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantDoubleInfo>()
+        self.try_cast::<ConstantDoubleInfo>(Tag::ConstantDouble)
     }
 
     /// Cast to a class constant pool entry
     pub fn try_cast_into_class(&self) -> Option<&ConstantClassInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantClassInfo>()
+        self.try_cast::<ConstantClassInfo>(Tag::ConstantClass)
     }
 
     /// Cast to a string constant pool entry
     pub fn try_cast_into_string(&self) -> Option<&ConstantStringInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantStringInfo>()
+        self.try_cast::<ConstantStringInfo>(Tag::ConstantString)
     }
 
     /// Cast to a field reference constant pool entry
     pub fn try_cast_into_field_ref(&self) -> Option<&ConstantFieldRefInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantFieldRefInfo>()
+        self.try_cast::<ConstantFieldRefInfo>(Tag::ConstantFieldRef)
     }
 
     /// Cast to a method reference constant pool entry
     pub fn try_cast_into_method_ref(&self) -> Option<&ConstantMethodRefInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantMethodRefInfo>()
+        self.try_cast::<ConstantMethodRefInfo>(Tag::ConstantMethodRef)
     }
 
     /// Cast to an interface method reference constant pool entry
     pub fn try_cast_into_interface_method_ref(&self) -> Option<&ConstantInterfaceMethodRefInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantInterfaceMethodRefInfo>()
+        self.try_cast::<ConstantInterfaceMethodRefInfo>(Tag::ConstantInterfaceMethodRef)
     }
 
     /// Cast to a name and type constant pool entry
     pub fn try_cast_into_name_and_type(&self) -> Option<&ConstantNameAndTypeInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantNameAndTypeInfo>()
+        self.try_cast::<ConstantNameAndTypeInfo>(Tag::ConstantNameAndType)
     }
 
     /// Cast to a method handle constant pool entry
     pub fn try_cast_into_method_handle(&self) -> Option<&ConstantMethodHandleInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantMethodHandleInfo>()
+        self.try_cast::<ConstantMethodHandleInfo>(Tag::ConstantMethodHandle)
     }
 
     /// Cast to a method type constant pool entry
     pub fn try_cast_into_method_type(&self) -> Option<&ConstantMethodTypeInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantMethodTypeInfo>()
+        self.try_cast::<ConstantMethodTypeInfo>(Tag::ConstantMethodType)
     }
 
     /// Cast to a dynamic constant pool entry
     pub fn try_cast_into_dynamic(&self) -> Option<&ConstantDynamicInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantDynamicInfo>()
+        self.try_cast::<ConstantDynamicInfo>(Tag::ConstantDynamic)
     }
 
     /// Cast to an invoke dynamic constant pool entry
     pub fn try_cast_into_invoke_dynamic(&self) -> Option<&ConstantInvokeDynamicInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantInvokeDynamicInfo>()
+        self.try_cast::<ConstantInvokeDynamicInfo>(Tag::ConstantInvokeDynamic)
     }
 
     /// Cast to a module constant pool entry
     pub fn try_cast_into_module(&self) -> Option<&ConstantModuleInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantModuleInfo>()
+        self.try_cast::<ConstantModuleInfo>(Tag::ConstantModule)
     }
 
     /// Cast to a package constant pool entry
     pub fn try_cast_into_package(&self) -> Option<&ConstantPackageInfo> {
-        self.data
-            .as_concrete_type()
-            .downcast_ref::<ConstantPackageInfo>()
+        self.try_cast::<ConstantPackageInfo>(Tag::ConstantPackage)
+    }
+
+    /// Fetch the indices of the entries this entry refers to, each paired with the name of the
+    /// field that holds the index, e.g. `("name_index", 7)` for a class entry
+    ///
+    /// The bootstrap method index of a dynamic entry refers to the bootstrap methods attribute
+    /// rather than to the constant pool, so it is not included
+    pub fn references(&self) -> Vec<(&'static str, u16)> {
+        let references = match self.tag {
+            Tag::ConstantClass => self
+                .try_cast_into_class()
+                .map(|class| vec![("name_index", class.name_index)]),
+            Tag::ConstantString => self
+                .try_cast_into_string()
+                .map(|string| vec![("string_index", string.string_index)]),
+            Tag::ConstantFieldRef => self.try_cast_into_field_ref().map(|field_ref| {
+                vec![
+                    ("class_index", field_ref.class_index),
+                    ("name_and_type_index", field_ref.name_and_type_index),
+                ]
+            }),
+            Tag::ConstantMethodRef => self.try_cast_into_method_ref().map(|method_ref| {
+                vec![
+                    ("class_index", method_ref.class_index),
+                    ("name_and_type_index", method_ref.name_and_type_index),
+                ]
+            }),
+            Tag::ConstantInterfaceMethodRef => {
+                self.try_cast_into_interface_method_ref().map(|method_ref| {
+                    vec![
+                        ("class_index", method_ref.class_index),
+                        ("name_and_type_index", method_ref.name_and_type_index),
+                    ]
+                })
+            }
+            Tag::ConstantNameAndType => self.try_cast_into_name_and_type().map(|name_and_type| {
+                vec![
+                    ("name_index", name_and_type.name_index),
+                    ("descriptor_index", name_and_type.descriptor_index),
+                ]
+            }),
+            Tag::ConstantMethodHandle => self
+                .try_cast_into_method_handle()
+                .map(|method_handle| vec![("reference_index", method_handle.reference_index)]),
+            Tag::ConstantMethodType => self
+                .try_cast_into_method_type()
+                .map(|method_type| vec![("descriptor_index", method_type.descriptor_index)]),
+            Tag::ConstantDynamic => self
+                .try_cast_into_dynamic()
+                .map(|dynamic| vec![("name_and_type_index", dynamic.name_and_type_index)]),
+            Tag::ConstantInvokeDynamic => {
+                self.try_cast_into_invoke_dynamic().map(|invoke_dynamic| {
+                    vec![("name_and_type_index", invoke_dynamic.name_and_type_index)]
+                })
+            }
+            Tag::ConstantModule => self
+                .try_cast_into_module()
+                .map(|module| vec![("name_index", module.name_index)]),
+            Tag::ConstantPackage => self
+                .try_cast_into_package()
+                .map(|package| vec![("name_index", package.name_index)]),
+            _ => None,
+        };
+
+        references.unwrap_or_default()
     }
 }
 
@@ -543,7 +685,21 @@ impl ConstantPoolInfo {
 pub struct ConstantUtf8Info {
     pub constant_pool_index: u16,
     pub length: u16,
+    /// Decoded string, invalid byte sequences have been replaced with U+FFFD
     pub string: String,
+    /// Bytes exactly as stored in the class file, which are kept because `string` may be lossy
+    pub raw: Vec<u8>,
+}
+
+impl ConstantUtf8Info {
+    /// Check whether decoding replaced invalid byte sequences, in which case `string` does not
+    /// represent `raw` exactly
+    ///
+    /// Obfuscators emit malformed strings on purpose, but the compiler's modified UTF-8 encoding of
+    /// `\0` and of characters outside the Basic Multilingual Plane is reported as lossy as well
+    pub fn is_lossy(&self) -> bool {
+        self.string.as_bytes() != self.raw.as_slice()
+    }
 }
 
 impl ConstantPoolInfoData for ConstantUtf8Info {
@@ -752,3 +908,85 @@ impl ConstantPoolInfoData for ConstantPackageInfo {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ConstantPoolInfo, ConstantUtf8Info, MethodHandleType, Tag};
+    use crate::byte_reader::ByteReader;
+
+    fn utf8_entry(tag: Tag) -> ConstantPoolInfo {
+        ConstantPoolInfo {
+            tag,
+            data: Box::new(ConstantUtf8Info {
+                constant_pool_index: 1,
+                length: 1,
+                string: String::from("A"),
+                raw: vec![b'A'],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_cast_matching_tag() {
+        let entry = utf8_entry(Tag::ConstantUtf8);
+
+        assert_eq!(entry.try_cast_into_utf8().unwrap().string, "A");
+        assert!(
+            entry.try_cast_into_class().is_none(),
+            "Casting into another entry type is not an error"
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "does not match its tag")]
+    fn test_cast_mismatched_tag() {
+        utf8_entry(Tag::ConstantClass).try_cast_into_utf8();
+    }
+
+    #[test]
+    fn test_lossy_utf8() {
+        let read = |bytes: &[u8]| {
+            let mut data = vec![0x01, 0x00, bytes.len() as u8];
+            data.extend_from_slice(bytes);
+
+            ConstantPoolInfo::new(&mut ByteReader::from_bytes(&data), 1).unwrap()
+        };
+
+        let valid = read("caf\u{e9}".as_bytes());
+        assert!(!valid.try_cast_into_utf8().unwrap().is_lossy());
+
+        // An overlong encoding, as emitted by obfuscators
+        let malformed = read(&[b'a', 0xc0, 0xaf]);
+        let utf8 = malformed.try_cast_into_utf8().unwrap();
+
+        assert!(utf8.is_lossy());
+        assert_eq!(utf8.string, "a\u{fffd}\u{fffd}");
+        assert_eq!(
+            utf8.raw,
+            vec![b'a', 0xc0, 0xaf],
+            "The original bytes are kept"
+        );
+    }
+
+    #[test]
+    fn test_method_handle_type() {
+        for kind in 1..=9 {
+            assert_eq!(MethodHandleType::from_kind(&kind).unwrap().kind(), kind);
+        }
+
+        assert_eq!(
+            MethodHandleType::RefInvokeStatic.to_string(),
+            "invokestatic"
+        );
+        assert_eq!(MethodHandleType::RefGetField.to_string(), "getfield");
+        assert_eq!(
+            MethodHandleType::RefNewInvokeSpecial.to_str(),
+            "newinvokespecial"
+        );
+        assert_eq!(
+            MethodHandleType::RefInvokeInterface.spec_name(),
+            "REF_invokeInterface"
+        );
+    }
+}
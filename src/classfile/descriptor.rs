@@ -0,0 +1,294 @@
+//! Provides functionality to parse field and method descriptors
+//!
+//! Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.3
+
+use std::fmt;
+
+use super::ClassNameForm;
+
+/// Type of a field, parameter, local variable, or value
+#[derive(Debug, PartialEq)]
+pub enum FieldType {
+    /// `B` - signed byte
+    Byte,
+
+    /// `C` - Unicode character code point in the Basic Multilingual Plane, encoded with UTF-16
+    Char,
+
+    /// `D` - double-precision floating-point value
+    Double,
+
+    /// `F` - single-precision floating-point value
+    Float,
+
+    /// `I` - integer
+    Int,
+
+    /// `J` - long integer
+    Long,
+
+    /// `S` - signed short
+    Short,
+
+    /// `Z` - `true` or `false`
+    Boolean,
+
+    /// `L ClassName ;` - an instance of the class with the specified binary name in internal form
+    Object(String),
+
+    /// `[` - one array dimension of the component type
+    Array(Box<FieldType>),
+}
+
+impl FieldType {
+    /// Number of local variable slots occupied by a value of this type
+    ///
+    /// Values of type `long` and `double` occupy two slots, all other values occupy a single slot
+    pub fn slots(&self) -> u16 {
+        match self {
+            Self::Long | Self::Double => 2,
+            _ => 1,
+        }
+    }
+
+    /// Render the type the way it is written in the Java programming language, with class names
+    /// in the specified form, e.g. `java/lang/String[]` for internal names
+    pub fn to_java_string(&self, names: ClassNameForm) -> String {
+        match self {
+            Self::Object(class_name) => names.apply(class_name),
+            Self::Array(component_type) => format!("{}[]", component_type.to_java_string(names)),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    /// Render the type the way it is written in the Java programming language, e.g. `java.lang.String[]`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Byte => write!(f, "byte"),
+            Self::Char => write!(f, "char"),
+            Self::Double => write!(f, "double"),
+            Self::Float => write!(f, "float"),
+            Self::Int => write!(f, "int"),
+            Self::Long => write!(f, "long"),
+            Self::Short => write!(f, "short"),
+            Self::Boolean => write!(f, "boolean"),
+            Self::Object(class_name) => write!(f, "{}", class_name.replace('/', ".")),
+            Self::Array(component_type) => write!(f, "{}[]", component_type),
+        }
+    }
+}
+
+/// Parameters and return type of a method
+#[derive(Debug, PartialEq)]
+pub struct MethodDescriptor {
+    /// Types of the parameters in declaration order
+    pub parameters: Vec<FieldType>,
+
+    /// Type of the returned value, `None` if the method returns `void`
+    pub return_type: Option<FieldType>,
+}
+
+impl MethodDescriptor {
+    /// Number of local variable slots occupied by the method's arguments when it is invoked
+    ///
+    /// Instance methods receive a reference to the object they were invoked on in slot zero.
+    /// Returns `None` if the count does not fit in a `u16`, a valid descriptor never needs more
+    /// than 255 slots.
+    pub fn arg_slots(&self, is_static: bool) -> Option<u16> {
+        let this_slots = if is_static { 0 } else { 1 };
+
+        self.parameters
+            .iter()
+            .try_fold(this_slots, |slots: u16, parameter| {
+                slots.checked_add(parameter.slots())
+            })
+    }
+
+    /// Consume the descriptor into all types it mentions, the parameters followed by the return
+    /// type unless the method returns `void`
+    pub fn types(self) -> impl Iterator<Item = FieldType> {
+        self.parameters.into_iter().chain(self.return_type)
+    }
+}
+
+/// Count the local variable slots occupied by the arguments of a method with the specified
+/// descriptor, returns `None` if the descriptor is malformed or the count does not fit in a `u16`
+pub fn method_arg_slots(descriptor: &str, is_static: bool) -> Option<u16> {
+    parse_method_descriptor(descriptor).and_then(|descriptor| descriptor.arg_slots(is_static))
+}
+
+/// Parse a field descriptor, returns `None` if the descriptor is malformed
+pub fn parse_field_descriptor(descriptor: &str) -> Option<FieldType> {
+    let mut chars = descriptor.chars().peekable();
+    let field_type = read_field_type(&mut chars)?;
+
+    if chars.peek().is_some() {
+        return None;
+    }
+
+    Some(field_type)
+}
+
+/// Parse a method descriptor, returns `None` if the descriptor is malformed
+pub fn parse_method_descriptor(descriptor: &str) -> Option<MethodDescriptor> {
+    let mut chars = descriptor.chars().peekable();
+
+    if chars.next()? != '(' {
+        return None;
+    }
+
+    let mut parameters = vec![];
+    while *chars.peek()? != ')' {
+        parameters.push(read_field_type(&mut chars)?);
+    }
+
+    // Skip the closing parenthesis
+    chars.next();
+
+    let return_type = if chars.peek() == Some(&'V') {
+        chars.next();
+        None
+    } else {
+        Some(read_field_type(&mut chars)?)
+    };
+
+    if chars.peek().is_some() {
+        return None;
+    }
+
+    Some(MethodDescriptor {
+        parameters,
+        return_type,
+    })
+}
+
+/// Read a single field type from the descriptor characters
+fn read_field_type(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<FieldType> {
+    let field_type = match chars.next()? {
+        'B' => FieldType::Byte,
+        'C' => FieldType::Char,
+        'D' => FieldType::Double,
+        'F' => FieldType::Float,
+        'I' => FieldType::Int,
+        'J' => FieldType::Long,
+        'S' => FieldType::Short,
+        'Z' => FieldType::Boolean,
+        'L' => {
+            let mut class_name = String::new();
+
+            loop {
+                match chars.next()? {
+                    ';' => break,
+                    character => class_name.push(character),
+                }
+            }
+
+            if class_name.is_empty() {
+                return None;
+            }
+
+            FieldType::Object(class_name)
+        }
+        '[' => FieldType::Array(Box::new(read_field_type(chars)?)),
+        _ => return None,
+    };
+
+    Some(field_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        method_arg_slots, parse_field_descriptor, parse_method_descriptor, FieldType,
+        MethodDescriptor,
+    };
+
+    #[test]
+    fn test_parse_field_descriptor() {
+        assert_eq!(parse_field_descriptor("I"), Some(FieldType::Int));
+        assert_eq!(
+            parse_field_descriptor("Ljava/lang/Object;"),
+            Some(FieldType::Object(String::from("java/lang/Object")))
+        );
+        assert_eq!(
+            parse_field_descriptor("[[D"),
+            Some(FieldType::Array(Box::new(FieldType::Array(Box::new(
+                FieldType::Double
+            )))))
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_field_descriptor() {
+        assert_eq!(parse_field_descriptor(""), None);
+        assert_eq!(parse_field_descriptor("V"), None);
+        assert_eq!(parse_field_descriptor("II"), None);
+        assert_eq!(parse_field_descriptor("Ljava/lang/Object"), None);
+        assert_eq!(parse_field_descriptor("L;"), None);
+    }
+
+    #[test]
+    fn test_parse_method_descriptor() {
+        assert_eq!(
+            parse_method_descriptor("(IDLjava/lang/Thread;)Ljava/lang/Object;"),
+            Some(MethodDescriptor {
+                parameters: vec![
+                    FieldType::Int,
+                    FieldType::Double,
+                    FieldType::Object(String::from("java/lang/Thread"))
+                ],
+                return_type: Some(FieldType::Object(String::from("java/lang/Object"))),
+            })
+        );
+        assert_eq!(
+            parse_method_descriptor("()V"),
+            Some(MethodDescriptor {
+                parameters: vec![],
+                return_type: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_display_field_type() {
+        assert_eq!(FieldType::Boolean.to_string(), "boolean");
+        assert_eq!(
+            parse_field_descriptor("[[Ljava/lang/String;")
+                .unwrap()
+                .to_string(),
+            "java.lang.String[][]"
+        );
+    }
+
+    #[test]
+    fn test_method_arg_slots() {
+        assert_eq!(method_arg_slots("()V", true), Some(0));
+        assert_eq!(method_arg_slots("()V", false), Some(1));
+        assert_eq!(
+            method_arg_slots("(IJ[DLjava/lang/Object;D)V", true),
+            Some(7)
+        );
+        assert_eq!(
+            method_arg_slots("(IJ[DLjava/lang/Object;D)V", false),
+            Some(8)
+        );
+        assert_eq!(method_arg_slots("(J", true), None);
+
+        let oversized = format!("({})V", "J".repeat(40_000));
+        assert_eq!(
+            method_arg_slots(&oversized, true),
+            None,
+            "80000 slots do not fit in a u16"
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed_method_descriptor() {
+        assert_eq!(parse_method_descriptor("V"), None);
+        assert_eq!(parse_method_descriptor("(I"), None);
+        assert_eq!(parse_method_descriptor("(V)V"), None);
+        assert_eq!(parse_method_descriptor("()VV"), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Compares the structure of two class files
+//!
+//! Members are matched by their name and descriptor, which is how the JVM links them. A member
+//! that changed its descriptor therefore shows up as one removed and one added member.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::flags::{ClassAccessFlags, FieldAccessFlags, MethodAccessFlags};
+
+use super::{resolve_utf8, ClassFile, ConstantPoolContainer};
+
+/// Describes how a single field or method differs between two class files
+#[derive(Debug, PartialEq)]
+pub enum MemberChange<F> {
+    /// The member only exists in the second class file
+    Added { name: String, descriptor: String },
+
+    /// The member only exists in the first class file
+    Removed { name: String, descriptor: String },
+
+    /// The member exists in both class files, but its access flags differ
+    AccessFlagsChanged {
+        name: String,
+        descriptor: String,
+        old: Vec<F>,
+        new: Vec<F>,
+    },
+}
+
+/// Structural differences between two class files
+#[derive(Debug, PartialEq)]
+pub struct ClassDiff {
+    /// Old and new (major, minor) version, if the version changed
+    pub version: Option<((u16, u16), (u16, u16))>,
+
+    /// Old and new class access flags, if the access flags changed
+    pub access_flags: Option<(Vec<ClassAccessFlags>, Vec<ClassAccessFlags>)>,
+
+    /// Changed fields, ordered by name and descriptor
+    pub fields: Vec<MemberChange<FieldAccessFlags>>,
+
+    /// Changed methods, ordered by name and descriptor
+    pub methods: Vec<MemberChange<MethodAccessFlags>>,
+}
+
+impl ClassDiff {
+    /// Check whether both class files are structurally identical
+    pub fn is_empty(&self) -> bool {
+        self.version.is_none()
+            && self.access_flags.is_none()
+            && self.fields.is_empty()
+            && self.methods.is_empty()
+    }
+}
+
+impl fmt::Display for ClassDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(((old_major, old_minor), (new_major, new_minor))) = self.version {
+            writeln!(
+                f,
+                "Version: {}.{} -> {}.{}",
+                old_major, old_minor, new_major, new_minor
+            )?;
+        }
+
+        if let Some((old, new)) = &self.access_flags {
+            writeln!(f, "Access flags: {:?} -> {:?}", old, new)?;
+        }
+
+        write_member_changes(f, "Fields", &self.fields)?;
+        write_member_changes(f, "Methods", &self.methods)
+    }
+}
+
+/// Compare two class files and report what changed from `old` to `new`
+pub fn diff_class_files(old: &ClassFile, new: &ClassFile) -> ClassDiff {
+    let old_version = (old.major_version, old.minor_version);
+    let new_version = (new.major_version, new.minor_version);
+
+    ClassDiff {
+        version: if old_version != new_version {
+            Some((old_version, new_version))
+        } else {
+            None
+        },
+        access_flags: if old.access_flags != new.access_flags {
+            Some((old.access_flags.clone(), new.access_flags.clone()))
+        } else {
+            None
+        },
+        fields: diff_members(
+            members(
+                old,
+                old.fields.iter().map(|field| {
+                    (
+                        field.name_index,
+                        field.descriptor_index,
+                        &field.access_flags,
+                    )
+                }),
+            ),
+            members(
+                new,
+                new.fields.iter().map(|field| {
+                    (
+                        field.name_index,
+                        field.descriptor_index,
+                        &field.access_flags,
+                    )
+                }),
+            ),
+        ),
+        methods: diff_members(
+            members(
+                old,
+                old.methods.iter().map(|method| {
+                    (
+                        method.name_index,
+                        method.descriptor_index,
+                        &method.access_flags,
+                    )
+                }),
+            ),
+            members(
+                new,
+                new.methods.iter().map(|method| {
+                    (
+                        method.name_index,
+                        method.descriptor_index,
+                        &method.access_flags,
+                    )
+                }),
+            ),
+        ),
+    }
+}
+
+/// Key members by their name and descriptor
+fn members<'a, F: 'a>(
+    class: &'a ClassFile,
+    members: impl Iterator<Item = (u16, u16, &'a Vec<F>)>,
+) -> BTreeMap<(&'a str, &'a str), &'a Vec<F>> {
+    members
+        .map(|(name_index, descriptor_index, access_flags)| {
+            (
+                (
+                    utf8_or_invalid(&class.constant_pool, name_index),
+                    utf8_or_invalid(&class.constant_pool, descriptor_index),
+                ),
+                access_flags,
+            )
+        })
+        .collect()
+}
+
+/// Compare two sets of members keyed by name and descriptor
+fn diff_members<F: Clone + PartialEq>(
+    old: BTreeMap<(&str, &str), &Vec<F>>,
+    new: BTreeMap<(&str, &str), &Vec<F>>,
+) -> Vec<MemberChange<F>> {
+    let mut keys: Vec<&(&str, &str)> = old.keys().chain(new.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let (name, descriptor) = (key.0.to_owned(), key.1.to_owned());
+
+            match (old.get(key), new.get(key)) {
+                (Some(_), None) => Some(MemberChange::Removed { name, descriptor }),
+                (None, Some(_)) => Some(MemberChange::Added { name, descriptor }),
+                (Some(old), Some(new)) if old != new => Some(MemberChange::AccessFlagsChanged {
+                    name,
+                    descriptor,
+                    old: old.to_vec(),
+                    new: new.to_vec(),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Fetch a UTF-8 constant pool entry, invalid indices still need a stable key
+fn utf8_or_invalid(constant_pool: &ConstantPoolContainer, index: u16) -> &str {
+    resolve_utf8(constant_pool, index).unwrap_or("<invalid>")
+}
+
+/// Render a section of member changes, nothing is written if there are no changes
+fn write_member_changes<F: fmt::Debug>(
+    f: &mut fmt::Formatter<'_>,
+    title: &str,
+    changes: &[MemberChange<F>],
+) -> fmt::Result {
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(f, "{}:", title)?;
+
+    for change in changes {
+        match change {
+            MemberChange::Added { name, descriptor } => writeln!(f, "\t+ {}:{}", name, descriptor)?,
+            MemberChange::Removed { name, descriptor } => {
+                writeln!(f, "\t- {}:{}", name, descriptor)?
+            }
+            MemberChange::AccessFlagsChanged {
+                name,
+                descriptor,
+                old,
+                new,
+            } => writeln!(f, "\t~ {}:{} {:?} -> {:?}", name, descriptor, old, new)?,
+        }
+    }
+
+    Ok(())
+}
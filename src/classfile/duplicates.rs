@@ -0,0 +1,164 @@
+//! Finds redundant entries in the constant pool
+//!
+//! A class writer is expected to store every constant only once. Duplicates do not make a class
+//! file invalid, but they are a sign of a class writer that does not reuse its entries.
+
+use std::collections::BTreeMap;
+
+use super::{ClassFile, ConstantPoolInfo, Tag};
+
+/// Value of a constant pool entry, in a form that can be compared and ordered
+///
+/// Entries are compared by their encoded value, so references are equal when they point at the
+/// same indices. Floating point values are compared by their bits, which is what makes two `NaN`
+/// constants with the same encoding equal.
+#[derive(Eq, Ord, PartialEq, PartialOrd)]
+enum ConstantValue<'a> {
+    Utf8(&'a str),
+    Integer(i32),
+    Float(u32),
+    Long(i64),
+    Double(u64),
+    Class(u16),
+    String(u16),
+    FieldRef(u16, u16),
+    MethodRef(u16, u16),
+    InterfaceMethodRef(u16, u16),
+    NameAndType(u16, u16),
+    MethodHandle(u8, u16),
+    MethodType(u16),
+    Dynamic(u16, u16),
+    InvokeDynamic(u16, u16),
+    Module(u16),
+    Package(u16),
+}
+
+impl<'a> ConstantValue<'a> {
+    /// Extract the value of a constant pool entry
+    fn of(entry: &'a ConstantPoolInfo) -> Self {
+        match entry.tag {
+            Tag::ConstantUtf8 => Self::Utf8(&entry.try_cast_into_utf8().unwrap().string),
+            Tag::ConstantInteger => Self::Integer(entry.try_cast_into_integer().unwrap().value),
+            Tag::ConstantFloat => Self::Float(entry.try_cast_into_float().unwrap().value.to_bits()),
+            Tag::ConstantLong => Self::Long(entry.try_cast_into_long().unwrap().value),
+            Tag::ConstantDouble => {
+                Self::Double(entry.try_cast_into_double().unwrap().value.to_bits())
+            }
+            Tag::ConstantClass => Self::Class(entry.try_cast_into_class().unwrap().name_index),
+            Tag::ConstantString => Self::String(entry.try_cast_into_string().unwrap().string_index),
+            Tag::ConstantFieldRef => {
+                let concrete = entry.try_cast_into_field_ref().unwrap();
+                Self::FieldRef(concrete.class_index, concrete.name_and_type_index)
+            }
+            Tag::ConstantMethodRef => {
+                let concrete = entry.try_cast_into_method_ref().unwrap();
+                Self::MethodRef(concrete.class_index, concrete.name_and_type_index)
+            }
+            Tag::ConstantInterfaceMethodRef => {
+                let concrete = entry.try_cast_into_interface_method_ref().unwrap();
+                Self::InterfaceMethodRef(concrete.class_index, concrete.name_and_type_index)
+            }
+            Tag::ConstantNameAndType => {
+                let concrete = entry.try_cast_into_name_and_type().unwrap();
+                Self::NameAndType(concrete.name_index, concrete.descriptor_index)
+            }
+            Tag::ConstantMethodHandle => {
+                let concrete = entry.try_cast_into_method_handle().unwrap();
+                Self::MethodHandle(concrete.reference_kind.kind(), concrete.reference_index)
+            }
+            Tag::ConstantMethodType => {
+                Self::MethodType(entry.try_cast_into_method_type().unwrap().descriptor_index)
+            }
+            Tag::ConstantDynamic => {
+                let concrete = entry.try_cast_into_dynamic().unwrap();
+                Self::Dynamic(
+                    concrete.bootstrap_method_attr_index,
+                    concrete.name_and_type_index,
+                )
+            }
+            Tag::ConstantInvokeDynamic => {
+                let concrete = entry.try_cast_into_invoke_dynamic().unwrap();
+                Self::InvokeDynamic(
+                    concrete.bootstrap_method_attr_index,
+                    concrete.name_and_type_index,
+                )
+            }
+            Tag::ConstantModule => Self::Module(entry.try_cast_into_module().unwrap().name_index),
+            Tag::ConstantPackage => {
+                Self::Package(entry.try_cast_into_package().unwrap().name_index)
+            }
+        }
+    }
+}
+
+/// Group the indices of constant pool entries that hold the same value
+///
+/// Only values that occur more than once are reported. Each group is sorted, and the groups are
+/// ordered by the index of their first entry.
+pub fn find_duplicate_constants(class: &ClassFile) -> Vec<Vec<u16>> {
+    let mut groups: BTreeMap<ConstantValue, Vec<u16>> = BTreeMap::new();
+
+    // The constant pool is ordered by index, so every group is sorted as well
+    for (index, entry) in &class.constant_pool {
+        groups
+            .entry(ConstantValue::of(entry))
+            .or_default()
+            .push(*index);
+    }
+
+    let mut duplicates: Vec<Vec<u16>> = groups
+        .into_values()
+        .filter(|indices| indices.len() > 1)
+        .collect();
+
+    duplicates.sort();
+    duplicates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::find_duplicate_constants;
+    use crate::byte_reader::ByteReader;
+    use crate::classfile::{ClassFile, ClassFileConfig};
+
+    #[test]
+    fn test_find_duplicate_constants() {
+        #[rustfmt::skip]
+        let input = [
+            0xCA, 0xFE, 0xBA, 0xBE,             // Magic
+            0x00, 0x00, 0x00, 0x3D,             // Version 61.0
+            0x00, 0x07,                         // Constant pool count
+            0x01, 0x00, 0x01, 0x41,             // #1 = Utf8 "A"
+            0x07, 0x00, 0x01,                   // #2 = Class #1
+            0x01, 0x00, 0x01, 0x42,             // #3 = Utf8 "B"
+            0x01, 0x00, 0x01, 0x41,             // #4 = Utf8 "A"
+            0x07, 0x00, 0x01,                   // #5 = Class #1
+            0x07, 0x00, 0x04,                   // #6 = Class #4, same name but another entry
+            0x00, 0x21,                         // Access flags
+            0x00, 0x02,                         // This class
+            0x00, 0x00,                         // No super class
+            0x00, 0x00,                         // Interfaces count
+            0x00, 0x00,                         // Fields count
+            0x00, 0x00,                         // Methods count
+            0x00, 0x00,                         // Attributes count
+        ];
+        let class =
+            ClassFile::new(&mut ByteReader::from_bytes(&input), &ClassFileConfig::new()).unwrap();
+
+        assert_eq!(
+            find_duplicate_constants(&class),
+            vec![vec![1, 4], vec![2, 5]]
+        );
+    }
+
+    #[test]
+    fn test_no_duplicates_in_javac_output() {
+        let class = ClassFile::new(
+            &mut ByteReader::from_bytes(include_bytes!("../../tests/fixtures/Numbers.class")),
+            &ClassFileConfig::new(),
+        )
+        .unwrap();
+
+        assert!(find_duplicate_constants(&class).is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+//! Errors that may occur while parsing a class file
+
+use std::fmt;
+
+use crate::byte_reader::ByteReaderError;
+use crate::bytecode::DecodeError;
+
+use super::Tag;
+
+/// Describes why a class file could not be parsed
+#[derive(Debug, PartialEq)]
+pub enum ClassFileError {
+    /// The input ended early or could not be read
+    Read(ByteReaderError),
+
+    /// A constant pool entry is missing or not of the type its referrer expects
+    Pool(PoolError),
+
+    /// The input is too small to contain even the fixed-size start of a class file
+    TooShort { length: usize, min_length: usize },
+
+    /// Attributes are nested deeper than the configured maximum depth
+    AttributeNestingTooDeep { max_depth: u16, offset: usize },
+
+    /// Element values of annotations are nested deeper than the configured maximum depth
+    AnnotationNestingTooDeep { max_depth: u16, offset: usize },
+
+    /// The input does not start with the class file magic number (0xCAFEBABE)
+    InvalidMagic { found: u32 },
+
+    /// A constant pool entry starts with a tag that is not defined by the specification
+    UnknownConstantTag { tag: u8, offset: usize },
+
+    /// A method handle constant uses a reference kind that is not defined by the specification
+    UnknownMethodHandleKind { kind: u8, offset: usize },
+
+    /// An annotation element value starts with a tag that is not defined by the specification
+    UnknownElementValueTag { tag: u8, offset: usize },
+
+    /// A type annotation has a target type that is not defined by the specification
+    UnknownTypeAnnotationTarget { target_type: u8, offset: usize },
+
+    /// A fixed-size attribute declares a length that does not match its layout
+    InvalidAttributeLength {
+        name: &'static str,
+        length: u32,
+        expected: u32,
+        offset: usize,
+    },
+
+    /// The constant pool count is zero, which is illegal because it is one larger than the number
+    /// of entries
+    InvalidConstantPoolCount { offset: usize },
+
+    /// The constant pool has no entries, so the class cannot refer to itself through `this_class`
+    EmptyConstantPool,
+
+    /// The class file's major version falls outside of the accepted range
+    UnsupportedVersion {
+        major_version: u16,
+        min_major_version: u16,
+        max_major_version: u16,
+        offset: usize,
+    },
+
+    /// The code of a method cannot be decoded into instructions
+    InvalidCode { method: String, error: DecodeError },
+}
+
+impl fmt::Display for ClassFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(error) => write!(f, "{}", error),
+            Self::Pool(error) => write!(f, "{}", error),
+            Self::AttributeNestingTooDeep { max_depth, offset } => write!(
+                f,
+                "attributes are nested more than {} levels deep at offset {:#X}",
+                max_depth, offset
+            ),
+            Self::AnnotationNestingTooDeep { max_depth, offset } => write!(
+                f,
+                "annotation element values are nested more than {} levels deep at offset {:#X}",
+                max_depth, offset
+            ),
+            Self::TooShort { length, min_length } => write!(
+                f,
+                "input is {} bytes long, but a class file is at least {} bytes long (is this a class file?)",
+                length, min_length
+            ),
+            Self::InvalidMagic { found } => write!(
+                f,
+                "invalid magic number {:#010X}, expected 0xCAFEBABE (is this a class file?)",
+                found
+            ),
+            Self::UnknownConstantTag { tag, offset } => {
+                write!(f, "unknown constant pool tag {} at offset {:#X}", tag, offset)
+            }
+            Self::UnknownMethodHandleKind { kind, offset } => write!(
+                f,
+                "unknown method handle reference kind {} at offset {:#X}",
+                kind, offset
+            ),
+            Self::UnknownElementValueTag { tag, offset } => write!(
+                f,
+                "unknown annotation element value tag {:#04X} at offset {:#X}",
+                tag, offset
+            ),
+            Self::UnknownTypeAnnotationTarget {
+                target_type,
+                offset,
+            } => write!(
+                f,
+                "unknown type annotation target type {:#04X} at offset {:#X}",
+                target_type, offset
+            ),
+            Self::InvalidAttributeLength {
+                name,
+                length,
+                expected,
+                offset,
+            } => write!(
+                f,
+                "{} attribute at offset {:#X} is {} bytes long, expected {}",
+                name, offset, length, expected
+            ),
+            Self::InvalidConstantPoolCount { offset } => write!(
+                f,
+                "constant pool count at offset {:#X} is 0, but it must be at least 1",
+                offset
+            ),
+            Self::EmptyConstantPool => write!(
+                f,
+                "the constant pool is empty, but this_class must refer to a class entry in it"
+            ),
+            Self::UnsupportedVersion {
+                major_version,
+                min_major_version,
+                max_major_version,
+                offset,
+            } => write!(
+                f,
+                "unsupported class file major version {} at offset {:#X} (accepted range is {}..={})",
+                major_version, offset, min_major_version, max_major_version
+            ),
+            Self::InvalidCode { method, error } => write!(f, "method {}: {}", method, error),
+        }
+    }
+}
+
+impl std::error::Error for ClassFileError {}
+
+impl From<ByteReaderError> for ClassFileError {
+    fn from(error: ByteReaderError) -> Self {
+        Self::Read(error)
+    }
+}
+
+impl From<PoolError> for ClassFileError {
+    fn from(error: PoolError) -> Self {
+        Self::Pool(error)
+    }
+}
+
+/// Describes why a reference between constant pool entries could not be followed
+#[derive(Debug, PartialEq)]
+pub enum PoolError {
+    /// There is no entry at the referenced index
+    MissingEntry { index: u16 },
+
+    /// The entry at the referenced index is not of the expected type
+    WrongType {
+        index: u16,
+        expected: &'static str,
+        found: Tag,
+    },
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingEntry { index } => {
+                write!(
+                    f,
+                    "constant pool index {} does not refer to an entry",
+                    index
+                )
+            }
+            Self::WrongType {
+                index,
+                expected,
+                found,
+            } => write!(
+                f,
+                "constant pool index {} expected {}, found {}",
+                index,
+                expected,
+                found.name()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PoolError {}
@@ -2,18 +2,18 @@
 //!
 //! Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.5
 
-use crate::{
-    byte_reader::ByteReader,
-    utils::to_u16,
-};
+use crate::byte_reader::ByteReader;
 use crate::flags::{FieldAccessFlags, Flags};
 
-use super::AttributeInfo;
-use super::ConstantPoolContainer;
+use super::{parse_field_descriptor, resolve_signature, resolve_utf8, FieldType};
+use super::{AttributeConstantValue, AttributeInfo, AttributeType};
+use super::{ClassFileError, ConstantPoolContainer};
 
 /// Represents a field on a class or interface
 pub struct FieldInfo {
     pub access_flags: Vec<FieldAccessFlags>,
+    /// Access flags exactly as stored in the class file, including bits without a known flag
+    pub access_flags_raw: u16,
     pub name_index: u16,
     pub descriptor_index: u16,
     pub attributes: Vec<AttributeInfo>,
@@ -21,38 +21,94 @@ pub struct FieldInfo {
 
 impl FieldInfo {
     /// Create a new field from a class file binary blob
-    pub fn new(reader: &mut ByteReader, constant_pool: &ConstantPoolContainer) -> Self {
-        let access_flags = Self::read_access_flags(reader);
-        let name_index = to_u16(&reader.read_n_bytes(2));
-        let descriptor_index = to_u16(&reader.read_n_bytes(2));
-        let attributes = Self::read_attributes(reader, constant_pool);
+    ///
+    /// Attributes nested deeper than the maximum attribute depth, and annotation element values
+    /// nested deeper than the maximum annotation depth, are rejected
+    pub fn new(
+        reader: &mut ByteReader,
+        constant_pool: &ConstantPoolContainer,
+        max_attribute_depth: u16,
+        max_annotation_depth: u16,
+    ) -> Result<Self, ClassFileError> {
+        let access_flags_raw = reader.read_u16()?;
+        let access_flags = FieldAccessFlags::from_u16(access_flags_raw);
+        let name_index = reader.read_u16()?;
+        let descriptor_index = reader.read_u16()?;
+        let attributes = Self::read_attributes(
+            reader,
+            constant_pool,
+            max_attribute_depth,
+            max_annotation_depth,
+        )?;
 
-        Self {
+        Ok(Self {
             access_flags,
+            access_flags_raw,
             name_index,
             descriptor_index,
             attributes,
-        }
+        })
+    }
+
+    /// Fetch the generic signature of this field, if it has one
+    pub fn signature<'a>(&self, constant_pool: &'a ConstantPoolContainer) -> Option<&'a str> {
+        resolve_signature(&self.attributes, constant_pool)
+    }
+
+    /// Parse the descriptor of this field into its type
+    ///
+    /// Returns `None` if the descriptor index does not refer to a valid field descriptor
+    pub fn descriptor(&self, constant_pool: &ConstantPoolContainer) -> Option<FieldType> {
+        resolve_utf8(constant_pool, self.descriptor_index).and_then(parse_field_descriptor)
+    }
+
+    /// Fetch the constant value attribute of this field, only constant fields have one
+    ///
+    /// The value initializes a static field, e.g. `static final int VERSION = 3`
+    pub fn constant_value(&self) -> Option<&AttributeConstantValue> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.attribute_type == AttributeType::ConstantValue)
+            .and_then(|attribute| attribute.try_cast_into_constant_value())
+    }
+
+    /// Check whether this field has been marked as deprecated
+    pub fn is_deprecated(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.attribute_type == AttributeType::Deprecated)
     }
 
-    /// Read field access flags
-    fn read_access_flags(reader: &mut ByteReader) -> Vec<FieldAccessFlags> {
-        let bitmask = to_u16(&reader.read_n_bytes(2));
-        FieldAccessFlags::from_u16(bitmask)
+    /// Check whether this field was generated by the compiler, either through its access flags or
+    /// through the legacy synthetic attribute
+    pub fn is_synthetic(&self) -> bool {
+        self.access_flags.contains(&FieldAccessFlags::AccSynthetic)
+            || self
+                .attributes
+                .iter()
+                .any(|attribute| attribute.attribute_type == AttributeType::Synthetic)
     }
 
     /// Read field attributes
     fn read_attributes(
         reader: &mut ByteReader,
         constant_pool: &ConstantPoolContainer,
-    ) -> Vec<AttributeInfo> {
-        let attributes_count = to_u16(&reader.read_n_bytes(2));
+        max_attribute_depth: u16,
+        max_annotation_depth: u16,
+    ) -> Result<Vec<AttributeInfo>, ClassFileError> {
+        let attributes_count = reader.read_count(6)?;
         let mut attributes = vec![];
 
         for _ in 0..attributes_count {
-            attributes.push(AttributeInfo::new(reader, constant_pool));
+            attributes.push(AttributeInfo::new(
+                reader,
+                constant_pool,
+                1,
+                max_attribute_depth,
+                max_annotation_depth,
+            )?);
         }
 
-        attributes
+        Ok(attributes)
     }
 }
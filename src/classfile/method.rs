@@ -2,18 +2,21 @@
 //!
 //! Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.6
 
+use crate::flags::{Flags, MethodAccessFlags};
 use crate::{
     byte_reader::ByteReader,
-    utils::to_u16,
+    bytecode::{decode, DecodeError, Instruction},
 };
-use crate::flags::{Flags, MethodAccessFlags};
 
-use super::AttributeInfo;
-use super::ConstantPoolContainer;
+use super::{parse_method_descriptor, resolve_signature, resolve_utf8, MethodDescriptor};
+use super::{AttributeCode, AttributeExceptions, AttributeInfo, AttributeType};
+use super::{ClassFileError, ConstantPoolContainer};
 
 /// Represents a method on a class or interface
 pub struct MethodInfo {
     pub access_flags: Vec<MethodAccessFlags>,
+    /// Access flags exactly as stored in the class file, including bits without a known flag
+    pub access_flags_raw: u16,
     pub name_index: u16,
     pub descriptor_index: u16,
     pub attributes: Vec<AttributeInfo>,
@@ -21,38 +24,129 @@ pub struct MethodInfo {
 
 impl MethodInfo {
     /// Create a new method from a class file binary blob
-    pub fn new(reader: &mut ByteReader, constant_pool: &ConstantPoolContainer) -> Self {
-        let access_flags = Self::read_access_flags(reader);
-        let name_index = to_u16(&reader.read_n_bytes(2));
-        let descriptor_index = to_u16(&reader.read_n_bytes(2));
-        let attributes = Self::read_attributes(reader, constant_pool);
+    ///
+    /// Attributes nested deeper than the maximum attribute depth, and annotation element values
+    /// nested deeper than the maximum annotation depth, are rejected
+    pub fn new(
+        reader: &mut ByteReader,
+        constant_pool: &ConstantPoolContainer,
+        max_attribute_depth: u16,
+        max_annotation_depth: u16,
+    ) -> Result<Self, ClassFileError> {
+        let access_flags_raw = reader.read_u16()?;
+        let access_flags = MethodAccessFlags::from_u16(access_flags_raw);
+        let name_index = reader.read_u16()?;
+        let descriptor_index = reader.read_u16()?;
+        let attributes = Self::read_attributes(
+            reader,
+            constant_pool,
+            max_attribute_depth,
+            max_annotation_depth,
+        )?;
 
-        Self {
+        Ok(Self {
             access_flags,
+            access_flags_raw,
             name_index,
             descriptor_index,
             attributes,
-        }
+        })
+    }
+
+    /// Fetch the generic signature of this method, if it has one
+    pub fn signature<'a>(&self, constant_pool: &'a ConstantPoolContainer) -> Option<&'a str> {
+        resolve_signature(&self.attributes, constant_pool)
+    }
+
+    /// Parse the descriptor of this method into its parameter and return types
+    ///
+    /// Returns `None` if the descriptor index does not refer to a valid method descriptor
+    pub fn descriptor(&self, constant_pool: &ConstantPoolContainer) -> Option<MethodDescriptor> {
+        resolve_utf8(constant_pool, self.descriptor_index).and_then(parse_method_descriptor)
+    }
+
+    /// Check whether this method has been marked as deprecated
+    pub fn is_deprecated(&self) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.attribute_type == AttributeType::Deprecated)
+    }
+
+    /// Check whether this method was generated by the compiler, either through its access flags
+    /// or through the legacy synthetic attribute
+    pub fn is_synthetic(&self) -> bool {
+        self.access_flags.contains(&MethodAccessFlags::AccSynthetic)
+            || self
+                .attributes
+                .iter()
+                .any(|attribute| attribute.attribute_type == AttributeType::Synthetic)
+    }
+
+    /// Check whether this method is a bridge method, which the compiler generates to forward calls
+    /// made through an erased signature, e.g. `compareTo(Object)` for a `Comparable<T>`
+    pub fn is_bridge(&self) -> bool {
+        self.access_flags.contains(&MethodAccessFlags::AccBridge)
+    }
+
+    /// Fetch the code attribute of this method, abstract and native methods do not have one
+    pub fn code(&self) -> Option<&AttributeCode> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.attribute_type == AttributeType::Code)
+            .and_then(|attribute| attribute.try_cast_into_code())
+    }
+
+    /// Fetch the exceptions attribute of this method, which lists the checked exceptions it throws
+    pub fn exceptions(&self) -> Option<&AttributeExceptions> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.attribute_type == AttributeType::Exceptions)
+            .and_then(|attribute| attribute.try_cast_into_exceptions())
+    }
+
+    /// Find the source line of the instruction at a bytecode offset
+    ///
+    /// A code attribute may have several line number tables, the entry with the greatest start
+    /// offset that does not exceed the offset wins. Returns `None` if there is no line number
+    /// information, or if the offset comes before the first entry.
+    pub fn source_line_for_offset(&self, offset: u16) -> Option<u16> {
+        self.code()?
+            .attributes
+            .iter()
+            .filter_map(|attribute| attribute.try_cast_into_line_number_table())
+            .filter_map(|table| table.entry_for_offset(offset))
+            .max_by_key(|entry| entry.start_pc)
+            .map(|entry| entry.line_number)
     }
 
-    /// Read field access flags
-    fn read_access_flags(reader: &mut ByteReader) -> Vec<MethodAccessFlags> {
-        let bitmask = to_u16(&reader.read_n_bytes(2));
-        MethodAccessFlags::from_u16(bitmask)
+    /// Decode the instructions that make up the body of this method
+    ///
+    /// Returns `None` for methods without a code attribute, such as abstract and native methods,
+    /// and an error if the code array ends in the middle of an instruction
+    pub fn instructions(&self) -> Result<Option<Vec<Instruction>>, DecodeError> {
+        self.code().map(|code| decode(&code.code)).transpose()
     }
 
     /// Read field attributes
     fn read_attributes(
         reader: &mut ByteReader,
         constant_pool: &ConstantPoolContainer,
-    ) -> Vec<AttributeInfo> {
-        let attributes_count = to_u16(&reader.read_n_bytes(2));
+        max_attribute_depth: u16,
+        max_annotation_depth: u16,
+    ) -> Result<Vec<AttributeInfo>, ClassFileError> {
+        let attributes_count = reader.read_count(6)?;
         let mut attributes = vec![];
 
         for _ in 0..attributes_count {
-            attributes.push(AttributeInfo::new(reader, constant_pool));
+            attributes.push(AttributeInfo::new(
+                reader,
+                constant_pool,
+                1,
+                max_attribute_depth,
+                max_annotation_depth,
+            )?);
         }
 
-        attributes
+        Ok(attributes)
     }
 }
@@ -2,14 +2,30 @@
 //! A classfile object is a binary file generated by a Java Virtual Machine language compiler
 
 // Re-export modules to make it easy to use for any code outside of this module
+pub use annotation::*;
 pub use attribute::*;
 pub use class_file::*;
 pub use constant_pool::*;
+pub use descriptor::*;
+pub use diff::*;
+pub use duplicates::*;
+pub use error::*;
 pub use field::*;
 pub use method::*;
+pub use resolve::*;
+pub use signature::*;
+pub use verifier::*;
 
+mod annotation;
 mod attribute;
 mod class_file;
 mod constant_pool;
+mod descriptor;
+mod diff;
+mod duplicates;
+mod error;
 mod field;
 mod method;
+mod resolve;
+mod signature;
+mod verifier;
@@ -0,0 +1,273 @@
+//! Helpers to follow references between constant pool entries
+//!
+//! Most constant pool entries do not store their data directly, but refer to other entries in the
+//! constant pool instead. The functions in this module follow those references.
+//!
+//! Index 0 never refers to an entry. A few fields use it to mean "none", these are resolved with
+//! the `resolve_optional_*` functions, which turn index 0 into `None`:
+//!
+//! - `super_class` of `java.lang.Object` (and of `module-info`)
+//! - `catch_type` of an exception table entry that catches any exception
+//! - `outer_class_info_index` and `inner_name_index` of an InnerClasses entry
+//! - `method_index` of an EnclosingMethod attribute
+//! - `name_index` of a MethodParameters entry
+//! - `module_version_index` and `requires_version_index` of a Module attribute
+//!
+//! Everywhere else index 0 is illegal and the regular functions report it as a missing entry.
+//!
+//! Every reference is checked against the type of entry it has to refer to before it is followed,
+//! and no type of entry refers, directly or indirectly, to an entry of its own type. A corrupt
+//! constant pool with self-referential or cyclic entries therefore ends in a `WrongType` error
+//! after a fixed number of steps, rather than in an endless loop.
+
+use super::{
+    ConstantClassInfo, ConstantNameAndTypeInfo, ConstantPoolContainer, ConstantPoolInfo, PoolError,
+    Tag,
+};
+
+/// Fetch the string stored in the UTF-8 constant pool entry at the specified index
+pub fn resolve_utf8(constant_pool: &ConstantPoolContainer, index: u16) -> Option<&str> {
+    constant_pool
+        .get(&index)?
+        .try_cast_into_utf8()
+        .map(|utf8| utf8.string.as_str())
+}
+
+/// Fetch the class constant pool entry at the specified index
+///
+/// Returns an error if there is no entry at the index or if the entry is not a class entry
+pub fn resolve_class(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Result<&ConstantClassInfo, PoolError> {
+    entry_at(constant_pool, index)?
+        .try_cast_into_class()
+        .ok_or_else(|| wrong_type(constant_pool, index, "Class"))
+}
+
+/// Form in which a class name is written
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClassNameForm {
+    /// Packages are separated by dots, e.g. `java.lang.Object`, the way Java source code and
+    /// `javap` write them
+    Binary,
+
+    /// Packages are separated by slashes, e.g. `java/lang/Object`, the way class files store them
+    Internal,
+}
+
+impl ClassNameForm {
+    /// Write a class name that is stored in internal form in this form
+    pub fn apply(self, internal_name: &str) -> String {
+        match self {
+            Self::Binary => internal_name.replace('/', "."),
+            Self::Internal => internal_name.to_owned(),
+        }
+    }
+}
+
+/// Fetch the name of the class entry at the specified index in internal form, e.g.
+/// `java/lang/Object`
+///
+/// Returns an error if the entry is not a class entry or if its name is not a UTF-8 entry
+pub fn resolve_class_name_internal(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Result<&str, PoolError> {
+    let class = resolve_class(constant_pool, index)?;
+    utf8_at(constant_pool, class.name_index)
+}
+
+/// Fetch the name of the class entry at the specified index in binary form, e.g.
+/// `java.lang.Object`
+///
+/// Returns an error if the entry is not a class entry or if its name is not a UTF-8 entry
+pub fn resolve_class_name_binary(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Result<String, PoolError> {
+    resolve_class_name_internal(constant_pool, index).map(|name| ClassNameForm::Binary.apply(name))
+}
+
+/// Fetch the class constant pool entry at the specified index, where index 0 means there is none
+///
+/// Returns an error if a non-zero index does not refer to a class entry
+pub fn resolve_optional_class(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Result<Option<&ConstantClassInfo>, PoolError> {
+    if index == 0 {
+        return Ok(None);
+    }
+
+    resolve_class(constant_pool, index).map(Some)
+}
+
+/// Fetch the string stored in the UTF-8 constant pool entry at the specified index, where index 0
+/// means there is none
+///
+/// Returns an error if a non-zero index does not refer to a UTF-8 entry
+pub fn resolve_optional_utf8(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Result<Option<&str>, PoolError> {
+    if index == 0 {
+        return Ok(None);
+    }
+
+    utf8_at(constant_pool, index).map(Some)
+}
+
+/// Fetch the name and type constant pool entry at the specified index
+pub fn resolve_name_and_type(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Option<&ConstantNameAndTypeInfo> {
+    constant_pool.get(&index)?.try_cast_into_name_and_type()
+}
+
+/// Fetch the name and descriptor of the name and type constant pool entry at the specified index
+pub fn resolve_name_and_descriptor(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Option<(&str, &str)> {
+    let name_and_type = resolve_name_and_type(constant_pool, index)?;
+    let name = resolve_utf8(constant_pool, name_and_type.name_index)?;
+    let descriptor = resolve_utf8(constant_pool, name_and_type.descriptor_index)?;
+
+    Some((name, descriptor))
+}
+
+/// Resolve a field, method, or interface method reference into the name of the class that declares
+/// the member, the member's name, and the member's descriptor
+///
+/// Returns an error if the entry at the index is not a reference or if any of the entries it refers
+/// to are missing or of the wrong type
+pub fn resolve_member_ref(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Result<(&str, &str, &str), PoolError> {
+    let entry = entry_at(constant_pool, index)?;
+
+    let (class_index, name_and_type_index) = match entry.tag {
+        Tag::ConstantFieldRef => entry
+            .try_cast_into_field_ref()
+            .map(|field_ref| (field_ref.class_index, field_ref.name_and_type_index)),
+        Tag::ConstantMethodRef => entry
+            .try_cast_into_method_ref()
+            .map(|method_ref| (method_ref.class_index, method_ref.name_and_type_index)),
+        Tag::ConstantInterfaceMethodRef => entry
+            .try_cast_into_interface_method_ref()
+            .map(|method_ref| (method_ref.class_index, method_ref.name_and_type_index)),
+        _ => None,
+    }
+    .ok_or(PoolError::WrongType {
+        index,
+        expected: "Fieldref, Methodref, or InterfaceMethodref",
+        found: entry.tag,
+    })?;
+
+    let class = resolve_class(constant_pool, class_index)?;
+    let name_and_type = entry_at(constant_pool, name_and_type_index)?
+        .try_cast_into_name_and_type()
+        .ok_or_else(|| wrong_type(constant_pool, name_and_type_index, "NameAndType"))?;
+
+    Ok((
+        utf8_at(constant_pool, class.name_index)?,
+        utf8_at(constant_pool, name_and_type.name_index)?,
+        utf8_at(constant_pool, name_and_type.descriptor_index)?,
+    ))
+}
+
+/// Fetch the entry at the specified index, or an error if there is none
+fn entry_at(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Result<&ConstantPoolInfo, PoolError> {
+    constant_pool
+        .get(&index)
+        .ok_or(PoolError::MissingEntry { index })
+}
+
+/// Fetch the string stored in the UTF-8 entry at the specified index, or an error if there is none
+pub(super) fn utf8_at(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+) -> Result<&str, PoolError> {
+    entry_at(constant_pool, index)?
+        .try_cast_into_utf8()
+        .map(|utf8| utf8.string.as_str())
+        .ok_or_else(|| wrong_type(constant_pool, index, "Utf8"))
+}
+
+/// Describe an existing entry that turned out not to be of the expected type
+fn wrong_type(
+    constant_pool: &ConstantPoolContainer,
+    index: u16,
+    expected: &'static str,
+) -> PoolError {
+    match constant_pool.get(&index) {
+        Some(entry) => PoolError::WrongType {
+            index,
+            expected,
+            found: entry.tag,
+        },
+        None => PoolError::MissingEntry { index },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_class, resolve_member_ref, resolve_name_and_descriptor, utf8_at};
+    use crate::byte_reader::ByteReader;
+    use crate::classfile::{ConstantPoolContainer, ConstantPoolInfo, PoolError, Tag};
+
+    /// Create a constant pool from the raw bytes of its entries, starting at index 1
+    fn constant_pool(entries: &[&[u8]]) -> ConstantPoolContainer {
+        let mut constant_pool = ConstantPoolContainer::new();
+
+        for (index, bytes) in (1..).zip(entries) {
+            let entry = ConstantPoolInfo::new(&mut ByteReader::from_bytes(bytes), index).unwrap();
+            constant_pool.insert(index, entry);
+        }
+
+        constant_pool
+    }
+
+    #[test]
+    fn test_cyclic_references() {
+        let constant_pool = constant_pool(&[
+            &[7, 0x00, 0x01],              // #1 = Class #1
+            &[12, 0x00, 0x02, 0x00, 0x02], // #2 = NameAndType #2:#2
+            &[10, 0x00, 0x04, 0x00, 0x02], // #3 = Methodref #4.#2
+            &[7, 0x00, 0x03],              // #4 = Class #3
+            &[15, 6, 0x00, 0x05],          // #5 = MethodHandle REF_invokeStatic #5
+        ]);
+
+        let class = resolve_class(&constant_pool, 1).unwrap();
+        assert_eq!(
+            utf8_at(&constant_pool, class.name_index),
+            Err(PoolError::WrongType {
+                index: 1,
+                expected: "Utf8",
+                found: Tag::ConstantClass,
+            }),
+            "A class cannot name itself"
+        );
+
+        assert_eq!(resolve_name_and_descriptor(&constant_pool, 2), None);
+        assert_eq!(
+            resolve_member_ref(&constant_pool, 3),
+            Err(PoolError::WrongType {
+                index: 3,
+                expected: "Utf8",
+                found: Tag::ConstantMethodRef,
+            }),
+            "The member's class names the member itself"
+        );
+        assert!(matches!(
+            resolve_member_ref(&constant_pool, 5),
+            Err(PoolError::WrongType { index: 5, .. })
+        ));
+    }
+}
@@ -0,0 +1,290 @@
+//! Provides functionality to work with generic signatures
+//!
+//! Signatures encode declarations written in the Java programming language that use types outside
+//! of the type system of the Java Virtual Machine, such as generic type parameters
+//!
+//! Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.7.9.1
+
+use super::{resolve_utf8, AttributeInfo, AttributeType, ConstantPoolContainer};
+
+/// Lexical element of a signature
+#[derive(Debug, PartialEq)]
+pub enum SignatureToken {
+    /// `<` that opens the type parameters of a generic class or method declaration
+    TypeParametersStart,
+
+    /// `>` that closes the type parameters of a generic class or method declaration
+    TypeParametersEnd,
+
+    /// Name of a declared type parameter, e.g. `T` in `<T:Ljava/lang/Object;>`
+    TypeParameter(String),
+
+    /// `:` that introduces an additional interface bound of a type parameter
+    InterfaceBound,
+
+    /// `<` that opens the type arguments of a class type
+    TypeArgumentsStart,
+
+    /// `>` that closes the type arguments of a class type
+    TypeArgumentsEnd,
+
+    /// Binary name of a class in internal form, e.g. `java/util/List`
+    ClassType(String),
+
+    /// Simple name of an inner class that follows a class type, e.g. `Entry` in `Ljava/util/Map.Entry;`
+    InnerClassType(String),
+
+    /// `;` that ends a class type
+    ClassTypeEnd,
+
+    /// Reference to a type variable, e.g. `T` in `TT;`
+    TypeVariable(String),
+
+    /// Primitive type or `void`, e.g. `I` or `V`
+    BaseType(char),
+
+    /// `[` that adds a dimension to the type that follows
+    ArrayDimension,
+
+    /// `*` unbounded wildcard type argument
+    Wildcard,
+
+    /// `+` wildcard indicator for an upper bound (`? extends`)
+    WildcardExtends,
+
+    /// `-` wildcard indicator for a lower bound (`? super`)
+    WildcardSuper,
+
+    /// `(` that opens the parameter types of a method signature
+    ParametersStart,
+
+    /// `)` that closes the parameter types of a method signature
+    ParametersEnd,
+
+    /// `^` that introduces a thrown type in a method signature
+    Throws,
+}
+
+/// Fetch the signature stored in a signature attribute in the list of attributes
+pub fn resolve_signature<'a>(
+    attributes: &[AttributeInfo],
+    constant_pool: &'a ConstantPoolContainer,
+) -> Option<&'a str> {
+    let signature = attributes
+        .iter()
+        .find(|attribute| attribute.attribute_type == AttributeType::Signature)?
+        .try_cast_into_signature()?;
+
+    resolve_utf8(constant_pool, signature.signature_index)
+}
+
+/// Split a class, method, or field signature into its lexical elements
+///
+/// Returns `None` when the signature is malformed
+pub fn tokenize_signature(signature: &str) -> Option<Vec<SignatureToken>> {
+    let chars: Vec<char> = signature.chars().collect();
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    // Nesting level of type arguments, used to tell the end of the type parameters apart from the
+    // end of the type arguments of one of the type parameter's bounds
+    let mut depth = 0;
+    let mut in_type_parameters = false;
+
+    if chars.first() == Some(&'<') {
+        tokens.push(SignatureToken::TypeParametersStart);
+        in_type_parameters = true;
+        position += 1;
+    }
+
+    while position < chars.len() {
+        let current = chars[position];
+
+        if in_type_parameters && depth == 0 {
+            // A type parameter's name follows the opening bracket or the end of the previous bound
+            let expects_name = matches!(
+                tokens.last(),
+                Some(SignatureToken::TypeParametersStart)
+                    | Some(SignatureToken::ClassTypeEnd)
+                    | Some(SignatureToken::TypeVariable(_))
+            );
+
+            match current {
+                '>' => {
+                    tokens.push(SignatureToken::TypeParametersEnd);
+                    in_type_parameters = false;
+                    position += 1;
+                    continue;
+                }
+                ':' => {
+                    tokens.push(SignatureToken::InterfaceBound);
+                    position += 1;
+                    continue;
+                }
+                _ if expects_name => {
+                    let name = read_identifier(&chars, &mut position, &[':'])?;
+
+                    // Skip the colon that introduces the (optional) class bound
+                    position += 1;
+                    tokens.push(SignatureToken::TypeParameter(name));
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        position += 1;
+
+        let token = match current {
+            'L' => {
+                SignatureToken::ClassType(read_identifier(&chars, &mut position, &['<', '.', ';'])?)
+            }
+            '.' => SignatureToken::InnerClassType(read_identifier(
+                &chars,
+                &mut position,
+                &['<', '.', ';'],
+            )?),
+            'T' => {
+                let name = read_identifier(&chars, &mut position, &[';'])?;
+
+                // Skip the semicolon that ends the type variable
+                position += 1;
+                SignatureToken::TypeVariable(name)
+            }
+            '<' => {
+                depth += 1;
+                SignatureToken::TypeArgumentsStart
+            }
+            '>' => {
+                if depth == 0 {
+                    return None;
+                }
+
+                depth -= 1;
+                SignatureToken::TypeArgumentsEnd
+            }
+            ';' => SignatureToken::ClassTypeEnd,
+            '[' => SignatureToken::ArrayDimension,
+            '*' => SignatureToken::Wildcard,
+            '+' => SignatureToken::WildcardExtends,
+            '-' => SignatureToken::WildcardSuper,
+            '(' => SignatureToken::ParametersStart,
+            ')' => SignatureToken::ParametersEnd,
+            '^' => SignatureToken::Throws,
+            'B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z' | 'V' => {
+                SignatureToken::BaseType(current)
+            }
+            _ => return None,
+        };
+
+        tokens.push(token);
+    }
+
+    if depth != 0 || in_type_parameters {
+        return None;
+    }
+
+    Some(tokens)
+}
+
+/// Read an identifier up to (but excluding) the first terminator character
+///
+/// Returns `None` if no terminator follows the identifier or if the identifier is empty
+fn read_identifier(chars: &[char], position: &mut usize, terminators: &[char]) -> Option<String> {
+    let start = *position;
+
+    while !terminators.contains(chars.get(*position)?) {
+        *position += 1;
+    }
+
+    if *position == start {
+        return None;
+    }
+
+    Some(chars[start..*position].iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tokenize_signature;
+    use super::SignatureToken::*;
+
+    #[test]
+    fn test_tokenize_field_signature() {
+        assert_eq!(
+            tokenize_signature("Ljava/util/List<Ljava/lang/String;>;"),
+            Some(vec![
+                ClassType(String::from("java/util/List")),
+                TypeArgumentsStart,
+                ClassType(String::from("java/lang/String")),
+                ClassTypeEnd,
+                TypeArgumentsEnd,
+                ClassTypeEnd,
+            ]),
+            "Incorrect tokens returned"
+        );
+    }
+
+    #[test]
+    fn test_tokenize_class_signature() {
+        assert_eq!(
+            tokenize_signature(
+                "<T:Ljava/lang/Object;U::Ljava/lang/Comparable<-TU;>;>Ljava/lang/Object;"
+            ),
+            Some(vec![
+                TypeParametersStart,
+                TypeParameter(String::from("T")),
+                ClassType(String::from("java/lang/Object")),
+                ClassTypeEnd,
+                TypeParameter(String::from("U")),
+                InterfaceBound,
+                ClassType(String::from("java/lang/Comparable")),
+                TypeArgumentsStart,
+                WildcardSuper,
+                TypeVariable(String::from("U")),
+                TypeArgumentsEnd,
+                ClassTypeEnd,
+                TypeParametersEnd,
+                ClassType(String::from("java/lang/Object")),
+                ClassTypeEnd,
+            ]),
+            "Incorrect tokens returned"
+        );
+    }
+
+    #[test]
+    fn test_tokenize_method_signature() {
+        assert_eq!(
+            tokenize_signature("<E:Ljava/lang/Exception;>([TE;Ljava/util/Map$Entry<**>;)V^TE;"),
+            Some(vec![
+                TypeParametersStart,
+                TypeParameter(String::from("E")),
+                ClassType(String::from("java/lang/Exception")),
+                ClassTypeEnd,
+                TypeParametersEnd,
+                ParametersStart,
+                ArrayDimension,
+                TypeVariable(String::from("E")),
+                ClassType(String::from("java/util/Map$Entry")),
+                TypeArgumentsStart,
+                Wildcard,
+                Wildcard,
+                TypeArgumentsEnd,
+                ClassTypeEnd,
+                ParametersEnd,
+                BaseType('V'),
+                Throws,
+                TypeVariable(String::from("E")),
+            ]),
+            "Incorrect tokens returned"
+        );
+    }
+
+    #[test]
+    fn test_tokenize_malformed_signature() {
+        assert_eq!(tokenize_signature("Ljava/util/List<"), None);
+        assert_eq!(tokenize_signature("Ljava/lang/String"), None);
+        assert_eq!(tokenize_signature("<T:Ljava/lang/Object;"), None);
+        assert_eq!(tokenize_signature("Q"), None);
+    }
+}
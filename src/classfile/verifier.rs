@@ -0,0 +1,208 @@
+//! Lightweight sanity checks on parsed class files and methods
+//!
+//! This is by no means a replacement for the verification performed by the Java Virtual Machine.
+//! It only checks invariants that can be determined without analysing the bytecode itself.
+//!
+//! Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.10
+
+use std::fmt;
+
+use crate::flags::{ClassAccessFlags, MethodAccessFlags};
+
+use super::{
+    parse_field_descriptor, parse_method_descriptor, resolve_utf8, ClassFile,
+    ConstantPoolContainer, FieldInfo, MethodInfo,
+};
+
+/// Internal name of the class that every interface extends
+const OBJECT_CLASS_NAME: &str = "java/lang/Object";
+
+/// Largest number of local variable slots the parameters of a method may occupy, including `this`
+///
+/// Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.3.3
+const MAX_PARAMETER_SLOTS: u16 = 255;
+
+/// Describes why a class file or method failed a sanity check
+#[derive(Debug, PartialEq)]
+pub enum VerifyError {
+    /// The member's descriptor index does not refer to a valid descriptor
+    InvalidDescriptor { descriptor_index: u16 },
+
+    /// The local variable array is too small to hold the method's arguments
+    MaxLocalsTooSmall { max_locals: u16, required: u16 },
+
+    /// The interface does not extend `java/lang/Object`, `found` is `None` without a superclass
+    InterfaceSuperClass { found: Option<String> },
+
+    /// The member's descriptor does not parse, `member` names the member, e.g. `field counter`
+    MalformedDescriptor { member: String, descriptor: String },
+
+    /// The method's parameters occupy more than 255 local variable slots, `member` names the
+    /// method, e.g. `method main`
+    TooManyParameterSlots { member: String },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDescriptor { descriptor_index } => write!(
+                f,
+                "constant pool index {} does not refer to a valid descriptor",
+                descriptor_index
+            ),
+            Self::MaxLocalsTooSmall {
+                max_locals,
+                required,
+            } => write!(
+                f,
+                "max_locals is {}, but the method's arguments require {} local variable slots",
+                max_locals, required
+            ),
+            Self::InterfaceSuperClass { found } => write!(
+                f,
+                "the superclass of an interface must be java/lang/Object, found {}",
+                found.as_deref().unwrap_or("none")
+            ),
+            Self::MalformedDescriptor { member, descriptor } => {
+                write!(
+                    f,
+                    "{} has a malformed descriptor \"{}\"",
+                    member, descriptor
+                )
+            }
+            Self::TooManyParameterSlots { member } => write!(
+                f,
+                "the parameters of {} occupy more than {} local variable slots",
+                member, MAX_PARAMETER_SLOTS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Check whether the method's `max_locals` is large enough to hold all of its arguments
+///
+/// Methods without a code attribute (abstract and native methods) trivially pass this check
+pub fn check_max_locals(
+    method: &MethodInfo,
+    constant_pool: &ConstantPoolContainer,
+) -> Result<(), VerifyError> {
+    let code = match method.code() {
+        Some(code) => code,
+        None => return Ok(()),
+    };
+
+    let descriptor = method
+        .descriptor(constant_pool)
+        .ok_or(VerifyError::InvalidDescriptor {
+            descriptor_index: method.descriptor_index,
+        })?;
+
+    let required = descriptor
+        .arg_slots(method.access_flags.contains(&MethodAccessFlags::AccStatic))
+        .ok_or_else(|| VerifyError::TooManyParameterSlots {
+            member: member_name("method", method.name_index, constant_pool),
+        })?;
+
+    if code.max_locals < required {
+        return Err(VerifyError::MaxLocalsTooSmall {
+            max_locals: code.max_locals,
+            required,
+        });
+    }
+
+    Ok(())
+}
+
+/// Check whether an interface has `java/lang/Object` as its superclass, as required by the JVMS
+///
+/// Classes that are not interfaces trivially pass this check
+///
+/// Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.1
+pub fn check_interface_super_class(class: &ClassFile) -> Result<(), VerifyError> {
+    if !class.access_flags.contains(&ClassAccessFlags::AccInterface) {
+        return Ok(());
+    }
+
+    let found = class.super_class.as_ref().map(|super_class| {
+        resolve_utf8(&class.constant_pool, super_class.name_index)
+            .unwrap_or("<invalid name>")
+            .to_owned()
+    });
+
+    if found.as_deref() != Some(OBJECT_CLASS_NAME) {
+        return Err(VerifyError::InterfaceSuperClass { found });
+    }
+
+    Ok(())
+}
+
+/// Check whether the field's descriptor is a syntactically valid field descriptor
+///
+/// Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.3.2
+pub fn check_field_descriptor(
+    field: &FieldInfo,
+    constant_pool: &ConstantPoolContainer,
+) -> Result<(), VerifyError> {
+    let descriptor = resolve_utf8(constant_pool, field.descriptor_index).ok_or(
+        VerifyError::InvalidDescriptor {
+            descriptor_index: field.descriptor_index,
+        },
+    )?;
+
+    match parse_field_descriptor(descriptor) {
+        Some(_) => Ok(()),
+        None => Err(malformed_descriptor(
+            "field",
+            field.name_index,
+            descriptor,
+            constant_pool,
+        )),
+    }
+}
+
+/// Check whether the method's descriptor is a syntactically valid method descriptor, whose
+/// parameters occupy no more than 255 local variable slots
+///
+/// Reference: https://docs.oracle.com/javase/specs/jvms/se17/html/jvms-4.html#jvms-4.3.3
+pub fn check_method_descriptor(
+    method: &MethodInfo,
+    constant_pool: &ConstantPoolContainer,
+) -> Result<(), VerifyError> {
+    let descriptor = resolve_utf8(constant_pool, method.descriptor_index).ok_or(
+        VerifyError::InvalidDescriptor {
+            descriptor_index: method.descriptor_index,
+        },
+    )?;
+
+    let parsed = parse_method_descriptor(descriptor).ok_or_else(|| {
+        malformed_descriptor("method", method.name_index, descriptor, constant_pool)
+    })?;
+
+    match parsed.arg_slots(method.access_flags.contains(&MethodAccessFlags::AccStatic)) {
+        Some(slots) if slots <= MAX_PARAMETER_SLOTS => Ok(()),
+        _ => Err(VerifyError::TooManyParameterSlots {
+            member: member_name("method", method.name_index, constant_pool),
+        }),
+    }
+}
+
+/// Describe a member whose descriptor does not parse
+fn malformed_descriptor(
+    kind: &str,
+    name_index: u16,
+    descriptor: &str,
+    constant_pool: &ConstantPoolContainer,
+) -> VerifyError {
+    VerifyError::MalformedDescriptor {
+        member: member_name(kind, name_index, constant_pool),
+        descriptor: descriptor.to_owned(),
+    }
+}
+
+/// Name a member for an error message, e.g. `method main`
+fn member_name(kind: &str, name_index: u16, constant_pool: &ConstantPoolContainer) -> String {
+    let name = resolve_utf8(constant_pool, name_index).unwrap_or("<invalid name>");
+    format!("{} {}", kind, name)
+}
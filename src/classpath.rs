@@ -0,0 +1,109 @@
+//! Looks up classes by name on a class path, the way the JVM's application class loader does
+//!
+//! A class path is a list of directories and JAR archives, separated the way the platform separates
+//! `PATH` entries, e.g. `classes:lib/foo.jar:more-classes` on Unix. Entries are searched in order
+//! and the first entry that has the class wins.
+
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+use crate::jar::Jar;
+
+/// Extension used by JAR archives
+const JAR_EXTENSION: &str = "jar";
+
+/// A single, indexed entry of a class path
+enum ClasspathEntry {
+    /// Root of a directory tree of class files, mirroring the packages
+    Directory(PathBuf),
+
+    /// JAR archive, indexed up front so lookups do not have to read the archive's directory again
+    Jar(Jar),
+}
+
+/// Directories and JAR archives that classes are looked up in, in order
+pub struct Classpath {
+    entries: Vec<ClasspathEntry>,
+}
+
+impl Classpath {
+    /// Index the entries of a class path, e.g. `classes:lib/foo.jar`
+    ///
+    /// Like the JVM, entries that do not exist or that cannot be read are ignored
+    pub fn new(classpath: &OsStr) -> Self {
+        let entries = std::env::split_paths(classpath)
+            .filter_map(|path| {
+                if path.is_dir() {
+                    Some(ClasspathEntry::Directory(path))
+                } else if path.extension() == Some(OsStr::new(JAR_EXTENSION)) {
+                    Jar::open(&path).ok().map(ClasspathEntry::Jar)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    /// Number of usable entries, i.e. without the entries that were ignored
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether none of the entries were usable
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Read the class file of a class by its fully qualified name, e.g. `java.lang.String` or
+    /// `java/lang/String`
+    ///
+    /// Nested classes are named by their binary name, e.g. `java.util.Map$Entry`. Returns `None`
+    /// if none of the entries have the class.
+    pub fn find(&self, class_name: &str) -> Option<Vec<u8>> {
+        let relative = format!("{}.class", class_name.replace('.', "/"));
+
+        self.entries.iter().find_map(|entry| match entry {
+            ClasspathEntry::Directory(root) => std::fs::read(root.join(&relative)).ok(),
+            ClasspathEntry::Jar(jar) => jar.read(&relative).ok().flatten(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Classpath;
+
+    #[test]
+    fn test_find_first_match() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let module = root.join("modules/com.example.greeter");
+        let jar = root.join("greeter.jar");
+
+        let classpath = Classpath::new(
+            &std::env::join_paths([root.join("missing"), jar.clone(), root.clone(), module])
+                .unwrap(),
+        );
+        assert_eq!(classpath.len(), 3, "Missing entries are ignored");
+
+        let plain = classpath
+            .find("Plain")
+            .expect("Classes in a directory should be found");
+        assert_eq!(plain, std::fs::read(root.join("Plain.class")).unwrap());
+
+        let greeter = classpath.find("com.example.greeter.Greeter").unwrap();
+        assert_eq!(classpath.find("com/example/greeter/Greeter"), Some(greeter));
+        assert_eq!(classpath.find("com.example.greeter.Missing"), None);
+    }
+
+    #[test]
+    fn test_empty_classpath() {
+        let classpath = Classpath::new("".as_ref());
+
+        assert!(classpath.is_empty());
+        assert_eq!(classpath.find("Plain"), None);
+    }
+}
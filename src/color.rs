@@ -0,0 +1,114 @@
+//! Optional ANSI coloring of the disassembler output
+//!
+//! Colors make the output easier to scan in a terminal, but they should never end up in output
+//! that is piped into another program or written to a file.
+
+use std::io::IsTerminal;
+
+/// Controls when the output is colored
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorMode {
+    /// Color the output only when it is written to a terminal
+    Auto,
+
+    /// Always color the output
+    Always,
+
+    /// Never color the output
+    Never,
+}
+
+impl ColorMode {
+    /// Convert a command-line value (`auto`, `always`, or `never`) into its matching enum type
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(Self::Auto),
+            "always" => Some(Self::Always),
+            "never" => Some(Self::Never),
+            _ => None,
+        }
+    }
+}
+
+/// ANSI escape sequence that resets all styling
+const RESET: &str = "\x1b[0m";
+
+/// Bold blue, used for keywords and constant pool entry types
+const KEYWORD: &str = "\x1b[1;34m";
+
+/// Green, used for instruction mnemonics
+const MNEMONIC: &str = "\x1b[32m";
+
+/// Yellow, used for constant pool references
+const REFERENCE: &str = "\x1b[33m";
+
+/// Gray, used for comments
+const COMMENT: &str = "\x1b[90m";
+
+/// Wraps pieces of output in ANSI color codes when coloring is enabled
+pub struct Painter {
+    /// Indicates whether color codes should be emitted at all
+    enabled: bool,
+}
+
+impl Painter {
+    /// Create a new painter, `ColorMode::Auto` colors only when standard output is a terminal
+    pub fn new(mode: ColorMode) -> Self {
+        let enabled = match mode {
+            ColorMode::Auto => std::io::stdout().is_terminal(),
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        };
+
+        Self { enabled }
+    }
+
+    /// Style a keyword, such as an access modifier or a constant pool entry type
+    pub fn keyword(&self, text: &str) -> String {
+        self.paint(KEYWORD, text)
+    }
+
+    /// Style an instruction mnemonic
+    pub fn mnemonic(&self, text: &str) -> String {
+        self.paint(MNEMONIC, text)
+    }
+
+    /// Style a reference to a constant pool entry, e.g. `#12`
+    pub fn reference(&self, text: &str) -> String {
+        self.paint(REFERENCE, text)
+    }
+
+    /// Style a comment, e.g. `// java/lang/Object`
+    pub fn comment(&self, text: &str) -> String {
+        self.paint(COMMENT, text)
+    }
+
+    /// Wrap the text in the specified color code if coloring is enabled
+    fn paint(&self, color: &str, text: &str) -> String {
+        if self.enabled {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColorMode, Painter};
+
+    #[test]
+    fn test_painter_never_colors() {
+        let painter = Painter::new(ColorMode::Never);
+
+        assert_eq!(painter.keyword("public"), "public");
+        assert_eq!(painter.reference("#1"), "#1");
+    }
+
+    #[test]
+    fn test_painter_always_colors() {
+        let painter = Painter::new(ColorMode::Always);
+
+        assert_eq!(painter.comment("// x"), "\x1b[90m// x\x1b[0m");
+    }
+}
@@ -0,0 +1,271 @@
+//! Finds class files in exploded class directories
+//!
+//! This allows a whole directory of compiled classes to be disassembled at once, e.g. the output
+//! directory of a build tool or an extracted (multi-release) JAR. Modules are looked up on a module
+//! path, which may also hold modular JARs.
+
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::classfile::parse;
+use crate::jar::Jar;
+
+/// Extension used by compiled class files
+const CLASS_FILE_EXTENSION: &str = "class";
+
+/// Extension used by JAR archives
+const JAR_EXTENSION: &str = "jar";
+
+/// Directory of a multi-release JAR that holds the versioned classes
+const VERSIONS_DIRECTORY: &str = "META-INF/versions";
+
+/// Class file that declares a module, found in the root of an exploded module or modular JAR
+const MODULE_INFO: &str = "module-info.class";
+
+/// Recursively find every class file in the specified directory, sorted by path
+///
+/// Versioned classes of an exploded multi-release JAR (`META-INF/versions`) are skipped, as they
+/// are alternative implementations of classes that are already part of the directory. Directories
+/// that cannot be read are skipped as well.
+pub fn find_class_files(directory: &Path) -> Vec<PathBuf> {
+    let mut class_files = vec![];
+    collect_class_files(directory, &mut class_files);
+    class_files.sort();
+    class_files
+}
+
+/// Find every class file of an exploded multi-release JAR as seen by the specified Java release
+///
+/// For each class the copy in `META-INF/versions/M` with the highest `M` that does not exceed the
+/// release is preferred, falling back to the copy in the root of the directory. The result is
+/// sorted by the class' path relative to its (versioned) root.
+pub fn find_class_files_for_release(directory: &Path, release: u16) -> Vec<PathBuf> {
+    // Keyed by the path relative to the (versioned) root, so newer versions replace older ones
+    let mut class_files: BTreeMap<PathBuf, PathBuf> = BTreeMap::new();
+    let mut roots = vec![directory.to_path_buf()];
+    roots.extend(applicable_versions(&directory.join(VERSIONS_DIRECTORY), release).into_values());
+
+    for root in roots {
+        let mut found = vec![];
+        collect_class_files(&root, &mut found);
+
+        for path in found {
+            if let Ok(relative) = path.strip_prefix(&root) {
+                class_files.insert(relative.to_path_buf(), path.clone());
+            }
+        }
+    }
+
+    class_files.into_values().collect()
+}
+
+/// Find the module with the specified name on a module path, i.e. its root directory or JAR
+///
+/// The module path is a list of paths separated the way the platform separates `PATH` entries.
+/// Each entry is either a module or a directory of modules. A module is an exploded module, i.e. a
+/// directory with a `module-info.class` in its root, or a modular JAR, which has one in the root of
+/// the archive. Entries are searched in order and the first module with a matching name wins.
+pub fn find_module(module_path: &OsStr, name: &str) -> Option<PathBuf> {
+    std::env::split_paths(module_path).find_map(|entry| {
+        if is_module(&entry) {
+            return Some(entry).filter(|module| module_name(module).as_deref() == Some(name));
+        }
+
+        let mut modules: Vec<PathBuf> = std::fs::read_dir(&entry)
+            .ok()?
+            .flatten()
+            .map(|child| child.path())
+            .filter(|child| is_module(child))
+            .collect();
+        modules.sort();
+
+        modules
+            .into_iter()
+            .find(|module| module_name(module).as_deref() == Some(name))
+    })
+}
+
+/// Check whether a path may hold a module, a JAR only turns out to be modular once it is read
+fn is_module(path: &Path) -> bool {
+    path.join(MODULE_INFO).is_file()
+        || (path.is_file() && path.extension() == Some(OsStr::new(JAR_EXTENSION)))
+}
+
+/// Read the name of the module declared by the `module-info.class` in the root of a directory or
+/// JAR archive
+fn module_name(module: &Path) -> Option<String> {
+    let data = if module.is_dir() {
+        std::fs::read(module.join(MODULE_INFO)).ok()?
+    } else {
+        Jar::open(module).ok()?.read(MODULE_INFO).ok()??
+    };
+
+    parse(&data).ok()?.module_name().map(str::to_owned)
+}
+
+/// Find the versioned directories that apply to the release, keyed (and thus ordered) by version
+///
+/// Entries that are not a directory named after a version are ignored.
+fn applicable_versions(versions_directory: &Path, release: u16) -> BTreeMap<u16, PathBuf> {
+    let entries = match std::fs::read_dir(versions_directory) {
+        Ok(entries) => entries,
+        Err(_) => return BTreeMap::new(),
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let version = path.file_name()?.to_str()?.parse::<u16>().ok()?;
+            Some((version, path))
+        })
+        .filter(|(version, _)| *version <= release)
+        .collect()
+}
+
+/// Add all class files in the directory and its subdirectories to the list of class files
+fn collect_class_files(directory: &Path, class_files: &mut Vec<PathBuf>) {
+    if directory.ends_with(VERSIONS_DIRECTORY) {
+        return;
+    }
+
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect_class_files(&path, class_files);
+        } else if path.extension() == Some(OsStr::new(CLASS_FILE_EXTENSION)) {
+            class_files.push(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{find_class_files, find_class_files_for_release, find_module};
+
+    #[test]
+    fn test_find_class_files() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests");
+        let class_files = find_class_files(&fixtures);
+
+        assert!(
+            class_files.contains(&fixtures.join("fixtures").join("Plain.class")),
+            "Class files in subdirectories should be found"
+        );
+        assert!(
+            class_files
+                .iter()
+                .all(|path| path.extension().unwrap() == "class"),
+            "Only class files should be returned"
+        );
+        assert!(
+            class_files.windows(2).all(|pair| pair[0] < pair[1]),
+            "Class files should be sorted by path"
+        );
+    }
+
+    #[test]
+    fn test_find_class_files_for_release() {
+        let directory = std::env::temp_dir().join(format!("jadis-mr-{}", std::process::id()));
+
+        for class_file in [
+            "a/A.class",
+            "a/B.class",
+            "META-INF/versions/9/a/A.class",
+            "META-INF/versions/11/a/A.class",
+            "META-INF/versions/11/a/C.class",
+            "META-INF/versions/17/a/B.class",
+            "META-INF/versions/latest/a/B.class",
+        ] {
+            let path = directory.join(class_file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, []).unwrap();
+        }
+
+        let relative = |release| -> Vec<PathBuf> {
+            find_class_files_for_release(&directory, release)
+                .into_iter()
+                .map(|path| path.strip_prefix(&directory).unwrap().to_path_buf())
+                .collect()
+        };
+
+        assert_eq!(
+            relative(8),
+            vec![PathBuf::from("a/A.class"), PathBuf::from("a/B.class")],
+            "Versioned classes do not apply to releases before their version"
+        );
+        assert_eq!(
+            relative(11),
+            vec![
+                PathBuf::from("META-INF/versions/11/a/A.class"),
+                PathBuf::from("a/B.class"),
+                PathBuf::from("META-INF/versions/11/a/C.class"),
+            ],
+            "The highest applicable version should be preferred"
+        );
+        assert_eq!(
+            relative(21)[1],
+            PathBuf::from("META-INF/versions/17/a/B.class")
+        );
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_find_module() {
+        let modules = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/modules");
+        let greeter = modules.join("com.example.greeter");
+
+        assert_eq!(
+            find_module(modules.as_os_str(), "com.example.greeter"),
+            Some(greeter.clone()),
+            "Modules should be found in a directory of modules"
+        );
+        assert_eq!(
+            find_module(greeter.as_os_str(), "com.example.greeter"),
+            Some(greeter.clone()),
+            "An exploded module can be on the module path itself"
+        );
+        assert_eq!(find_module(greeter.as_os_str(), "com.example.other"), None);
+
+        let module_path = std::env::join_paths([Path::new("missing"), &modules]).unwrap();
+        assert_eq!(
+            find_module(&module_path, "com.example.greeter"),
+            Some(greeter)
+        );
+    }
+
+    #[test]
+    fn test_find_modular_jar() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let module_jars = fixtures.join("module-jars");
+        let greeter = module_jars.join("com.example.greeter.jar");
+
+        assert_eq!(
+            find_module(module_jars.as_os_str(), "com.example.greeter"),
+            Some(greeter.clone()),
+            "Modular JARs should be found in a directory of modules"
+        );
+        assert_eq!(
+            find_module(greeter.as_os_str(), "com.example.greeter"),
+            Some(greeter)
+        );
+
+        let plain_jar = fixtures.join("greeter.jar");
+        assert_eq!(
+            find_module(plain_jar.as_os_str(), "com.example.greeter"),
+            None,
+            "JARs without a module-info.class are not modules"
+        );
+    }
+}
@@ -5,8 +5,20 @@
 //! Obviously it is not a direct replacement as this module has been written for educational purposes.
 //! However, the disassembler should function well enough that it can theoretically be used as a drop-in replacement for [`javap`](https://docs.oracle.com/javase/7/docs/technotes/tools/windows/javap.html).
 
-use crate::{byte_reader::ByteReader};
-use crate::classfile::ClassFile;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::byte_reader::ByteReader;
+use crate::bytecode::{decode_with, mnemonic, Instruction, UnknownOpcodes};
+use crate::classfile::{
+    parse_field_descriptor, resolve_class_name_internal, resolve_member_ref,
+    resolve_name_and_descriptor, resolve_optional_class, resolve_utf8, ClassFile, ClassFileConfig,
+    ClassFileError, ClassKind, ClassNameForm, FieldInfo, FieldType, MethodInfo, Tag,
+};
+use crate::classfile::{AttributeCode, AttributeInfo};
+use crate::color::{ColorMode, Painter};
+use crate::flags::{ClassAccessFlags, FieldAccessFlags, Flags, MethodAccessFlags};
 
 /// Controls which access level shows up in the output
 pub enum DisassemblerVisibility {
@@ -23,6 +35,69 @@ pub enum DisassemblerVisibility {
     PRIVATE,
 }
 
+impl DisassemblerVisibility {
+    /// Check whether a class or member with the specified access level passes this filter
+    fn allows(&self, access: &DisassemblerVisibility) -> bool {
+        access.rank() <= self.rank()
+    }
+
+    /// Order the visibility levels from most to least restrictive filter
+    fn rank(&self) -> u8 {
+        match self {
+            Self::PUBLIC => 0,
+            Self::PROTECTED => 1,
+            Self::PACKAGE => 2,
+            Self::PRIVATE => 3,
+        }
+    }
+}
+
+/// Layout of the disassembler output that does not affect its content
+struct OutputFormat {
+    /// Prepended once per nesting level, e.g. to the members of a class
+    indent: String,
+
+    /// Width of an instruction listing before its comment, which lines up the comments of a method
+    comment_column: usize,
+}
+
+impl OutputFormat {
+    /// Create the default layout, which indents with tabs
+    fn new() -> Self {
+        Self {
+            indent: String::from("\t"),
+            comment_column: 40,
+        }
+    }
+
+    /// Indentation of a line at the specified nesting level
+    fn indent(&self, level: usize) -> String {
+        self.indent.repeat(level)
+    }
+}
+
+/// Selects methods by name and, optionally, by descriptor
+struct MethodFilter {
+    name: String,
+    descriptor: Option<String>,
+}
+
+impl MethodFilter {
+    /// Check whether a method passes this filter, overloads only pass if no descriptor was given
+    fn allows(&self, class: &ClassFile, method: &MethodInfo) -> bool {
+        let constant_pool = &class.constant_pool;
+        let is_descriptor_match = match &self.descriptor {
+            Some(descriptor) => {
+                resolve_utf8(constant_pool, method.descriptor_index) == Some(descriptor.as_str())
+            }
+            None => true,
+        };
+
+        is_descriptor_match
+            && resolve_utf8(constant_pool, method.name_index) == Some(self.name.as_str())
+    }
+}
+
 /// Data needed to create a disassembler
 pub struct DisassemblerConfig {
     /// Class and member visibility setting
@@ -42,8 +117,48 @@ pub struct DisassemblerConfig {
 
     /// Indicates whether final constants should be shown
     show_final_constants: bool,
+
+    /// Indicates whether only the constant pool and class-level metadata should be parsed and shown
+    show_constants_only: bool,
+
+    /// Indicates whether only fields should be shown, methods are skipped
+    show_fields_only: bool,
+
+    /// Indicates whether only methods should be shown, fields are skipped
+    show_methods_only: bool,
+
+    /// Indicates whether low-level class file details, such as the magic number, should be shown
+    show_verbose_info: bool,
+
+    /// Indicates whether only the constant pool should be written, as comma-separated values
+    show_pool_csv: bool,
+
+    /// Indicates whether only the constant pool should be written, as a Graphviz DOT graph
+    show_pool_graph: bool,
+
+    /// Controls when the output is colored
+    color_mode: ColorMode,
+
+    /// Controls the indentation and alignment of the output
+    format: OutputFormat,
+
+    /// Restricts the output to the matching methods, if set
+    method_filter: Option<MethodFilter>,
+
+    /// Form in which class names are rendered
+    class_names: ClassNameForm,
+
+    /// Controls how opcodes that are not defined by the specification are disassembled
+    unknown_opcodes: UnknownOpcodes,
+
+    /// Controls how class files are parsed
+    class_file: ClassFileConfig,
 }
 
+/// Misspelled name of [`DisassemblerConfig`], kept so existing users keep compiling
+#[deprecated(note = "use `DisassemblerConfig` instead")]
+pub type DisassemberConfig = DisassemblerConfig;
+
 /// Java Virtual Machine disassembler
 pub struct Disassembler<'a> {
     /// Used to customize the disassembler's behaviour
@@ -51,11 +166,13 @@ pub struct Disassembler<'a> {
 
     /// Disassembled class file information
     class: ClassFile,
+
+    /// Decoded instructions of each method, `None` if it has no code or instructions are hidden
+    instructions: Vec<Option<Vec<Instruction>>>,
 }
 
-impl DisassemblerConfig {
-    /// Create a new disassember instance
-    pub fn new() -> Self {
+impl Default for DisassemblerConfig {
+    fn default() -> Self {
         Self {
             visibility: DisassemblerVisibility::PACKAGE,
             show_line_numbers: false,
@@ -63,205 +180,2597 @@ impl DisassemblerConfig {
             show_type_signatures: false,
             show_system_info: false,
             show_final_constants: false,
+            show_constants_only: false,
+            show_fields_only: false,
+            show_methods_only: false,
+            show_verbose_info: false,
+            show_pool_csv: false,
+            show_pool_graph: false,
+            color_mode: ColorMode::Auto,
+            format: OutputFormat::new(),
+            method_filter: None,
+            class_names: ClassNameForm::Binary,
+            unknown_opcodes: UnknownOpcodes::RawBytes,
+            class_file: ClassFileConfig::new(),
         }
     }
+}
+
+impl DisassemblerConfig {
+    /// Create a new disassember instance
+    pub fn new() -> Self {
+        Self::default()
+    }
 
     /// Filter which visibility level should show up in the output
-    pub fn with_visibility(&mut self, visibility: DisassemblerVisibility) {
+    pub fn with_visibility(&mut self, visibility: DisassemblerVisibility) -> &mut Self {
         self.visibility = visibility;
+        self
     }
 
     /// Show line numbers
-    pub fn show_line_numbers(&mut self) {
+    pub fn show_line_numbers(&mut self) -> &mut Self {
         self.show_line_numbers = true;
+        self
     }
 
     /// Show assembly instructions
-    pub fn show_assembly_instructions(&mut self) {
+    pub fn show_assembly_instructions(&mut self) -> &mut Self {
         self.show_instructions = true;
+        self
     }
 
     /// Show type signatures
-    pub fn show_type_signatures(&mut self) {
+    pub fn show_type_signatures(&mut self) -> &mut Self {
         self.show_type_signatures = true;
+        self
     }
 
     /// Show system information
-    pub fn show_system_info(&mut self) {
+    pub fn show_system_info(&mut self) -> &mut Self {
         self.show_system_info = true;
+        self
     }
 
     /// Show final constants
-    pub fn show_final_constants(&mut self) {
+    pub fn show_final_constants(&mut self) -> &mut Self {
         self.show_final_constants = true;
+        self
+    }
+
+    /// Show only the constant pool and class-level metadata, fields and methods are not parsed
+    pub fn show_constants_only(&mut self) -> &mut Self {
+        self.show_constants_only = true;
+        self
+    }
+
+    /// Write only the constant pool as comma-separated values, one row per entry, instead of the
+    /// regular output
+    pub fn show_pool_csv(&mut self) -> &mut Self {
+        self.show_pool_csv = true;
+        self
+    }
+
+    /// Write only the constant pool as a Graphviz DOT graph, in which entries are connected to the
+    /// entries they refer to, instead of the regular output
+    pub fn show_pool_graph(&mut self) -> &mut Self {
+        self.show_pool_graph = true;
+        self
+    }
+
+    /// Show only fields, methods are skipped
+    pub fn show_fields_only(&mut self) -> &mut Self {
+        self.show_fields_only = true;
+        self
+    }
+
+    /// Show only methods, fields are skipped
+    pub fn show_methods_only(&mut self) -> &mut Self {
+        self.show_methods_only = true;
+        self
+    }
+
+    /// Show only the methods with the specified name, fields are skipped
+    ///
+    /// All overloads are shown, unless a descriptor such as `([Ljava/lang/String;)V` narrows the
+    /// selection down to a single method
+    pub fn with_method_filter(&mut self, name: &str, descriptor: Option<&str>) -> &mut Self {
+        self.method_filter = Some(MethodFilter {
+            name: name.to_owned(),
+            descriptor: descriptor.map(str::to_owned),
+        });
+        self
+    }
+
+    /// Show low-level class file details, such as the magic number and raw constant pool indices
+    pub fn show_verbose_info(&mut self) -> &mut Self {
+        self.show_verbose_info = true;
+        self
+    }
+
+    /// Control when the output is colored
+    pub fn with_color_mode(&mut self, color_mode: ColorMode) -> &mut Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    /// Indent nested lines with the specified string instead of a tab, e.g. two or four spaces
+    pub fn with_indent(&mut self, indent: &str) -> &mut Self {
+        self.format.indent = indent.to_owned();
+        self
+    }
+
+    /// Line up the comments that follow instructions at the specified column, measured from the
+    /// start of the instruction's offset
+    pub fn with_comment_column(&mut self, comment_column: usize) -> &mut Self {
+        self.format.comment_column = comment_column;
+        self
+    }
+
+    /// Render class names in internal form, e.g. `java/lang/Object`, instead of the default binary
+    /// form, e.g. `java.lang.Object`
+    ///
+    /// Instruction comments always use the internal form, like `javap -c` does
+    pub fn show_internal_names(&mut self) -> &mut Self {
+        self.class_names = ClassNameForm::Internal;
+        self
+    }
+
+    /// Control how opcodes that are not defined by the specification are disassembled
+    ///
+    /// By default they are shown as raw bytes, e.g. `db 0xfe`, so the rest of the method can still
+    /// be read. Rejecting them stops the output with an error instead.
+    pub fn with_unknown_opcodes(&mut self, unknown_opcodes: UnknownOpcodes) -> &mut Self {
+        self.unknown_opcodes = unknown_opcodes;
+        self
+    }
+
+    /// Reject class files with a major version older than the specified version
+    pub fn with_min_major_version(&mut self, min_major_version: u16) -> &mut Self {
+        self.class_file.with_min_major_version(min_major_version);
+        self
+    }
+
+    /// Reject class files with a major version newer than the specified version
+    pub fn with_max_major_version(&mut self, max_major_version: u16) -> &mut Self {
+        self.class_file.with_max_major_version(max_major_version);
+        self
+    }
+
+    /// Compiler-generated members, such as bridge methods, are only interesting when looking at all
+    /// members of a class, like `javap -p` does
+    fn show_synthetic_members(&self) -> bool {
+        matches!(self.visibility, DisassemblerVisibility::PRIVATE)
+    }
+
+    /// Fetch the settings used to parse class files
+    pub fn class_file_config(&self) -> &ClassFileConfig {
+        &self.class_file
+    }
+
+    /// Reject class files with attributes nested deeper than the specified depth
+    pub fn with_max_attribute_depth(&mut self, max_attribute_depth: u16) -> &mut Self {
+        self.class_file
+            .with_max_attribute_depth(max_attribute_depth);
+        self
     }
 }
 
 impl<'a> Disassembler<'a> {
-    pub fn new(config: &'a DisassemblerConfig, reader: &mut ByteReader) -> Self {
-        let class = ClassFile::new(reader);
+    /// Parse a class file, nothing is printed until [`Disassembler::print`] is called
+    pub fn new(
+        config: &'a DisassemblerConfig,
+        reader: &mut ByteReader,
+    ) -> Result<Self, ClassFileError> {
+        let class = if config.show_constants_only || config.show_pool_csv || config.show_pool_graph
+        {
+            ClassFile::parse_header_only(reader, &config.class_file)?
+        } else {
+            ClassFile::new(reader, &config.class_file)?
+        };
+
+        // Bytecode is decoded up front, so a broken method fails before any output is written
+        let instructions = class
+            .methods
+            .iter()
+            .map(|method| match method.code() {
+                Some(code) if config.show_instructions => {
+                    decode_with(&code.code, config.unknown_opcodes)
+                        .map(Some)
+                        .map_err(|error| ClassFileError::InvalidCode {
+                            method: resolve_utf8(&class.constant_pool, method.name_index)
+                                .unwrap_or_default()
+                                .to_string(),
+                            error,
+                        })
+                }
+                _ => Ok(None),
+            })
+            .collect::<Result<_, _>>()?;
 
-        // TODO: remove debug printing
+        Ok(Self {
+            config,
+            class,
+            instructions,
+        })
+    }
 
-        println!("Magic number: {:#08x}", class.magic);
-        println!("Version: {}.{}", class.major_version, class.minor_version);
-        println!("This class: #{}", class.this_class.constant_pool_index);
+    /// Fetch the parsed class file that is being disassembled
+    pub fn class_file(&self) -> &ClassFile {
+        &self.class
+    }
 
-        if class.super_class.is_some() {
-            println!(
-                "Super class: #{}",
-                class.super_class.as_ref().unwrap().constant_pool_index
-            );
-        } else {
-            println!("Super class: NONE");
+    /// Print the disassembled class file to stdout
+    pub fn print(&self) -> io::Result<()> {
+        self.write_to(&mut io::stdout().lock())
+    }
+
+    /// Write the disassembled class file to the specified sink, e.g. a file or stdout
+    pub fn write_to<W: Write + ?Sized>(&self, out: &mut W) -> io::Result<()> {
+        let config = self.config;
+        let class = &self.class;
+        let painter = Painter::new(config.color_mode);
+        let indent = config.format.indent(1);
+        let nested = config.format.indent(2);
+
+        if config.show_pool_csv {
+            return self.write_pool_csv(out);
+        }
+
+        if config.show_pool_graph {
+            return self.write_pool_graph(out);
+        }
+
+        writeln!(
+            out,
+            "{}{}",
+            class_declaration(class, &painter, config.class_names),
+            interfaces_clause(class, &painter, config.class_names)
+        )?;
+
+        if config.show_verbose_info {
+            writeln!(out, "Magic number: {:#08x}", class.magic)?;
+            write!(
+                out,
+                "Version: {}.{}",
+                class.major_version, class.minor_version
+            )?;
+
+            // Release numbers line up with major versions since Java SE 1.2 (major 46)
+            let release = class.major_version.checked_sub(44);
+
+            if let (true, Some(release)) = (class.is_preview(), release) {
+                write!(
+                    out,
+                    " (preview, requires Java SE {} with --enable-preview)",
+                    release
+                )?;
+            }
+
+            writeln!(out)?;
+            // Unlike the declaration line, this includes flags without a source keyword, such as
+            // ACC_SUPER, which changes the semantics of `invokespecial`
+            writeln!(
+                out,
+                "Flags: {}",
+                ClassAccessFlags::describe(class.access_flags_raw)
+            )?;
+            writeln!(out, "This class: #{}", class.this_class.constant_pool_index)?;
+
+            match &class.super_class {
+                Some(super_class) => {
+                    writeln!(out, "Super class: #{}", super_class.constant_pool_index)?
+                }
+                None => writeln!(out, "Super class: NONE")?,
+            }
+
+            writeln!(out, "Interfaces: {:?}", class.interfaces)?;
+        }
+
+        if config.show_type_signatures {
+            if let Some(signature) = class.signature() {
+                writeln!(out, "Signature: {}", signature)?;
+            }
+        }
+
+        // The constant pool and the raw access flags are only of interest when digging into the
+        // class file itself, the declaration line already shows the modifiers
+        if config.show_verbose_info || config.show_constants_only {
+            self.write_constant_pool(out, &painter)?;
+        }
+
+        if config.show_verbose_info {
+            writeln!(out, "Access flags:")?;
+
+            for flag in &class.access_flags {
+                writeln!(out, "{}- {:?}", indent, flag)?;
+            }
+        }
+
+        // Fields, methods, and attributes have not been parsed at all
+        if config.show_constants_only {
+            return Ok(());
+        }
+
+        // Like `javap`, the members are enclosed in braces even if there are none
+        writeln!(out, "{{")?;
+
+        if !config.show_methods_only && config.method_filter.is_none() {
+            let fields: Vec<&FieldInfo> = class
+                .fields
+                .iter()
+                .filter(|field| {
+                    config
+                        .visibility
+                        .allows(&field_visibility(&field.access_flags))
+                })
+                .filter(|field| !field.is_synthetic() || config.show_synthetic_members())
+                .collect();
+
+            // Classes without (visible) fields, such as marker interfaces, skip the section
+            if !fields.is_empty() {
+                writeln!(out, "Fields:")?;
+            }
+
+            for field in fields {
+                writeln!(
+                    out,
+                    "{}- {}{}",
+                    indent,
+                    field_declaration(
+                        class,
+                        field,
+                        config.show_final_constants,
+                        config.class_names
+                    ),
+                    member_markers(
+                        config,
+                        &painter,
+                        field.is_deprecated(),
+                        false,
+                        field.is_synthetic()
+                    )
+                )?;
+
+                if config.show_verbose_info {
+                    writeln!(
+                        out,
+                        "{}  Flags: {}",
+                        indent,
+                        FieldAccessFlags::describe(field.access_flags_raw)
+                    )?;
+                }
+
+                if config.show_type_signatures {
+                    if let Some(signature) = field.signature(&class.constant_pool) {
+                        writeln!(out, "{}  Signature: {}", indent, signature)?;
+                    }
+                }
+
+                write_member_attributes(out, config, &field.attributes)?;
+            }
         }
 
-        println!("Interfaces: {:?}", class.interfaces);
+        if !config.show_fields_only {
+            let methods: Vec<(&MethodInfo, &Option<Vec<Instruction>>)> = class
+                .methods
+                .iter()
+                .zip(&self.instructions)
+                .filter(|(method, _)| {
+                    config
+                        .visibility
+                        .allows(&method_visibility(&method.access_flags))
+                })
+                .filter(|(method, _)| !method.is_synthetic() || config.show_synthetic_members())
+                .filter(|(method, _)| match &config.method_filter {
+                    Some(filter) => filter.allows(class, method),
+                    None => true,
+                })
+                .collect();
+            let is_method_shown = !methods.is_empty();
+
+            // A method filter always gets the section, to say that nothing matched
+            if is_method_shown || config.method_filter.is_some() {
+                writeln!(out, "Methods:")?;
+            }
+
+            for (method, instructions) in methods {
+                writeln!(
+                    out,
+                    "{}- {}{}",
+                    indent,
+                    method_declaration(class, method, config.class_names),
+                    member_markers(
+                        config,
+                        &painter,
+                        method.is_deprecated(),
+                        method.is_bridge(),
+                        method.is_synthetic()
+                    )
+                )?;
+
+                if config.show_verbose_info {
+                    writeln!(
+                        out,
+                        "{}  Flags: {}",
+                        indent,
+                        MethodAccessFlags::describe(method.access_flags_raw)
+                    )?;
+                }
+
+                if config.show_type_signatures {
+                    if let Some(signature) = method.signature(&class.constant_pool) {
+                        writeln!(out, "{}  Signature: {}", indent, signature)?;
+                    }
+                }
+
+                if let (Some(code), Some(instructions)) = (method.code(), instructions) {
+                    write_code(out, class, code, instructions, &painter, config)?;
+                }
+
+                write_member_attributes(out, config, &method.attributes)?;
+            }
+
+            // An empty list would look like the class has no methods at all
+            if let (Some(filter), false) = (&config.method_filter, is_method_shown) {
+                writeln!(
+                    out,
+                    "{}No such method: {}{}",
+                    indent,
+                    filter.name,
+                    filter.descriptor.as_deref().unwrap_or("")
+                )?;
+            }
+        }
+
+        writeln!(out, "}}")?;
+
+        if config.show_verbose_info && !class.attributes.is_empty() {
+            writeln!(out, "Attributes: {}", attribute_names(&class.attributes))?;
+        }
+
+        // Bootstrap methods are needed to make sense of invokedynamic call sites and dynamic
+        // constants
+        if config.show_verbose_info {
+            if let Some(bootstrap_methods) = class.bootstrap_methods() {
+                writeln!(out, "BootstrapMethods:")?;
+
+                for (index, bootstrap_method) in
+                    bootstrap_methods.bootstrap_methods.iter().enumerate()
+                {
+                    writeln!(
+                        out,
+                        "{}- {}: {} {}",
+                        indent,
+                        index,
+                        painter.reference(&format!("#{}", bootstrap_method.bootstrap_method_ref)),
+                        painter.comment(&format!(
+                            "// {}",
+                            describe_method_handle(class, bootstrap_method.bootstrap_method_ref)
+                        ))
+                    )?;
+
+                    if bootstrap_method.bootstrap_arguments.is_empty() {
+                        continue;
+                    }
+
+                    writeln!(out, "{}  Arguments:", indent)?;
+
+                    for argument in &bootstrap_method.bootstrap_arguments {
+                        writeln!(
+                            out,
+                            "{}- {} {}",
+                            nested,
+                            painter.reference(&format!("#{}", argument)),
+                            painter.comment(&format!(
+                                "// {}",
+                                describe_bootstrap_argument(class, *argument, config.class_names)
+                            ))
+                        )?;
+                    }
+                }
+            }
+        }
+
+        // Only sealed classes restrict which classes may extend or implement them
+        if let Some(permitted_subclasses) = class.permitted_subclasses() {
+            writeln!(out, "PermittedSubclasses:")?;
+
+            for index in &permitted_subclasses.classes {
+                match class
+                    .constant_pool
+                    .get(index)
+                    .and_then(|entry| entry.try_cast_into_class())
+                {
+                    Some(subclass) => writeln!(
+                        out,
+                        "{}- {}",
+                        indent,
+                        class_name(class, subclass.name_index, config.class_names)
+                    )?,
+                    None => writeln!(out, "{}- <invalid class #{}>", indent, index)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write every entry of the constant pool, one per line, e.g. `#7 = String #8 // "hello"`
+    fn write_constant_pool<W: Write + ?Sized>(
+        &self,
+        out: &mut W,
+        painter: &Painter,
+    ) -> io::Result<()> {
+        let class = &self.class;
 
-        println!("Constant pool:");
+        writeln!(
+            out,
+            "Constant pool ({} entries, count {}):",
+            class.constant_pool_len(),
+            class.constant_pool_count
+        )?;
 
         for entry in class.constant_pool.values() {
             match entry.tag {
                 crate::classfile::Tag::ConstantUtf8 => {
                     let concrete = entry.try_cast_into_utf8().unwrap();
-                    println!("#{} = Utf8", concrete.constant_pool_index);
+                    write!(
+                        out,
+                        "{} = {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("Utf8"),
+                        concrete.string
+                    )?;
+
+                    // The decoded string cannot be trusted, so show what is actually stored
+                    if concrete.is_lossy() {
+                        let raw: Vec<String> = concrete
+                            .raw
+                            .iter()
+                            .map(|byte| format!("{:02x}", byte))
+                            .collect();
+
+                        write!(
+                            out,
+                            " {}",
+                            painter.comment(&format!("// raw bytes: {}", raw.join(" ")))
+                        )?;
+                    }
+
+                    writeln!(out)?;
                 }
                 crate::classfile::Tag::ConstantInteger => {
                     let concrete = entry.try_cast_into_integer().unwrap();
-                    println!("#{} = Integer", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("Integer"),
+                        integer_literal(concrete.value)
+                    )?;
                 }
                 crate::classfile::Tag::ConstantFloat => {
                     let concrete = entry.try_cast_into_float().unwrap();
-                    println!("#{} = Float", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("Float"),
+                        float_literal(concrete.value)
+                    )?;
                 }
                 crate::classfile::Tag::ConstantLong => {
                     let concrete = entry.try_cast_into_long().unwrap();
-                    println!("#{} = Long", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("Long"),
+                        long_literal(concrete.value)
+                    )?;
                 }
                 crate::classfile::Tag::ConstantDouble => {
                     let concrete = entry.try_cast_into_double().unwrap();
-                    println!("#{} = Double", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("Double"),
+                        double_literal(concrete.value)
+                    )?;
                 }
                 crate::classfile::Tag::ConstantClass => {
                     let concrete = entry.try_cast_into_class().unwrap();
-                    println!("#{} = Class", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("Class")
+                    )?;
                 }
                 crate::classfile::Tag::ConstantString => {
                     let concrete = entry.try_cast_into_string().unwrap();
-                    println!("#{} = String", concrete.constant_pool_index);
+                    let literal = match resolve_utf8(&class.constant_pool, concrete.string_index) {
+                        Some(text) => format!("\"{}\"", escape_string_literal(text)),
+                        None => format!("<invalid string #{}>", concrete.string_index),
+                    };
+
+                    writeln!(
+                        out,
+                        "{} = {} {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("String"),
+                        painter.reference(&format!("#{}", concrete.string_index)),
+                        painter.comment(&format!("// {}", literal))
+                    )?;
                 }
                 crate::classfile::Tag::ConstantFieldRef => {
                     let concrete = entry.try_cast_into_field_ref().unwrap();
-                    println!("#{} = FieldRef", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("FieldRef"),
+                        painter.reference(&format!(
+                            "#{}.#{}",
+                            concrete.class_index, concrete.name_and_type_index
+                        )),
+                        painter.comment(&format!(
+                            "// {}",
+                            describe_member_ref(class, concrete.constant_pool_index)
+                        ))
+                    )?;
                 }
                 crate::classfile::Tag::ConstantMethodRef => {
                     let concrete = entry.try_cast_into_method_ref().unwrap();
-                    println!("#{} = MethodRef", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("MethodRef"),
+                        painter.reference(&format!(
+                            "#{}.#{}",
+                            concrete.class_index, concrete.name_and_type_index
+                        )),
+                        painter.comment(&format!(
+                            "// {}",
+                            describe_member_ref(class, concrete.constant_pool_index)
+                        ))
+                    )?;
                 }
                 crate::classfile::Tag::ConstantInterfaceMethodRef => {
                     let concrete = entry.try_cast_into_interface_method_ref().unwrap();
-                    println!("#{} = InterfaceMethodRef", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("InterfaceMethodRef"),
+                        painter.reference(&format!(
+                            "#{}.#{}",
+                            concrete.class_index, concrete.name_and_type_index
+                        )),
+                        painter.comment(&format!(
+                            "// {}",
+                            describe_member_ref(class, concrete.constant_pool_index)
+                        ))
+                    )?;
                 }
                 crate::classfile::Tag::ConstantNameAndType => {
                     let concrete = entry.try_cast_into_name_and_type().unwrap();
-                    println!("#{} = ConstantNameAndType", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("ConstantNameAndType")
+                    )?;
                 }
                 crate::classfile::Tag::ConstantMethodHandle => {
                     let concrete = entry.try_cast_into_method_handle().unwrap();
-                    println!("#{} = MethodHandle", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("MethodHandle"),
+                        painter.reference(&format!(
+                            "{}:#{}",
+                            concrete.reference_kind.kind(),
+                            concrete.reference_index
+                        )),
+                        painter.comment(&format!(
+                            "// {}",
+                            describe_method_handle(class, concrete.constant_pool_index)
+                        ))
+                    )?;
                 }
                 crate::classfile::Tag::ConstantMethodType => {
                     let concrete = entry.try_cast_into_method_type().unwrap();
-                    println!("#{} = MethodType", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("MethodType")
+                    )?;
                 }
                 crate::classfile::Tag::ConstantDynamic => {
                     let concrete = entry.try_cast_into_dynamic().unwrap();
-                    println!("#{} = Dynamic", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("Dynamic"),
+                        painter.reference(&format!(
+                            "#{}:#{}",
+                            concrete.bootstrap_method_attr_index, concrete.name_and_type_index
+                        )),
+                        painter.comment(&format!(
+                            "// {}",
+                            describe_dynamic_entry(
+                                class,
+                                concrete.bootstrap_method_attr_index,
+                                concrete.name_and_type_index
+                            )
+                        ))
+                    )?;
                 }
                 crate::classfile::Tag::ConstantInvokeDynamic => {
                     let concrete = entry.try_cast_into_invoke_dynamic().unwrap();
-                    println!("#{} = InvokeDynamic", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {} {} {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("InvokeDynamic"),
+                        painter.reference(&format!(
+                            "#{}:#{}",
+                            concrete.bootstrap_method_attr_index, concrete.name_and_type_index
+                        )),
+                        painter.comment(&format!(
+                            "// {}",
+                            describe_dynamic_entry(
+                                class,
+                                concrete.bootstrap_method_attr_index,
+                                concrete.name_and_type_index
+                            )
+                        ))
+                    )?;
                 }
                 crate::classfile::Tag::ConstantModule => {
                     let concrete = entry.try_cast_into_module().unwrap();
-                    println!("#{} = Module", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("Module")
+                    )?;
                 }
                 crate::classfile::Tag::ConstantPackage => {
                     let concrete = entry.try_cast_into_package().unwrap();
-                    println!("#{} = Package", concrete.constant_pool_index);
+                    writeln!(
+                        out,
+                        "{} = {}",
+                        painter.reference(&format!("#{}", concrete.constant_pool_index)),
+                        painter.keyword("Package")
+                    )?;
                 }
             }
         }
 
-        println!("Access flags:");
+        Ok(())
+    }
+
+    /// Write the constant pool as comma-separated values with an `index,tag,value` header
+    ///
+    /// References are flattened into the value they resolve to, e.g. `java/lang/Object` for a
+    /// class entry, so the rows can be sorted and searched without following indices
+    pub fn write_pool_csv<W: Write + ?Sized>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "index,tag,value")?;
 
-        for flag in &class.access_flags {
-            println!("\t- {:?}", flag);
+        for (index, entry) in &self.class.constant_pool {
+            writeln!(
+                out,
+                "{},{},{}",
+                index,
+                entry.tag.name(),
+                csv_field(&flatten_constant(&self.class, *index))
+            )?;
         }
 
-        println!("Fields:");
+        Ok(())
+    }
 
-        for field in &class.fields {
-            let constant_pool_entry = class.constant_pool.get(&field.name_index).expect(&format!(
-                "Unable to fetch field name from constant pool at index {}",
-                field.name_index
-            ));
+    /// Write the constant pool as a Graphviz DOT graph, e.g. to render it with `dot -Tpng`
+    ///
+    /// Every entry is a node labeled with its index, tag, and flattened value. Edges point from an
+    /// entry to the entries it refers to and are labeled with the field that holds the reference,
+    /// e.g. `name_index`. References to missing entries point at a node of their own, so corrupt
+    /// class files can still be rendered.
+    pub fn write_pool_graph<W: Write + ?Sized>(&self, out: &mut W) -> io::Result<()> {
+        let constant_pool = &self.class.constant_pool;
 
-            println!(
-                "\t- {}",
-                constant_pool_entry
-                    .try_cast_into_utf8()
-                    .expect("Unable to cast into UTF-8 constant pool entry")
-                    .string
-                    .as_str()
-            );
+        writeln!(out, "digraph constant_pool {{")?;
+        writeln!(out, "    node [shape=box];")?;
 
-            println!(
-                "\t  Attributes: {:?}",
-                field
-                    .attributes
-                    .iter()
-                    .map(|x| &x.attribute_type)
-                    .collect::<Vec<_>>()
-            );
+        for (index, entry) in constant_pool {
+            writeln!(
+                out,
+                "    {} [label=\"#{} {}\\n{}\"];",
+                index,
+                index,
+                entry.tag.name(),
+                dot_label(&flatten_constant(&self.class, *index))
+            )?;
         }
 
-        println!("Methods:");
+        for (index, entry) in constant_pool {
+            for (field, reference) in entry.references() {
+                if !constant_pool.contains_key(&reference) {
+                    writeln!(
+                        out,
+                        "    {} [label=\"#{} <missing>\", style=dashed];",
+                        reference, reference
+                    )?;
+                }
 
-        for method in &class.methods {
-            let constant_pool_entry = class.constant_pool.get(&method.name_index).expect(&format!(
-                "Unable to fetch method name from constant pool at index {}",
-                method.name_index
-            ));
+                writeln!(out, "    {} -> {} [label=\"{}\"];", index, reference, field)?;
+            }
+        }
 
-            println!(
-                "\t- {}",
-                constant_pool_entry
-                    .try_cast_into_utf8()
-                    .expect("Unable to cast into UTF-8 constant pool entry")
-                    .string
-                    .as_str()
-            );
+        writeln!(out, "}}")
+    }
 
-            println!(
-                "\t  Attributes: {:?}",
-                method
-                    .attributes
-                    .iter()
-                    .map(|x| &x.attribute_type)
-                    .collect::<Vec<_>>()
-            );
+    /// Write a summary of the class as a single line of JSON, terminated by a line break
+    ///
+    /// The record starts with the specified source, e.g. the path the class was read from, so
+    /// records remain attributable when many classes are streamed into the same sink. All members
+    /// are included regardless of the visibility setting.
+    pub fn write_json_line<W: Write + ?Sized>(&self, out: &mut W, source: &str) -> io::Result<()> {
+        let class = &self.class;
+        let names = self.config.class_names;
+
+        write!(
+            out,
+            "{{\"source\":{},\"class\":{},\"super_class\":",
+            json_string(source),
+            json_string(&class_name(class, class.this_class.name_index, names))
+        )?;
+
+        match &class.super_class {
+            Some(super_class) => write!(
+                out,
+                "{}",
+                json_string(&class_name(class, super_class.name_index, names))
+            )?,
+            None => write!(out, "null")?,
         }
 
-        println!(
-            "Attributes: {:?}",
-            class
-                .attributes
-                .iter()
-                .map(|x| &x.attribute_type)
-                .collect::<Vec<_>>()
-        );
+        let interfaces: Vec<String> = class
+            .interfaces
+            .iter()
+            .map(|interface| json_string(&class_name(class, interface.name_index, names)))
+            .collect();
+
+        write!(
+            out,
+            ",\"interfaces\":[{}],\"major_version\":{},\"minor_version\":{},\
+             \"access_flags\":{}",
+            interfaces.join(","),
+            class.major_version,
+            class.minor_version,
+            json_flags::<ClassAccessFlags>(&class.access_flags)
+        )?;
+
+        let fields: Vec<String> = class
+            .fields
+            .iter()
+            .map(|field| {
+                json_member::<FieldAccessFlags>(
+                    class,
+                    field.name_index,
+                    field.descriptor_index,
+                    &field.access_flags,
+                )
+            })
+            .collect();
+
+        let methods: Vec<String> = class
+            .methods
+            .iter()
+            .map(|method| {
+                json_member::<MethodAccessFlags>(
+                    class,
+                    method.name_index,
+                    method.descriptor_index,
+                    &method.access_flags,
+                )
+            })
+            .collect();
+
+        writeln!(
+            out,
+            ",\"fields\":[{}],\"methods\":[{}]}}",
+            fields.join(","),
+            methods.join(",")
+        )
+    }
+}
+
+/// Render the instructions of a method body, followed by its exception handlers
+fn write_code<W: Write + ?Sized>(
+    out: &mut W,
+    class: &ClassFile,
+    code: &AttributeCode,
+    instructions: &[Instruction],
+    painter: &Painter,
+    config: &DisassemblerConfig,
+) -> io::Result<()> {
+    let indent = config.format.indent(1);
+    let nested = config.format.indent(2);
+
+    writeln!(out, "{}  Code:", indent)?;
+
+    for instruction in instructions {
+        let painted = paint_instruction(instruction, painter);
+
+        match instruction_comment(class, instruction) {
+            // Color codes take up no space, so the padding is based on the plain text
+            Some(comment) => writeln!(
+                out,
+                "{}{}{:padding$} {}",
+                nested,
+                painted,
+                "",
+                painter.comment(&format!("// {}", comment)),
+                padding = config
+                    .format
+                    .comment_column
+                    .saturating_sub(instruction.to_string().len())
+            )?,
+            None => writeln!(out, "{}{}", nested, painted)?,
+        }
+    }
+
+    // Handlers are listed in the order in which the JVM searches them
+    if !code.exception_table.is_empty() {
+        writeln!(out, "{}  Exception table:", indent)?;
+        writeln!(out, "{} from    to  target type", nested)?;
+
+        for entry in &code.exception_table {
+            // A catch type of zero catches any exception, which is how finally blocks are compiled
+            let catch_type = match resolve_optional_class(&class.constant_pool, entry.catch_type) {
+                Ok(Some(catch_type)) => {
+                    class_name(class, catch_type.name_index, config.class_names)
+                }
+                Ok(None) => String::from("any"),
+                Err(_) => format!("<invalid class #{}>", entry.catch_type),
+            };
+
+            writeln!(
+                out,
+                "{}{:>5} {:>5} {:>5}   {}",
+                nested, entry.start_pc, entry.end_pc, entry.handler_pc, catch_type
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Render an instruction like its `Display` implementation does, with a colored mnemonic and
+/// constant pool reference
+///
+/// Unknown opcodes have no mnemonic, they are rendered as is
+fn paint_instruction(instruction: &Instruction, painter: &Painter) -> String {
+    let mnemonic = match mnemonic(instruction.opcode) {
+        Some(mnemonic) => mnemonic,
+        None => return instruction.to_string(),
+    };
+    let mut operands = instruction.format_operands();
+
+    // Instructions that refer to the constant pool list the reference first, e.g. `#5, 1`
+    if let Some(index) = instruction.constant_pool_index() {
+        let reference = format!("#{}", index);
+
+        if let Some(rest) = operands.strip_prefix(&reference) {
+            operands = format!("{}{}", painter.reference(&reference), rest);
+        }
+    }
+
+    let mut text = format!("{:>5}: {}", instruction.offset, painter.mnemonic(mnemonic));
+
+    if !operands.is_empty() {
+        text.push(' ');
+        text.push_str(&operands);
+    }
+
+    text
+}
+
+/// Write the attributes of a field or method, nothing is written if there are none
+///
+/// Only verbose mode lists them, each by name on its own line, like `javap -v` does
+fn write_member_attributes<W: Write + ?Sized>(
+    out: &mut W,
+    config: &DisassemblerConfig,
+    attributes: &[AttributeInfo],
+) -> io::Result<()> {
+    let indent = config.format.indent(1);
+
+    if attributes.is_empty() || !config.show_verbose_info {
+        return Ok(());
+    }
+
+    writeln!(out, "{}  Attributes:", indent)?;
+
+    for attribute in attributes {
+        writeln!(
+            out,
+            "{}- {}",
+            config.format.indent(2),
+            attribute.attribute_type
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Render the names of attributes as a list, e.g. `[Code, Signature]`
+fn attribute_names(attributes: &[AttributeInfo]) -> String {
+    let names: Vec<&str> = attributes
+        .iter()
+        .map(|attribute| attribute.attribute_type.name())
+        .collect();
+
+    format!("[{}]", names.join(", "))
+}
+
+/// Render the markers that follow a member's declaration, e.g. ` // Deprecated, synthetic`
+///
+/// Bridge and synthetic members are only marked in verbose mode, nothing is rendered if there are
+/// no markers
+fn member_markers(
+    config: &DisassemblerConfig,
+    painter: &Painter,
+    is_deprecated: bool,
+    is_bridge: bool,
+    is_synthetic: bool,
+) -> String {
+    let mut markers = vec![];
+
+    if is_deprecated {
+        markers.push("Deprecated");
+    }
+
+    if is_bridge && config.show_verbose_info {
+        markers.push("bridge");
+    }
+
+    if is_synthetic && config.show_verbose_info {
+        markers.push("synthetic");
+    }
+
+    if markers.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " {}",
+            painter.comment(&format!("// {}", markers.join(", ")))
+        )
+    }
+}
+
+/// Fetch the access level of a field, fields without an access flag are package-private
+fn field_visibility(access_flags: &[FieldAccessFlags]) -> DisassemblerVisibility {
+    if access_flags.contains(&FieldAccessFlags::AccPublic) {
+        DisassemblerVisibility::PUBLIC
+    } else if access_flags.contains(&FieldAccessFlags::AccProtected) {
+        DisassemblerVisibility::PROTECTED
+    } else if access_flags.contains(&FieldAccessFlags::AccPrivate) {
+        DisassemblerVisibility::PRIVATE
+    } else {
+        DisassemblerVisibility::PACKAGE
+    }
+}
+
+/// Fetch the access level of a method, methods without an access flag are package-private
+fn method_visibility(access_flags: &[MethodAccessFlags]) -> DisassemblerVisibility {
+    if access_flags.contains(&MethodAccessFlags::AccPublic) {
+        DisassemblerVisibility::PUBLIC
+    } else if access_flags.contains(&MethodAccessFlags::AccProtected) {
+        DisassemblerVisibility::PROTECTED
+    } else if access_flags.contains(&MethodAccessFlags::AccPrivate) {
+        DisassemblerVisibility::PRIVATE
+    } else {
+        DisassemblerVisibility::PACKAGE
+    }
+}
+
+/// Render the declaration of the class, e.g. `public final class Foo extends java.lang.Object`
+///
+/// Interfaces and annotation interfaces do not have a superclass of their own, so they never get
+/// an extends clause, and neither does `java.lang.Object`, as it has no superclass at all
+fn class_declaration(class: &ClassFile, painter: &Painter, names: ClassNameForm) -> String {
+    let kind = class.kind();
+    let has_flag = |flag: ClassAccessFlags| class.access_flags.contains(&flag);
+    let mut keywords = vec![];
+
+    if has_flag(ClassAccessFlags::AccPublic) {
+        keywords.push("public");
+    }
+
+    // Interfaces are implicitly abstract and enums are implicitly final
+    if kind == ClassKind::Class {
+        if has_flag(ClassAccessFlags::AccAbstract) {
+            keywords.push("abstract");
+        }
+
+        if has_flag(ClassAccessFlags::AccFinal) {
+            keywords.push("final");
+        }
+    }
+
+    if class.permitted_subclasses().is_some() {
+        keywords.push("sealed");
+    }
+
+    let kind_keyword = kind.to_string();
+    keywords.push(&kind_keyword);
+
+    // The class of a module declaration is always called `module-info`, the module has a name
+    let name = match class.module_name() {
+        Some(module_name) if kind == ClassKind::Module => module_name.to_owned(),
+        _ => class_name(class, class.this_class.name_index, names),
+    };
+
+    let mut declaration = format!("{} {}", painter.keyword(&keywords.join(" ")), name);
+
+    if let (ClassKind::Class | ClassKind::Enum, Some(super_class)) = (kind, &class.super_class) {
+        declaration.push_str(&format!(
+            " {} {}",
+            painter.keyword("extends"),
+            class_name(class, super_class.name_index, names)
+        ));
+    }
+
+    declaration
+}
+
+/// Render the interfaces a class implements, e.g. ` implements java.lang.Runnable`
+///
+/// Interfaces extend their superinterfaces instead, nothing is rendered if there are no interfaces
+fn interfaces_clause(class: &ClassFile, painter: &Painter, names: ClassNameForm) -> String {
+    if class.interfaces.is_empty() {
+        return String::new();
+    }
+
+    let keyword = match class.kind() {
+        ClassKind::Interface | ClassKind::Annotation => "extends",
+        _ => "implements",
+    };
+
+    let interfaces: Vec<String> = class
+        .interfaces
+        .iter()
+        .map(|interface| class_name(class, interface.name_index, names))
+        .collect();
+
+    format!(" {} {}", painter.keyword(keyword), interfaces.join(", "))
+}
+
+/// Describe a dynamically-computed constant or call site as `#bootstrap_method:name:descriptor`
+///
+/// The bootstrap method index refers to an entry in the class' bootstrap methods attribute rather
+/// than to an entry in the constant pool
+fn describe_dynamic_entry(
+    class: &ClassFile,
+    bootstrap_method_attr_index: u16,
+    name_and_type_index: u16,
+) -> String {
+    // The bootstrap methods attribute is absent when only the class header has been parsed
+    let is_missing_bootstrap_method = match class.bootstrap_methods() {
+        Some(attribute) => attribute
+            .bootstrap_methods
+            .get(usize::from(bootstrap_method_attr_index))
+            .is_none(),
+        None => false,
+    };
+
+    if is_missing_bootstrap_method {
+        return format!(
+            "<invalid bootstrap method #{}>",
+            bootstrap_method_attr_index
+        );
+    }
+
+    match resolve_name_and_descriptor(&class.constant_pool, name_and_type_index) {
+        Some((name, descriptor)) => {
+            format!("#{}:{}:{}", bootstrap_method_attr_index, name, descriptor)
+        }
+        None => format!("<invalid name and type #{}>", name_and_type_index),
+    }
+}
+
+/// Describe the constant pool entry an instruction refers to the way `javap -c` does, e.g.
+/// `Method java/io/PrintStream.println:(I)V` or `InvokeDynamic #0:run:()Ljava/lang/Runnable;`
+///
+/// Returns `None` for instructions without a described constant pool operand
+fn instruction_comment(class: &ClassFile, instruction: &Instruction) -> Option<String> {
+    let index = instruction.constant_pool_index()?;
+
+    // ldc, ldc_w, and ldc2_w
+    if (0x12..=0x14).contains(&instruction.opcode) {
+        return Some(describe_ldc_constant(
+            class,
+            index,
+            instruction.opcode == 0x14,
+        ));
+    }
+
+    let entry = class.constant_pool.get(&index)?;
+
+    let kind = match entry.tag {
+        Tag::ConstantFieldRef => "Field",
+        Tag::ConstantMethodRef => "Method",
+        Tag::ConstantInterfaceMethodRef => "InterfaceMethod",
+        // The call site is named by the bootstrap method that links it and its name and type
+        Tag::ConstantInvokeDynamic => {
+            let invoke_dynamic = entry.try_cast_into_invoke_dynamic()?;
+
+            return Some(format!(
+                "InvokeDynamic {}",
+                describe_dynamic_entry(
+                    class,
+                    invoke_dynamic.bootstrap_method_attr_index,
+                    invoke_dynamic.name_and_type_index,
+                )
+            ));
+        }
+        Tag::ConstantClass => {
+            let name_index = entry.try_cast_into_class()?.name_index;
+
+            return Some(format!(
+                "class {}",
+                resolve_utf8(&class.constant_pool, name_index).unwrap_or("<invalid name>")
+            ));
+        }
+        _ => return None,
+    };
+
+    Some(format!("{} {}", kind, describe_member_ref(class, index)))
+}
+
+/// Describe the constant an `ldc` instruction pushes by its type and value, e.g. `String "hello"`
+/// or `Class java/lang/Object`
+///
+/// `ldc2_w` pushes a long or double, which take up two slots of the constant pool. The second slot
+/// has no entry of its own, an index that points at it is described as such rather than as missing.
+fn describe_ldc_constant(class: &ClassFile, index: u16, is_wide: bool) -> String {
+    let constant_pool = &class.constant_pool;
+
+    let entry = match constant_pool.get(&index) {
+        Some(entry) => entry,
+        None => {
+            let previous = index
+                .checked_sub(1)
+                .and_then(|previous| constant_pool.get(&previous));
+            let is_second_slot = matches!(
+                previous.map(|previous| previous.tag),
+                Some(Tag::ConstantLong | Tag::ConstantDouble)
+            );
+
+            return if is_second_slot {
+                format!("<unusable second slot of constant #{}>", index - 1)
+            } else {
+                format!("<invalid constant #{}>", index)
+            };
+        }
+    };
+
+    let is_two_slots = matches!(entry.tag, Tag::ConstantLong | Tag::ConstantDouble);
+
+    // Only ldc2_w loads constants that take up two slots, and it loads nothing else
+    if is_wide != is_two_slots {
+        let mnemonic = if is_wide { "ldc2_w" } else { "ldc" };
+        return format!("<{} cannot be loaded by {}>", entry.tag.name(), mnemonic);
+    }
+
+    let kind = match entry.tag {
+        Tag::ConstantInteger => "int",
+        Tag::ConstantFloat => "float",
+        Tag::ConstantLong => "long",
+        Tag::ConstantDouble => "double",
+        Tag::ConstantClass => {
+            return match resolve_class_name_internal(constant_pool, index) {
+                Ok(name) => format!("Class {}", name),
+                Err(error) => format!("<{}>", error),
+            };
+        }
+        Tag::ConstantDynamic => {
+            let dynamic = entry.try_cast_into_dynamic().unwrap();
+
+            return format!(
+                "Dynamic {}",
+                describe_dynamic_entry(
+                    class,
+                    dynamic.bootstrap_method_attr_index,
+                    dynamic.name_and_type_index,
+                )
+            );
+        }
+        tag => tag.name(),
+    };
+
+    format!(
+        "{} {}",
+        kind,
+        describe_constant(class, index, ClassNameForm::Internal)
+    )
+}
+
+/// Describe a field, method, or interface method reference as `class.name:descriptor`
+fn describe_member_ref(class: &ClassFile, index: u16) -> String {
+    match resolve_member_ref(&class.constant_pool, index) {
+        Ok((class_name, name, descriptor)) => format!("{}.{}:{}", class_name, name, descriptor),
+        Err(error) => format!("<{}>", error),
+    }
+}
+
+/// Describe a method handle by its behaviour and the member it refers to, e.g.
+/// `invokestatic java/lang/Integer.valueOf:(I)Ljava/lang/Integer;`
+fn describe_method_handle(class: &ClassFile, index: u16) -> String {
+    match class
+        .constant_pool
+        .get(&index)
+        .and_then(|entry| entry.try_cast_into_method_handle())
+    {
+        Some(method_handle) => format!(
+            "{} {}",
+            method_handle.reference_kind,
+            describe_member_ref(class, method_handle.reference_index)
+        ),
+        None => format!("<invalid method handle #{}>", index),
+    }
+}
+
+/// Describe a static argument of a bootstrap method by its resolved value
+///
+/// Method handles are described by their reference kind, e.g.
+/// `REF_invokeInterface java/util/List.size:()I`, which tells the implementation method of a lambda
+/// apart from the bootstrap method itself. Dynamically-computed constants refer to their own
+/// bootstrap method, which is listed separately.
+fn describe_bootstrap_argument(class: &ClassFile, index: u16, names: ClassNameForm) -> String {
+    let entry = match class.constant_pool.get(&index) {
+        Some(entry) => entry,
+        None => return format!("<invalid constant #{}>", index),
+    };
+
+    match entry.tag {
+        Tag::ConstantMethodHandle => {
+            let method_handle = entry.try_cast_into_method_handle().unwrap();
+
+            format!(
+                "{} {}",
+                method_handle.reference_kind.spec_name(),
+                describe_member_ref(class, method_handle.reference_index)
+            )
+        }
+        Tag::ConstantDynamic => {
+            let dynamic = entry.try_cast_into_dynamic().unwrap();
+
+            describe_dynamic_entry(
+                class,
+                dynamic.bootstrap_method_attr_index,
+                dynamic.name_and_type_index,
+            )
+        }
+        _ => describe_constant(class, index, names),
+    }
+}
+
+/// Describe a loadable constant, such as a bootstrap method argument, by its resolved value
+fn describe_constant(class: &ClassFile, index: u16, names: ClassNameForm) -> String {
+    let entry = match class.constant_pool.get(&index) {
+        Some(entry) => entry,
+        None => return format!("<invalid constant #{}>", index),
+    };
+
+    match entry.tag {
+        Tag::ConstantString => {
+            let string_index = entry.try_cast_into_string().unwrap().string_index;
+
+            match resolve_utf8(&class.constant_pool, string_index) {
+                Some(text) => format!("\"{}\"", escape_string_literal(text)),
+                None => format!("<invalid string #{}>", string_index),
+            }
+        }
+        Tag::ConstantInteger => integer_literal(entry.try_cast_into_integer().unwrap().value),
+        Tag::ConstantFloat => float_literal(entry.try_cast_into_float().unwrap().value),
+        Tag::ConstantLong => long_literal(entry.try_cast_into_long().unwrap().value),
+        Tag::ConstantDouble => double_literal(entry.try_cast_into_double().unwrap().value),
+        Tag::ConstantClass => class_name(
+            class,
+            entry.try_cast_into_class().unwrap().name_index,
+            names,
+        ),
+        Tag::ConstantMethodType => {
+            let descriptor_index = entry.try_cast_into_method_type().unwrap().descriptor_index;
+
+            resolve_utf8(&class.constant_pool, descriptor_index)
+                .map(str::to_owned)
+                .unwrap_or_else(|| format!("<invalid method type #{}>", descriptor_index))
+        }
+        Tag::ConstantMethodHandle => describe_method_handle(class, index),
+        tag => format!("<{} #{}>", tag.name(), index),
+    }
+}
+
+/// Render a field the way it would be declared in the Java programming language, e.g.
+/// `private java.util.List list;`
+///
+/// Constant fields can be rendered with their initializer as well, e.g.
+/// `public static final int VERSION = 3;`
+fn field_declaration(
+    class: &ClassFile,
+    field: &FieldInfo,
+    show_initializer: bool,
+    names: ClassNameForm,
+) -> String {
+    let name = resolve_utf8(&class.constant_pool, field.name_index).unwrap_or("<invalid name>");
+    let mut declaration = String::new();
+
+    let modifiers = [
+        (FieldAccessFlags::AccPublic, "public "),
+        (FieldAccessFlags::AccProtected, "protected "),
+        (FieldAccessFlags::AccPrivate, "private "),
+        (FieldAccessFlags::AccStatic, "static "),
+        (FieldAccessFlags::AccFinal, "final "),
+        (FieldAccessFlags::AccVolatile, "volatile "),
+        (FieldAccessFlags::AccTransient, "transient "),
+    ];
+
+    for (flag, keyword) in modifiers {
+        if field.access_flags.contains(&flag) {
+            declaration.push_str(keyword);
+        }
+    }
+
+    let field_type = match field.descriptor(&class.constant_pool) {
+        Some(field_type) => field_type,
+        None => {
+            declaration.push_str(&format!(
+                "{} <invalid descriptor #{}>;",
+                name, field.descriptor_index
+            ));
+            return declaration;
+        }
+    };
+
+    declaration.push_str(&format!("{} {}", field_type.to_java_string(names), name));
+
+    if let Some(constant_value) = field.constant_value().filter(|_| show_initializer) {
+        declaration.push_str(" = ");
+        declaration.push_str(&constant_literal(
+            class,
+            &field_type,
+            constant_value.constantvalue_index,
+            names,
+        ));
+    }
+
+    declaration.push(';');
+    declaration
+}
+
+/// Render the value of a constant field as a literal of the field's type
+///
+/// Booleans and characters are stored as integer constants, they are converted back to `true`,
+/// `false`, or a character literal
+fn constant_literal(
+    class: &ClassFile,
+    field_type: &FieldType,
+    index: u16,
+    names: ClassNameForm,
+) -> String {
+    let value = class
+        .constant_pool
+        .get(&index)
+        .and_then(|entry| entry.try_cast_into_integer())
+        .map(|integer| integer.value);
+
+    match (field_type, value) {
+        (FieldType::Boolean, Some(value)) => (value != 0).to_string(),
+        (FieldType::Char, Some(value)) => {
+            match u32::try_from(value).ok().and_then(char::from_u32) {
+                Some('\'') => String::from("'\\''"),
+                Some('"') => String::from("'\"'"),
+                Some(character) => format!("'{}'", escape_string_literal(&character.to_string())),
+                None => integer_literal(value),
+            }
+        }
+        _ => describe_constant(class, index, names),
+    }
+}
+
+/// Render a method the way it would be declared in the Java programming language, e.g.
+/// `public static void main(java.lang.String[]);`
+///
+/// Constructors are named after their class and static initializers are rendered as `static {};`
+fn method_declaration(class: &ClassFile, method: &MethodInfo, names: ClassNameForm) -> String {
+    let name = resolve_utf8(&class.constant_pool, method.name_index).unwrap_or("<invalid name>");
+
+    if name == "<clinit>" {
+        return String::from("static {};");
+    }
+
+    let has_flag = |flag: MethodAccessFlags| method.access_flags.contains(&flag);
+    let mut declaration = String::new();
+
+    let modifiers = [
+        (MethodAccessFlags::AccPublic, "public "),
+        (MethodAccessFlags::AccProtected, "protected "),
+        (MethodAccessFlags::AccPrivate, "private "),
+        (MethodAccessFlags::AccAbstract, "abstract "),
+        (MethodAccessFlags::AccStatic, "static "),
+        (MethodAccessFlags::AccFinal, "final "),
+        (MethodAccessFlags::AccSynchronized, "synchronized "),
+        (MethodAccessFlags::AccNative, "native "),
+        (MethodAccessFlags::AccStrict, "strictfp "),
+    ];
+
+    for (flag, keyword) in modifiers {
+        if has_flag(flag) {
+            declaration.push_str(keyword);
+        }
+
+        // Interface methods with a body that are neither static nor private are default methods
+        if keyword == "public "
+            && class.kind() == ClassKind::Interface
+            && !has_flag(MethodAccessFlags::AccAbstract)
+            && !has_flag(MethodAccessFlags::AccStatic)
+            && !has_flag(MethodAccessFlags::AccPrivate)
+        {
+            declaration.push_str("default ");
+        }
+    }
+
+    let descriptor = match method.descriptor(&class.constant_pool) {
+        Some(descriptor) => descriptor,
+        None => {
+            declaration.push_str(&format!(
+                "{} <invalid descriptor #{}>;",
+                name, method.descriptor_index
+            ));
+            return declaration;
+        }
+    };
+
+    if name == "<init>" {
+        declaration.push_str(&class_name(class, class.this_class.name_index, names));
+    } else {
+        match &descriptor.return_type {
+            Some(return_type) => declaration.push_str(&return_type.to_java_string(names)),
+            None => declaration.push_str("void"),
+        }
+
+        declaration.push(' ');
+        declaration.push_str(name);
+    }
+
+    let is_varargs = has_flag(MethodAccessFlags::AccVarArgs);
+    let parameter_count = descriptor.parameters.len();
+    let parameters: Vec<String> = descriptor
+        .parameters
+        .iter()
+        .enumerate()
+        .map(|(index, parameter)| match parameter {
+            // The trailing array parameter of a variable arity method is written as `Type...`
+            FieldType::Array(component_type) if is_varargs && index + 1 == parameter_count => {
+                format!("{}...", component_type.to_java_string(names))
+            }
+            _ => parameter.to_java_string(names),
+        })
+        .collect();
+
+    declaration.push_str(&format!("({})", parameters.join(", ")));
+
+    if let Some(exceptions) = method.exceptions() {
+        let thrown: Vec<String> = exceptions
+            .exception_index_table
+            .iter()
+            .map(|index| {
+                match class
+                    .constant_pool
+                    .get(index)
+                    .and_then(|entry| entry.try_cast_into_class())
+                {
+                    Some(thrown_class) => class_name(class, thrown_class.name_index, names),
+                    None => format!("<invalid class #{}>", index),
+                }
+            })
+            .collect();
+
+        if !thrown.is_empty() {
+            declaration.push_str(&format!(" throws {}", thrown.join(", ")));
+        }
+    }
+
+    declaration.push(';');
+    declaration
+}
+
+/// Resolve a class name stored in the constant pool and convert it into the specified form
+///
+/// Array classes are named by their descriptor, e.g. `[[I`, these are rendered as Java types
+/// instead, e.g. `int[][]`
+fn class_name(class: &ClassFile, name_index: u16, names: ClassNameForm) -> String {
+    match resolve_utf8(&class.constant_pool, name_index) {
+        Some(name) if name.starts_with('[') => match parse_field_descriptor(name) {
+            Some(array_type) => array_type.to_java_string(names),
+            None => names.apply(name),
+        },
+        Some(name) => names.apply(name),
+        None => format!("<invalid class name #{}>", name_index),
+    }
+}
+
+/// Render an integer constant the way it would appear in Java source
+fn integer_literal(value: i32) -> String {
+    value.to_string()
+}
+
+/// Render a long constant the way `javap` does, using an `l` suffix
+fn long_literal(value: i64) -> String {
+    format!("{}l", value)
+}
+
+/// Render a float constant the way `javap` does, using an `f` suffix
+fn float_literal(value: f32) -> String {
+    format!("{}f", java_floating_point(value))
+}
+
+/// Render a double constant the way `javap` does, using a `d` suffix
+fn double_literal(value: f64) -> String {
+    format!("{}d", java_floating_point(value))
+}
+
+/// Format a floating point value like Java's `Float.toString` and `Double.toString`
+///
+/// Both use the shortest representation that round-trips, switch to scientific notation outside
+/// of [10^-3, 10^7) and always keep at least one digit after the decimal point.
+fn java_floating_point<T>(value: T) -> String
+where
+    T: Copy + Into<f64> + fmt::Debug + fmt::LowerExp,
+{
+    let wide: f64 = value.into();
+
+    if wide.is_nan() {
+        return String::from("NaN");
+    }
+
+    if wide.is_infinite() {
+        return String::from(if wide > 0.0 { "Infinity" } else { "-Infinity" });
+    }
+
+    let magnitude = wide.abs();
+
+    if magnitude == 0.0 || (1e-3..1e7).contains(&magnitude) {
+        // Debug formatting is the shortest round-trip representation and keeps the ".0"
+        return format!("{:?}", value);
+    }
+
+    let scientific = format!("{:e}", value);
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap());
+
+    if mantissa.contains('.') {
+        format!("{}E{}", mantissa, &exponent[1..])
+    } else {
+        format!("{}.0E{}", mantissa, &exponent[1..])
+    }
+}
+
+/// Describe a constant pool entry by the value it holds or resolves to, strings are not quoted
+fn flatten_constant(class: &ClassFile, index: u16) -> String {
+    let entry = match class.constant_pool.get(&index) {
+        Some(entry) => entry,
+        None => return format!("<invalid constant #{}>", index),
+    };
+    let name = |name_index| {
+        resolve_utf8(&class.constant_pool, name_index)
+            .map(str::to_owned)
+            .unwrap_or_else(|| format!("<invalid name #{}>", name_index))
+    };
+
+    match entry.tag {
+        Tag::ConstantUtf8 => entry.try_cast_into_utf8().unwrap().string.clone(),
+        Tag::ConstantString => {
+            let string_index = entry.try_cast_into_string().unwrap().string_index;
+
+            resolve_utf8(&class.constant_pool, string_index)
+                .map(str::to_owned)
+                .unwrap_or_else(|| format!("<invalid string #{}>", string_index))
+        }
+        Tag::ConstantClass => name(entry.try_cast_into_class().unwrap().name_index),
+        Tag::ConstantFieldRef | Tag::ConstantMethodRef | Tag::ConstantInterfaceMethodRef => {
+            describe_member_ref(class, index)
+        }
+        Tag::ConstantNameAndType => {
+            match resolve_name_and_descriptor(&class.constant_pool, index) {
+                Some((name, descriptor)) => format!("{}:{}", name, descriptor),
+                None => format!("<invalid name and type #{}>", index),
+            }
+        }
+        Tag::ConstantDynamic => {
+            let dynamic = entry.try_cast_into_dynamic().unwrap();
+
+            describe_dynamic_entry(
+                class,
+                dynamic.bootstrap_method_attr_index,
+                dynamic.name_and_type_index,
+            )
+        }
+        Tag::ConstantInvokeDynamic => {
+            let invoke_dynamic = entry.try_cast_into_invoke_dynamic().unwrap();
+
+            describe_dynamic_entry(
+                class,
+                invoke_dynamic.bootstrap_method_attr_index,
+                invoke_dynamic.name_and_type_index,
+            )
+        }
+        Tag::ConstantModule => name(entry.try_cast_into_module().unwrap().name_index),
+        Tag::ConstantPackage => name(entry.try_cast_into_package().unwrap().name_index),
+        _ => describe_constant(class, index, ClassNameForm::Internal),
+    }
+}
+
+/// Quote a CSV field if it contains a separator, a quote, or a line break, quotes are doubled
+fn csv_field(text: &str) -> String {
+    if text.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Escape text for use inside a quoted Graphviz label, the text is shown like a string literal
+///
+/// Graphviz interprets backslashes in labels itself, e.g. `\n` is a line break, so the backslashes
+/// of the string literal escapes are escaped once more
+fn dot_label(text: &str) -> String {
+    escape_string_literal(text)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+}
+
+/// Quote a string as a JSON string, escaping quotes, backslashes, and control characters
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            character if character.is_control() => {
+                quoted.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => quoted.push(character),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+/// Render access flags as a JSON array of their specification names
+fn json_flags<F: Flags>(flags: &[F::AccessFlagType]) -> String {
+    let names: Vec<String> = flags
+        .iter()
+        .map(|flag| json_string(F::spec_name(*flag)))
+        .collect();
+
+    format!("[{}]", names.join(","))
+}
+
+/// Render a field or method as a JSON object with its name, descriptor, and access flags
+fn json_member<F: Flags>(
+    class: &ClassFile,
+    name_index: u16,
+    descriptor_index: u16,
+    access_flags: &[F::AccessFlagType],
+) -> String {
+    format!(
+        "{{\"name\":{},\"descriptor\":{},\"access_flags\":{}}}",
+        json_string(resolve_utf8(&class.constant_pool, name_index).unwrap_or("<invalid name>")),
+        json_string(
+            resolve_utf8(&class.constant_pool, descriptor_index).unwrap_or("<invalid descriptor>")
+        ),
+        json_flags::<F>(access_flags)
+    )
+}
+
+/// Escape a string so it can be shown as a string literal without breaking the output's layout
+fn escape_string_literal(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            _ => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        class_declaration, class_name, csv_field, describe_ldc_constant, dot_label, double_literal,
+        escape_string_literal, field_declaration, field_visibility, float_literal,
+        interfaces_clause, json_string, long_literal, Disassembler, DisassemblerConfig,
+        DisassemblerVisibility,
+    };
+    use crate::byte_reader::ByteReader;
+    use crate::bytecode::{DecodeError, UnknownOpcodes};
+    use crate::classfile::{ClassFile, ClassFileConfig, ClassFileError, ClassNameForm, Tag};
+    use crate::color::{ColorMode, Painter};
+    use crate::flags::{FieldAccessFlags, Flags};
+
+    /// Parse a class file from the integration test fixtures
+    fn parse_fixture(bytes: &[u8]) -> ClassFile {
+        ClassFile::new(&mut ByteReader::from_bytes(bytes), &ClassFileConfig::new()).unwrap()
+    }
+
+    #[test]
+    fn test_write_to_sink() {
+        let mut config = DisassemblerConfig::new();
+        config.with_color_mode(ColorMode::Never);
+
+        let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Plain.class"));
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.starts_with("public class Plain extends java.lang.Object\n"),
+            "The declaration comes first"
+        );
+        assert!(output.contains("\t- public int increment();\n"));
+    }
+
+    #[test]
+    fn test_interfaces_clause() {
+        let painter = Painter::new(ColorMode::Never);
+        let task = parse_fixture(include_bytes!("../tests/fixtures/Task.class"));
+        let plain = parse_fixture(include_bytes!("../tests/fixtures/Plain.class"));
+
+        assert_eq!(
+            interfaces_clause(&task, &painter, ClassNameForm::Binary),
+            " implements java.lang.Runnable, java.io.Serializable"
+        );
+        assert_eq!(
+            interfaces_clause(&plain, &painter, ClassNameForm::Binary),
+            "",
+            "The clause should be omitted when there are no interfaces"
+        );
+    }
+
+    #[test]
+    fn test_visibility_filter() {
+        let private = field_visibility(&FieldAccessFlags::from_u16(0x0002));
+        let protected = field_visibility(&FieldAccessFlags::from_u16(0x0004));
+        let package = field_visibility(&FieldAccessFlags::from_u16(0x0010));
+
+        assert!(DisassemblerVisibility::PRIVATE.allows(&private));
+        assert!(!DisassemblerVisibility::PACKAGE.allows(&private));
+        assert!(DisassemblerVisibility::PACKAGE.allows(&package));
+        assert!(!DisassemblerVisibility::PROTECTED.allows(&package));
+        assert!(DisassemblerVisibility::PROTECTED.allows(&protected));
+        assert!(!DisassemblerVisibility::PUBLIC.allows(&protected));
+    }
+
+    #[test]
+    fn test_config_setters_chain() {
+        let mut config = DisassemblerConfig::new();
+        config
+            .show_line_numbers()
+            .show_assembly_instructions()
+            .with_color_mode(ColorMode::Never);
+
+        assert!(config.show_line_numbers);
+        assert!(config.show_instructions);
+        assert!(!config.show_type_signatures);
+        assert_eq!(config.color_mode, ColorMode::Never);
+    }
+
+    #[test]
+    fn test_escape_string_literal() {
+        assert_eq!(escape_string_literal("plain text"), "plain text");
+        assert_eq!(
+            escape_string_literal("say \"hi\"\n\tC:\\temp"),
+            "say \\\"hi\\\"\\n\\tC:\\\\temp"
+        );
+    }
+
+    #[test]
+    fn test_numeric_literals() {
+        assert_eq!(long_literal(1234567890123), "1234567890123l");
+        assert_eq!(float_literal(1.5), "1.5f");
+        assert_eq!(float_literal(0.1), "0.1f");
+        assert_eq!(float_literal(1e10), "1.0E10f");
+        assert_eq!(float_literal(f32::NAN), "NaNf");
+        assert_eq!(double_literal(0.25), "0.25d");
+        assert_eq!(double_literal(100.0), "100.0d");
+        assert_eq!(double_literal(-0.0), "-0.0d");
+        assert_eq!(double_literal(1.25e-5), "1.25E-5d");
+        assert_eq!(double_literal(f64::INFINITY), "Infinityd");
+        assert_eq!(double_literal(f64::NEG_INFINITY), "-Infinityd");
+    }
+
+    #[test]
+    fn test_exception_table() {
+        let mut config = DisassemblerConfig::new();
+        config
+            .show_assembly_instructions()
+            .with_color_mode(ColorMode::Never);
+
+        let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Guarded.class"));
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("\t\t   40: athrow\n\t  Exception table:\n"));
+        assert!(output.contains("\t\t    0     5    15   java.lang.NumberFormatException\n"));
+        assert!(
+            output.contains("\t\t   28    30    28   any\n"),
+            "Finally blocks catch any exception"
+        );
+    }
+
+    #[test]
+    fn test_class_declaration() {
+        let painter = Painter::new(ColorMode::Never);
+        let suit = parse_fixture(include_bytes!("../tests/fixtures/Suit.class"));
+        let shape = parse_fixture(include_bytes!("../tests/fixtures/Shape.class"));
+
+        #[rustfmt::skip]
+        let object = parse_fixture(&[
+            0xCA, 0xFE, 0xBA, 0xBE,                         // Magic
+            0x00, 0x00, 0x00, 0x3D,                         // Version 61.0
+            0x00, 0x03,                                     // Constant pool count
+            0x01, 0x00, 0x10, b'j', b'a', b'v', b'a', b'/', // #1 = Utf8 "java/lang/Object"
+            b'l', b'a', b'n', b'g', b'/', b'O', b'b', b'j',
+            b'e', b'c', b't',
+            0x07, 0x00, 0x01,                               // #2 = Class #1
+            0x00, 0x21,                                     // Access flags
+            0x00, 0x02,                                     // This class
+            0x00, 0x00,                                     // No super class
+            0x00, 0x00,                                     // Interfaces count
+            0x00, 0x00,                                     // Fields count
+            0x00, 0x00,                                     // Methods count
+            0x00, 0x00,                                     // Attributes count
+        ]);
+
+        assert_eq!(
+            class_declaration(&suit, &painter, ClassNameForm::Binary),
+            "public enum Suit extends java.lang.Enum",
+            "Enums are implicitly final"
+        );
+        assert_eq!(
+            class_declaration(&shape, &painter, ClassNameForm::Binary),
+            "public interface Shape",
+            "Interfaces do not extend java.lang.Object"
+        );
+        assert_eq!(
+            class_declaration(&object, &painter, ClassNameForm::Binary),
+            "public class java.lang.Object",
+            "The root of the class hierarchy has no superclass"
+        );
+    }
+
+    #[test]
+    fn test_bridge_methods_follow_visibility() {
+        let render = |visibility, is_verbose| {
+            let mut config = DisassemblerConfig::new();
+            config
+                .with_visibility(visibility)
+                .with_color_mode(ColorMode::Never);
+
+            if is_verbose {
+                config.show_verbose_info();
+            }
+
+            let mut reader =
+                ByteReader::from_bytes(include_bytes!("../tests/fixtures/Bridged.class"));
+            let mut output = vec![];
+            Disassembler::new(&config, &mut reader)
+                .unwrap()
+                .write_to(&mut output)
+                .unwrap();
+
+            String::from_utf8(output).unwrap()
+        };
+        let bridge = "\t- public int compareTo(java.lang.Object);";
+
+        assert!(!render(DisassemblerVisibility::PUBLIC, false).contains(bridge));
+        assert!(!render(DisassemblerVisibility::PACKAGE, true).contains(bridge));
+        assert!(render(DisassemblerVisibility::PRIVATE, false).contains(&format!("{}\n", bridge)));
+        assert!(render(DisassemblerVisibility::PRIVATE, true)
+            .contains(&format!("{} // bridge, synthetic\n", bridge)));
+    }
+
+    #[test]
+    fn test_instruction_comments() {
+        let mut config = DisassemblerConfig::new();
+        config
+            .show_assembly_instructions()
+            .with_color_mode(ColorMode::Never);
+
+        let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Plain.class"));
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(
+            "\t\t   15: invokevirtual #26                 \
+             // Method java/io/PrintStream.println:(I)V\n"
+        ));
+        assert!(output.contains("\t\t    3: new #8                            // class Plain\n"));
+        assert!(
+            output.contains("\t\t   18: return\n"),
+            "Only constant pool operands have comments"
+        );
+    }
+
+    #[test]
+    fn test_ldc_comments() {
+        let mut config = DisassemblerConfig::new();
+        config
+            .show_assembly_instructions()
+            .with_color_mode(ColorMode::Never);
+
+        let bytes = include_bytes!("../tests/fixtures/Literals.class");
+        let mut reader = ByteReader::from_bytes(bytes);
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let comments: Vec<&str> = output
+            .lines()
+            .filter(|line| line.contains(": ldc"))
+            .filter_map(|line| line.split_once("// ").map(|(_, comment)| comment))
+            .collect();
+
+        assert_eq!(
+            comments,
+            [
+                "String \"hello\"",
+                "int 100000",
+                "float 1.5f",
+                "long 1234567890123l",
+                "double 0.25d",
+                "Class java/lang/Object",
+                "Class [I",
+            ]
+        );
+
+        let class = parse_fixture(bytes);
+        let long_index = *class
+            .constant_pool
+            .iter()
+            .find(|(_, entry)| entry.tag == Tag::ConstantLong)
+            .unwrap()
+            .0;
+        let string_index = *class
+            .constant_pool
+            .iter()
+            .find(|(_, entry)| entry.tag == Tag::ConstantString)
+            .unwrap()
+            .0;
+
+        assert_eq!(
+            describe_ldc_constant(&class, long_index + 1, true),
+            format!("<unusable second slot of constant #{}>", long_index)
+        );
+        assert_eq!(
+            describe_ldc_constant(&class, long_index, false),
+            "<Long cannot be loaded by ldc>"
+        );
+        assert_eq!(
+            describe_ldc_constant(&class, string_index, true),
+            "<String cannot be loaded by ldc2_w>"
+        );
+    }
+
+    #[test]
+    fn test_invokedynamic_comments() {
+        let mut config = DisassemblerConfig::new();
+        config
+            .show_assembly_instructions()
+            .with_color_mode(ColorMode::Never);
+
+        let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Concat.class"));
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(
+            ": invokedynamic #7                  // InvokeDynamic \
+             #0:makeConcatWithConstants:(Ljava/lang/String;I)Ljava/lang/String;\n"
+        ));
+    }
+
+    #[test]
+    fn test_colored_instructions() {
+        let render = |color_mode| {
+            let mut config = DisassemblerConfig::new();
+            config
+                .show_assembly_instructions()
+                .with_color_mode(color_mode);
+
+            let mut reader =
+                ByteReader::from_bytes(include_bytes!("../tests/fixtures/Plain.class"));
+            let mut output = vec![];
+            Disassembler::new(&config, &mut reader)
+                .unwrap()
+                .write_to(&mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let colored = render(ColorMode::Always);
+        assert!(colored.contains("    0: \x1b[32maload_0\x1b[0m\n"));
+        assert!(colored.contains(
+            "    1: \x1b[32minvokespecial\x1b[0m \x1b[33m#1\x1b[0m                  \
+             \x1b[90m// Method java/lang/Object.<init>:()V\x1b[0m\n"
+        ));
+
+        // Comments line up at the same column as in the plain output
+        assert!(render(ColorMode::Never).contains(
+            "    1: invokespecial #1                  // Method java/lang/Object.<init>:()V\n"
+        ));
+    }
+
+    #[test]
+    fn test_unknown_opcodes() {
+        let mut bytes = include_bytes!("../tests/fixtures/Plain.class").to_vec();
+        let dup = bytes
+            .windows(3)
+            .position(|window| window == [0x59, 0x10, 0x29])
+            .expect("main should create a Plain with `dup` followed by `bipush 41`");
+        bytes[dup] = 0xfe;
+
+        let mut config = DisassemblerConfig::new();
+        config
+            .show_assembly_instructions()
+            .with_color_mode(ColorMode::Never);
+
+        let mut output = vec![];
+        Disassembler::new(&config, &mut ByteReader::from_bytes(&bytes))
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("\t\t    6: db 0xfe\n\t\t    7: bipush 41\n"));
+
+        config.with_unknown_opcodes(UnknownOpcodes::Reject);
+        let error = Disassembler::new(&config, &mut ByteReader::from_bytes(&bytes))
+            .err()
+            .expect("unknown opcodes should be rejected");
+        assert_eq!(
+            error,
+            ClassFileError::InvalidCode {
+                method: String::from("main"),
+                error: DecodeError::UnknownOpcode {
+                    opcode: 0xfe,
+                    offset: 6
+                },
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "method main: unknown opcode 0xfe at offset 6"
+        );
+    }
+
+    #[test]
+    fn test_field_declaration() {
+        let class = parse_fixture(include_bytes!("../tests/fixtures/Constants.class"));
+        let declarations = |show_initializer| -> Vec<String> {
+            class
+                .fields
+                .iter()
+                .map(|field| {
+                    field_declaration(&class, field, show_initializer, ClassNameForm::Binary)
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            declarations(true),
+            vec![
+                "public static final int VERSION = 3;",
+                "public static final java.lang.String NAME = \"jadis \\\"disassembler\\\"\";",
+                "public static final boolean ENABLED = true;",
+                "public static final char SEPARATOR = '/';",
+                "public static final double RATIO = 0.5d;",
+                "protected static volatile long counter;",
+                "private java.util.List list;",
+            ]
+        );
+        assert_eq!(declarations(false)[0], "public static final int VERSION;");
+    }
+
+    #[test]
+    fn test_array_class_names() {
+        let class = parse_fixture(include_bytes!("../tests/fixtures/Casts.class"));
+        let class_names = |form| -> Vec<String> {
+            class
+                .constant_pool
+                .values()
+                .filter_map(|entry| entry.try_cast_into_class())
+                .map(|entry| class_name(&class, entry.name_index, form))
+                .collect()
+        };
+
+        let names = class_names(ClassNameForm::Binary);
+        assert!(names.contains(&"java.lang.String[]".to_owned()));
+        assert!(names.contains(&"int[][]".to_owned()));
+        assert!(names.contains(&"java.lang.Object".to_owned()));
+
+        let names = class_names(ClassNameForm::Internal);
+        assert!(names.contains(&"java/lang/String[]".to_owned()));
+        assert!(names.contains(&"int[][]".to_owned()));
+        assert!(names.contains(&"java/lang/Object".to_owned()));
+    }
+
+    #[test]
+    fn test_internal_names() {
+        let mut config = DisassemblerConfig::new();
+        config.with_color_mode(ColorMode::Never);
+
+        let disassemble = |config: &DisassemblerConfig| {
+            let mut reader =
+                ByteReader::from_bytes(include_bytes!("../tests/fixtures/Plain.class"));
+            let mut output = vec![];
+            Disassembler::new(config, &mut reader)
+                .unwrap()
+                .write_to(&mut output)
+                .unwrap();
+
+            String::from_utf8(output).unwrap()
+        };
+
+        let dotted = disassemble(&config);
+        let slashed = disassemble(config.show_internal_names());
+
+        assert!(dotted.contains("public static void main(java.lang.String[]);"));
+        assert!(slashed.contains("public static void main(java/lang/String[]);"));
+        assert_eq!(dotted.replace("java.lang.", "java/lang/"), slashed);
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("java/lang/Object"), "java/lang/Object");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("java/lang/Object"), "\"java/lang/Object\"");
+        assert_eq!(json_string("a \"b\" \\c"), "\"a \\\"b\\\" \\\\c\"");
+        assert_eq!(json_string("line\nbreak\u{1}"), "\"line\\nbreak\\u0001\"");
+    }
+
+    #[test]
+    fn test_json_line() {
+        let config = DisassemblerConfig::new();
+        let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Shape.class"));
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_json_line(&mut output, "fixtures/Shape.class")
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"source\":\"fixtures/Shape.class\",\"class\":\"Shape\",\
+             \"super_class\":\"java.lang.Object\",\"interfaces\":[],\"major_version\":61,\
+             \"minor_version\":0,\"access_flags\":[\"ACC_PUBLIC\",\"ACC_INTERFACE\",\
+             \"ACC_ABSTRACT\"],\"fields\":[],\"methods\":[{\"name\":\"area\",\
+             \"descriptor\":\"()D\",\"access_flags\":[\"ACC_PUBLIC\",\"ACC_ABSTRACT\"]},\
+             {\"name\":\"describe\",\"descriptor\":\"()Ljava/lang/String;\",\
+             \"access_flags\":[\"ACC_PUBLIC\"]}]}\n"
+        );
+    }
+
+    #[test]
+    fn test_pool_csv() {
+        let mut config = DisassemblerConfig::new();
+        config.show_pool_csv();
+
+        let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Guarded.class"));
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let rows: Vec<&str> = output.lines().collect();
+
+        assert_eq!(rows[0], "index,tag,value");
+        assert_eq!(rows[1], "1,Methodref,java/lang/Object.<init>:()V");
+        assert_eq!(rows[2], "2,Class,java/lang/Object");
+        assert_eq!(rows[3], "3,NameAndType,<init>:()V");
+        assert!(rows.contains(&"19,String,done"));
+        assert!(
+            !output.contains("public class"),
+            "The regular output is replaced entirely"
+        );
+    }
+
+    #[test]
+    fn test_pool_graph() {
+        let mut config = DisassemblerConfig::new();
+        config.show_pool_graph();
+
+        let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Guarded.class"));
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "digraph constant_pool {");
+        assert_eq!(lines.last(), Some(&"}"));
+        assert!(lines.contains(&"    2 [label=\"#2 Class\\njava/lang/Object\"];"));
+        assert!(lines.contains(&"    1 -> 2 [label=\"class_index\"];"));
+        assert!(lines.contains(&"    1 -> 3 [label=\"name_and_type_index\"];"));
+        assert!(lines.contains(&"    2 -> 4 [label=\"name_index\"];"));
+        assert!(
+            !output.contains("<missing>"),
+            "Only references to missing entries get a node of their own"
+        );
+
+        assert_eq!(dot_label("say \"hi\"\n"), r#"say \\\"hi\\\"\\n"#);
+    }
+
+    #[test]
+    fn test_output_format() {
+        let mut config = DisassemblerConfig::new();
+        config
+            .show_assembly_instructions()
+            .with_indent("  ")
+            .with_comment_column(20)
+            .with_color_mode(ColorMode::Never);
+
+        let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Plain.class"));
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("\n  - public int increment();\n    Code:\n"));
+        assert!(output.contains("\n        2: getfield #7   // Field Plain.counter:I\n"));
+        assert!(!output.contains('\t'), "Tabs are replaced entirely");
+    }
+
+    #[test]
+    fn test_method_filter() {
+        let render = |name, descriptor| {
+            let mut config = DisassemblerConfig::new();
+            config
+                .with_method_filter(name, descriptor)
+                .with_color_mode(ColorMode::Never);
+
+            let mut reader =
+                ByteReader::from_bytes(include_bytes!("../tests/fixtures/Bridged.class"));
+            let mut output = vec![];
+            Disassembler::new(&config, &mut reader)
+                .unwrap()
+                .write_to(&mut output)
+                .unwrap();
+
+            String::from_utf8(output).unwrap()
+        };
+
+        let overloads = render("compareTo", None);
+        assert!(overloads.contains("\t- public int compareTo(Bridged);\n"));
+        assert!(!overloads.contains("Fields:"), "Only methods are shown");
+        assert!(!overloads.contains("Bridged(int)"));
+
+        let exact = render("compareTo", Some("(LBridged;)I"));
+        assert!(exact.contains("\t- public int compareTo(Bridged);\n"));
+
+        assert!(render("compareTo", Some("()V")).contains("\tNo such method: compareTo()V\n"));
+        assert!(render("missing", None).contains("\tNo such method: missing\n"));
+    }
+
+    #[test]
+    fn test_empty_interface() {
+        let render = |config: &DisassemblerConfig| {
+            let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Pet.class"));
+            let mut output = vec![];
+            Disassembler::new(config, &mut reader)
+                .unwrap()
+                .write_to(&mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let mut config = DisassemblerConfig::new();
+        config.with_color_mode(ColorMode::Never);
+        assert_eq!(
+            render(&config),
+            "interface Pet\n{\n}\n",
+            "Empty member lists do not get a section"
+        );
+
+        config.show_verbose_info();
+        assert!(render(&config).ends_with("\n{\n}\nAttributes: [SourceFile]\n"));
+    }
+
+    #[test]
+    fn test_constant_pool_is_verbose() {
+        let render = |config: &mut DisassemblerConfig| {
+            let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Pet.class"));
+            let mut output = vec![];
+            Disassembler::new(config.with_color_mode(ColorMode::Never), &mut reader)
+                .unwrap()
+                .write_to(&mut output)
+                .unwrap();
+
+            String::from_utf8(output).unwrap()
+        };
+
+        let output = render(&mut DisassemblerConfig::new());
+        assert!(!output.contains("Constant pool") && !output.contains("Access flags:"));
+
+        let output = render(DisassemblerConfig::new().show_verbose_info());
+        assert!(output.contains("Constant pool (6 entries, count 7):\n#1 = Class"));
+        assert!(output.contains("Access flags:\n\t- AccInterface\n"));
+
+        let output = render(DisassemblerConfig::new().show_constants_only());
+        assert!(output.contains("Constant pool (6 entries, count 7):\n"));
+        assert!(!output.contains("Access flags:"));
+    }
+
+    #[test]
+    fn test_preview_version() {
+        let mut bytes = include_bytes!("../tests/fixtures/Plain.class").to_vec();
+        bytes[4] = 0xFF;
+        bytes[5] = 0xFF;
+
+        let mut config = DisassemblerConfig::new();
+        config
+            .show_verbose_info()
+            .with_min_major_version(0)
+            .with_color_mode(ColorMode::Never);
+
+        let render = |bytes: &[u8]| {
+            let mut output = vec![];
+            Disassembler::new(&config, &mut ByteReader::from_bytes(bytes))
+                .unwrap()
+                .write_to(&mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        assert!(render(&bytes)
+            .contains("Version: 61.65535 (preview, requires Java SE 17 with --enable-preview)\n"));
+
+        // Class files older than Java SE 12 cannot depend on preview features
+        bytes[6..8].copy_from_slice(&40u16.to_be_bytes());
+        assert!(render(&bytes).contains("Version: 40.65535\n"));
+    }
+
+    #[test]
+    fn test_member_flags_line() {
+        let render = |is_verbose| {
+            let mut config = DisassemblerConfig::new();
+            config
+                .with_visibility(DisassemblerVisibility::PRIVATE)
+                .with_color_mode(ColorMode::Never);
+
+            if is_verbose {
+                config.show_verbose_info();
+            }
+
+            let mut reader =
+                ByteReader::from_bytes(include_bytes!("../tests/fixtures/Bridged.class"));
+            let mut output = vec![];
+            Disassembler::new(&config, &mut reader)
+                .unwrap()
+                .write_to(&mut output)
+                .unwrap();
+
+            String::from_utf8(output).unwrap()
+        };
+        let verbose = render(true);
+
+        assert!(verbose
+            .contains("\t- private final int rank;\n\t  Flags: (0x0012) ACC_PRIVATE, ACC_FINAL\n"));
+        assert!(verbose.contains(
+            "// bridge, synthetic\n\t  Flags: (0x1041) ACC_PUBLIC, ACC_BRIDGE, ACC_SYNTHETIC\n"
+        ));
+        assert!(
+            !render(false).contains("\t  Flags:"),
+            "Member flags are only listed in verbose mode"
+        );
+    }
+
+    #[test]
+    fn test_member_attributes() {
+        let render = |config: &DisassemblerConfig| {
+            let mut reader =
+                ByteReader::from_bytes(include_bytes!("../tests/fixtures/Guarded.class"));
+            let mut output = vec![];
+            Disassembler::new(config, &mut reader)
+                .unwrap()
+                .write_to(&mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+
+        let mut config = DisassemblerConfig::new();
+        config.with_color_mode(ColorMode::Never);
+        assert!(
+            !render(&config).contains("\t  Attributes"),
+            "Member attributes are only listed in verbose mode"
+        );
+
+        config.show_verbose_info();
+        assert!(render(&config).contains("\t  Attributes:\n\t\t- Code\n"));
+    }
+
+    #[test]
+    fn test_bootstrap_arguments() {
+        let mut config = DisassemblerConfig::new();
+        config.show_verbose_info().with_color_mode(ColorMode::Never);
+
+        let mut reader = ByteReader::from_bytes(include_bytes!("../tests/fixtures/Lambdas.class"));
+        let mut output = vec![];
+        Disassembler::new(&config, &mut reader)
+            .unwrap()
+            .write_to(&mut output)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
 
-        Self { config, class }
+        assert!(output.contains("// invokestatic java/lang/invoke/LambdaMetafactory.metafactory:"));
+        assert!(output.contains(
+            "\t\t- #28 // (Ljava/lang/Object;)Ljava/lang/Object;\n\
+             \t\t- #30 // REF_invokeInterface java/util/List.size:()I\n\
+             \t\t- #36 // (Ljava/util/List;)Ljava/lang/Integer;\n"
+        ));
     }
 }
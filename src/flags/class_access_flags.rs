@@ -1,10 +1,8 @@
-use crate::utils::bitmask_matches;
-
 use super::Flags;
 
 /// Class access and property flags
 // TODO: remove debug directive
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ClassAccessFlags {
     /// Declared public; may be accessed from outside its package
     AccPublic,
@@ -37,47 +35,32 @@ pub enum ClassAccessFlags {
 impl Flags for ClassAccessFlags {
     type AccessFlagType = ClassAccessFlags;
 
-    fn from_u16(value: u16) -> Vec<Self::AccessFlagType> {
-        let mut flags = vec![];
-
-        if bitmask_matches(value, 0x0001) {
-            flags.push(Self::AccPublic);
-        }
-
-        if bitmask_matches(value, 0x0010) {
-            flags.push(Self::AccFinal);
-        }
-
-        if bitmask_matches(value, 0x0020) {
-            flags.push(Self::AccSuper);
-        }
-
-        if bitmask_matches(value, 0x0200) {
-            flags.push(Self::AccInterface);
-        }
-
-        if bitmask_matches(value, 0x0400) {
-            flags.push(Self::AccAbstract);
-        }
-
-        if bitmask_matches(value, 0x1000) {
-            flags.push(Self::AccSynthetic);
-        }
-
-        if bitmask_matches(value, 0x2000) {
-            flags.push(Self::AccAnnotation);
-        }
-
-        if bitmask_matches(value, 0x4000) {
-            flags.push(Self::AccEnum);
-        }
+    fn all() -> &'static [(Self::AccessFlagType, u16)] {
+        &[
+            (Self::AccPublic, 0x0001),
+            (Self::AccFinal, 0x0010),
+            (Self::AccSuper, 0x0020),
+            (Self::AccInterface, 0x0200),
+            (Self::AccAbstract, 0x0400),
+            (Self::AccSynthetic, 0x1000),
+            (Self::AccAnnotation, 0x2000),
+            (Self::AccEnum, 0x4000),
+            (Self::AccModule, 0x8000),
+        ]
+    }
 
-        if bitmask_matches(value, 0x8000) {
-            flags.push(Self::AccModule);
+    fn spec_name(flag: Self::AccessFlagType) -> &'static str {
+        match flag {
+            Self::AccPublic => "ACC_PUBLIC",
+            Self::AccFinal => "ACC_FINAL",
+            Self::AccSuper => "ACC_SUPER",
+            Self::AccInterface => "ACC_INTERFACE",
+            Self::AccAbstract => "ACC_ABSTRACT",
+            Self::AccSynthetic => "ACC_SYNTHETIC",
+            Self::AccAnnotation => "ACC_ANNOTATION",
+            Self::AccEnum => "ACC_ENUM",
+            Self::AccModule => "ACC_MODULE",
         }
-
-        assert!(flags.len() > 0, "Bitmask does not specify ANY access flags");
-        flags
     }
 }
 
@@ -178,4 +161,52 @@ mod tests {
             "Incorrect access flags returned"
         );
     }
+
+    #[test]
+    fn test_class_access_flags_all() {
+        for (flag, bitmask) in ClassAccessFlags::all() {
+            assert_eq!(
+                ClassAccessFlags::from_u16(*bitmask),
+                vec![*flag],
+                "Bitmask should map back onto its own flag"
+            );
+        }
+
+        assert_eq!(
+            ClassAccessFlags::all().len(),
+            9,
+            "Incorrect number of flags"
+        );
+    }
+
+    #[test]
+    fn test_class_access_flags_describe() {
+        let flags = ClassAccessFlags::from_u16(0x0021);
+
+        assert_eq!(ClassAccessFlags::to_u16(&flags), 0x0021);
+        assert_eq!(
+            ClassAccessFlags::describe(0x0021),
+            "(0x0021) ACC_PUBLIC, ACC_SUPER"
+        );
+        assert_eq!(ClassAccessFlags::describe(0x0000), "(0x0000)");
+    }
+
+    #[test]
+    fn test_class_access_flags_in_declaration_order() {
+        assert_eq!(
+            ClassAccessFlags::from_u16(0xFFFF),
+            vec![
+                ClassAccessFlags::AccPublic,
+                ClassAccessFlags::AccFinal,
+                ClassAccessFlags::AccSuper,
+                ClassAccessFlags::AccInterface,
+                ClassAccessFlags::AccAbstract,
+                ClassAccessFlags::AccSynthetic,
+                ClassAccessFlags::AccAnnotation,
+                ClassAccessFlags::AccEnum,
+                ClassAccessFlags::AccModule,
+            ],
+            "Flags should be returned in declaration order"
+        );
+    }
 }
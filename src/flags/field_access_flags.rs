@@ -1,10 +1,8 @@
-use crate::utils::bitmask_matches;
-
 use super::Flags;
 
 /// Field access and property flags
 // TODO: remove debug directive
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FieldAccessFlags {
     /// Declared public; may be accessed from outside its package
     AccPublic,
@@ -37,47 +35,32 @@ pub enum FieldAccessFlags {
 impl Flags for FieldAccessFlags {
     type AccessFlagType = FieldAccessFlags;
 
-    fn from_u16(value: u16) -> Vec<Self::AccessFlagType> {
-        let mut flags = vec![];
-
-        if bitmask_matches(value, 0x0001) {
-            flags.push(Self::AccPublic);
-        }
-
-        if bitmask_matches(value, 0x0002) {
-            flags.push(Self::AccPrivate);
-        }
-
-        if bitmask_matches(value, 0x0004) {
-            flags.push(Self::AccProtected);
-        }
-
-        if bitmask_matches(value, 0x0008) {
-            flags.push(Self::AccStatic);
-        }
-
-        if bitmask_matches(value, 0x0010) {
-            flags.push(Self::AccFinal);
-        }
-
-        if bitmask_matches(value, 0x0040) {
-            flags.push(Self::AccVolatile);
-        }
-
-        if bitmask_matches(value, 0x0080) {
-            flags.push(Self::AccTransient);
-        }
-
-        if bitmask_matches(value, 0x1000) {
-            flags.push(Self::AccSynthetic);
-        }
+    fn all() -> &'static [(Self::AccessFlagType, u16)] {
+        &[
+            (Self::AccPublic, 0x0001),
+            (Self::AccPrivate, 0x0002),
+            (Self::AccProtected, 0x0004),
+            (Self::AccStatic, 0x0008),
+            (Self::AccFinal, 0x0010),
+            (Self::AccVolatile, 0x0040),
+            (Self::AccTransient, 0x0080),
+            (Self::AccSynthetic, 0x1000),
+            (Self::AccEnum, 0x4000),
+        ]
+    }
 
-        if bitmask_matches(value, 0x4000) {
-            flags.push(Self::AccEnum);
+    fn spec_name(flag: Self::AccessFlagType) -> &'static str {
+        match flag {
+            Self::AccPublic => "ACC_PUBLIC",
+            Self::AccPrivate => "ACC_PRIVATE",
+            Self::AccProtected => "ACC_PROTECTED",
+            Self::AccStatic => "ACC_STATIC",
+            Self::AccFinal => "ACC_FINAL",
+            Self::AccVolatile => "ACC_VOLATILE",
+            Self::AccTransient => "ACC_TRANSIENT",
+            Self::AccSynthetic => "ACC_SYNTHETIC",
+            Self::AccEnum => "ACC_ENUM",
         }
-
-        assert!(flags.len() > 0, "Bitmask does not specify ANY access flags");
-        flags
     }
 }
 
@@ -85,6 +68,14 @@ mod tests {
     use super::FieldAccessFlags;
     use super::Flags;
 
+    #[test]
+    fn test_field_access_flag_none() {
+        assert!(
+            FieldAccessFlags::from_u16(0x0000).is_empty(),
+            "Package-private fields do not have any access flags"
+        );
+    }
+
     #[test]
     fn test_field_access_flag_public() {
         assert_eq!(
@@ -179,4 +170,23 @@ mod tests {
             "Incorrect access flags returned"
         );
     }
+
+    #[test]
+    fn test_field_access_flags_in_declaration_order() {
+        assert_eq!(
+            FieldAccessFlags::from_u16(0xFFFF),
+            vec![
+                FieldAccessFlags::AccPublic,
+                FieldAccessFlags::AccPrivate,
+                FieldAccessFlags::AccProtected,
+                FieldAccessFlags::AccStatic,
+                FieldAccessFlags::AccFinal,
+                FieldAccessFlags::AccVolatile,
+                FieldAccessFlags::AccTransient,
+                FieldAccessFlags::AccSynthetic,
+                FieldAccessFlags::AccEnum,
+            ],
+            "Flags should be returned in declaration order"
+        );
+    }
 }
@@ -1,10 +1,8 @@
-use crate::utils::bitmask_matches;
-
 use super::Flags;
 
 /// Method access and property flags
 // TODO: remove debug directive
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MethodAccessFlags {
     /// Declared public; may be accessed from outside its package
     AccPublic,
@@ -46,59 +44,38 @@ pub enum MethodAccessFlags {
 impl Flags for MethodAccessFlags {
     type AccessFlagType = MethodAccessFlags;
 
-    fn from_u16(value: u16) -> Vec<Self::AccessFlagType> {
-        let mut flags = vec![];
-
-        if bitmask_matches(value, 0x0001) {
-            flags.push(Self::AccPublic);
-        }
-
-        if bitmask_matches(value, 0x0002) {
-            flags.push(Self::AccPrivate);
-        }
-
-        if bitmask_matches(value, 0x0004) {
-            flags.push(Self::AccProtected);
-        }
-
-        if bitmask_matches(value, 0x0008) {
-            flags.push(Self::AccStatic);
-        }
-
-        if bitmask_matches(value, 0x0010) {
-            flags.push(Self::AccFinal);
-        }
-
-        if bitmask_matches(value, 0x0020) {
-            flags.push(Self::AccSynchronized);
-        }
-
-        if bitmask_matches(value, 0x0040) {
-            flags.push(Self::AccBridge);
-        }
-
-        if bitmask_matches(value, 0x0080) {
-            flags.push(Self::AccVarArgs);
-        }
-
-        if bitmask_matches(value, 0x0100) {
-            flags.push(Self::AccNative);
-        }
-
-        if bitmask_matches(value, 0x0400) {
-            flags.push(Self::AccAbstract);
-        }
-
-        if bitmask_matches(value, 0x0800) {
-            flags.push(Self::AccStrict);
-        }
+    fn all() -> &'static [(Self::AccessFlagType, u16)] {
+        &[
+            (Self::AccPublic, 0x0001),
+            (Self::AccPrivate, 0x0002),
+            (Self::AccProtected, 0x0004),
+            (Self::AccStatic, 0x0008),
+            (Self::AccFinal, 0x0010),
+            (Self::AccSynchronized, 0x0020),
+            (Self::AccBridge, 0x0040),
+            (Self::AccVarArgs, 0x0080),
+            (Self::AccNative, 0x0100),
+            (Self::AccAbstract, 0x0400),
+            (Self::AccStrict, 0x0800),
+            (Self::AccSynthetic, 0x1000),
+        ]
+    }
 
-        if bitmask_matches(value, 0x1000) {
-            flags.push(Self::AccSynthetic);
+    fn spec_name(flag: Self::AccessFlagType) -> &'static str {
+        match flag {
+            Self::AccPublic => "ACC_PUBLIC",
+            Self::AccPrivate => "ACC_PRIVATE",
+            Self::AccProtected => "ACC_PROTECTED",
+            Self::AccStatic => "ACC_STATIC",
+            Self::AccFinal => "ACC_FINAL",
+            Self::AccSynchronized => "ACC_SYNCHRONIZED",
+            Self::AccBridge => "ACC_BRIDGE",
+            Self::AccVarArgs => "ACC_VARARGS",
+            Self::AccNative => "ACC_NATIVE",
+            Self::AccAbstract => "ACC_ABSTRACT",
+            Self::AccStrict => "ACC_STRICT",
+            Self::AccSynthetic => "ACC_SYNTHETIC",
         }
-
-        assert!(flags.len() > 0, "Bitmask does not specify ANY access flags");
-        flags
     }
 }
 
@@ -230,4 +207,26 @@ mod tests {
             "Incorrect access flags returned"
         );
     }
+
+    #[test]
+    fn test_method_access_flags_in_declaration_order() {
+        assert_eq!(
+            MethodAccessFlags::from_u16(0xFFFF),
+            vec![
+                MethodAccessFlags::AccPublic,
+                MethodAccessFlags::AccPrivate,
+                MethodAccessFlags::AccProtected,
+                MethodAccessFlags::AccStatic,
+                MethodAccessFlags::AccFinal,
+                MethodAccessFlags::AccSynchronized,
+                MethodAccessFlags::AccBridge,
+                MethodAccessFlags::AccVarArgs,
+                MethodAccessFlags::AccNative,
+                MethodAccessFlags::AccAbstract,
+                MethodAccessFlags::AccStrict,
+                MethodAccessFlags::AccSynthetic,
+            ],
+            "Flags should be returned in declaration order"
+        );
+    }
 }
@@ -1,9 +1,7 @@
-use crate::utils::bitmask_matches;
-
 use super::Flags;
 
 /// Method parameter access flags
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum MethodParameterAccessFlags {
     /// Indicates that the formal parameter was declared `final`
     AccFinal,
@@ -26,23 +24,20 @@ pub enum MethodParameterAccessFlags {
 impl Flags for MethodParameterAccessFlags {
     type AccessFlagType = MethodParameterAccessFlags;
 
-    fn from_u16(value: u16) -> Vec<Self::AccessFlagType> {
-        let mut flags = vec![];
-
-        if bitmask_matches(value, 0x0010) {
-            flags.push(Self::AccFinal);
-        }
-
-        if bitmask_matches(value, 0x1000) {
-            flags.push(Self::AccSynthetic);
-        }
+    fn all() -> &'static [(Self::AccessFlagType, u16)] {
+        &[
+            (Self::AccFinal, 0x0010),
+            (Self::AccSynthetic, 0x1000),
+            (Self::AccMandated, 0x8000),
+        ]
+    }
 
-        if bitmask_matches(value, 0x8000) {
-            flags.push(Self::AccMandated);
+    fn spec_name(flag: Self::AccessFlagType) -> &'static str {
+        match flag {
+            Self::AccFinal => "ACC_FINAL",
+            Self::AccSynthetic => "ACC_SYNTHETIC",
+            Self::AccMandated => "ACC_MANDATED",
         }
-
-        assert!(flags.len() > 0, "Bitmask does not specify ANY access flags");
-        flags
     }
 }
 
@@ -89,4 +84,17 @@ mod tests {
             "Incorrect access flags returned"
         );
     }
+
+    #[test]
+    fn test_method_parameter_access_flags_in_declaration_order() {
+        assert_eq!(
+            MethodParameterAccessFlags::from_u16(0xFFFF),
+            vec![
+                MethodParameterAccessFlags::AccFinal,
+                MethodParameterAccessFlags::AccSynthetic,
+                MethodParameterAccessFlags::AccMandated,
+            ],
+            "Flags should be returned in declaration order"
+        );
+    }
 }
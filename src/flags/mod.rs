@@ -21,10 +21,97 @@ mod module_opens_flags;
 mod module_requires_flags;
 mod nested_class_access_flags;
 
+use crate::utils::bitmask_matches;
+
 /// Base trait for all flag types
 pub trait Flags {
-    type AccessFlagType;
+    type AccessFlagType: Copy + PartialEq + 'static;
+
+    /// Fetch every flag paired with its bitmask, in ascending order of the bitmask
+    ///
+    /// Flag types declare their variants in this same order
+    fn all() -> &'static [(Self::AccessFlagType, u16)];
 
     /// Fetch all flags from a value
-    fn from_u16(value: u16) -> Vec<Self::AccessFlagType>;
+    ///
+    /// The flags are returned in ascending order of their bitmask, which is also the order in which
+    /// they are declared. This order is stable and matches the conventional order of modifiers in
+    /// the Java programming language, e.g. `public static final`, which the disassembler relies on
+    /// to print keywords. A value without any flags is valid, e.g. for package-private members, and
+    /// results in an empty list.
+    fn from_u16(value: u16) -> Vec<Self::AccessFlagType> {
+        Self::all()
+            .iter()
+            .filter(|(_, bitmask)| bitmask_matches(value, *bitmask))
+            .map(|(flag, _)| *flag)
+            .collect()
+    }
+
+    /// Fetch the name of a flag as used in the specification, e.g. `ACC_PUBLIC`
+    fn spec_name(flag: Self::AccessFlagType) -> &'static str;
+
+    /// Combine flags back into the value they were decoded from
+    ///
+    /// Bits that do not correspond to any known flag were dropped while decoding, so they are not
+    /// part of the result
+    fn to_u16(flags: &[Self::AccessFlagType]) -> u16 {
+        Self::all()
+            .iter()
+            .filter(|(flag, _)| flags.contains(flag))
+            .fold(0, |value, (_, bitmask)| value | bitmask)
+    }
+
+    /// Describe a value the way `javap -v` does, e.g. `(0x0021) ACC_PUBLIC, ACC_SUPER`
+    fn describe(value: u16) -> String {
+        let names: Vec<&str> = Self::from_u16(value)
+            .into_iter()
+            .map(Self::spec_name)
+            .collect();
+
+        if names.is_empty() {
+            format!("({:#06x})", value)
+        } else {
+            format!("({:#06x}) {}", value, names.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ClassAccessFlags, FieldAccessFlags, Flags, MethodAccessFlags, MethodParameterAccessFlags,
+        ModuleExportsFlags, ModuleFlags, ModuleOpensFlags, ModuleRequiresFlags,
+        NestedClassAccessFlags,
+    };
+
+    /// Check whether the bitmasks of a flag type are listed in strictly ascending order
+    fn is_ascending<F: Flags>() -> bool {
+        F::all().windows(2).all(|pair| pair[0].1 < pair[1].1)
+    }
+
+    #[test]
+    fn test_bitmasks_ascending() {
+        assert!(is_ascending::<ClassAccessFlags>());
+        assert!(is_ascending::<FieldAccessFlags>());
+        assert!(is_ascending::<MethodAccessFlags>());
+        assert!(is_ascending::<MethodParameterAccessFlags>());
+        assert!(is_ascending::<ModuleExportsFlags>());
+        assert!(is_ascending::<ModuleFlags>());
+        assert!(is_ascending::<ModuleOpensFlags>());
+        assert!(is_ascending::<ModuleRequiresFlags>());
+        assert!(is_ascending::<NestedClassAccessFlags>());
+    }
+
+    #[test]
+    fn test_conventional_modifier_order() {
+        assert_eq!(
+            FieldAccessFlags::from_u16(0x0019),
+            vec![
+                FieldAccessFlags::AccPublic,
+                FieldAccessFlags::AccStatic,
+                FieldAccessFlags::AccFinal,
+            ],
+            "Modifiers should come out as `public static final`"
+        );
+    }
 }
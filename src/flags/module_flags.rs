@@ -1,9 +1,7 @@
-use crate::utils::bitmask_matches;
-
 use super::Flags;
 
 /// Module flags
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ModuleFlags {
     /// Indicates that this module is open
     AccOpen,
@@ -18,23 +16,20 @@ pub enum ModuleFlags {
 impl Flags for ModuleFlags {
     type AccessFlagType = ModuleFlags;
 
-    fn from_u16(value: u16) -> Vec<Self::AccessFlagType> {
-        let mut flags = vec![];
-
-        if bitmask_matches(value, 0x0020) {
-            flags.push(Self::AccOpen);
-        }
-
-        if bitmask_matches(value, 0x1000) {
-            flags.push(Self::AccSynthetic);
-        }
+    fn all() -> &'static [(Self::AccessFlagType, u16)] {
+        &[
+            (Self::AccOpen, 0x0020),
+            (Self::AccSynthetic, 0x1000),
+            (Self::AccMandated, 0x8000),
+        ]
+    }
 
-        if bitmask_matches(value, 0x8000) {
-            flags.push(Self::AccMandated);
+    fn spec_name(flag: Self::AccessFlagType) -> &'static str {
+        match flag {
+            Self::AccOpen => "ACC_OPEN",
+            Self::AccSynthetic => "ACC_SYNTHETIC",
+            Self::AccMandated => "ACC_MANDATED",
         }
-
-        assert!(flags.len() > 0, "Bitmask does not specify ANY access flags");
-        flags
     }
 }
 
@@ -81,4 +76,17 @@ mod tests {
             "Incorrect access flags returned"
         );
     }
+
+    #[test]
+    fn test_module_access_flags_in_declaration_order() {
+        assert_eq!(
+            ModuleFlags::from_u16(0xFFFF),
+            vec![
+                ModuleFlags::AccOpen,
+                ModuleFlags::AccSynthetic,
+                ModuleFlags::AccMandated,
+            ],
+            "Flags should be returned in declaration order"
+        );
+    }
 }
@@ -1,9 +1,7 @@
-use crate::utils::bitmask_matches;
-
 use super::Flags;
 
 /// Module opens flags
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ModuleOpensFlags {
     /// Indicates that this opening was not explicitly or implicitly declared in the source of the
     /// module declaration
@@ -16,19 +14,15 @@ pub enum ModuleOpensFlags {
 impl Flags for ModuleOpensFlags {
     type AccessFlagType = ModuleOpensFlags;
 
-    fn from_u16(value: u16) -> Vec<Self::AccessFlagType> {
-        let mut flags = vec![];
-
-        if bitmask_matches(value, 0x1000) {
-            flags.push(Self::AccSynthetic);
-        }
+    fn all() -> &'static [(Self::AccessFlagType, u16)] {
+        &[(Self::AccSynthetic, 0x1000), (Self::AccMandated, 0x8000)]
+    }
 
-        if bitmask_matches(value, 0x8000) {
-            flags.push(Self::AccMandated);
+    fn spec_name(flag: Self::AccessFlagType) -> &'static str {
+        match flag {
+            Self::AccSynthetic => "ACC_SYNTHETIC",
+            Self::AccMandated => "ACC_MANDATED",
         }
-
-        assert!(flags.len() > 0, "Bitmask does not specify ANY access flags");
-        flags
     }
 }
 
@@ -65,4 +59,16 @@ mod tests {
             "Incorrect access flags returned"
         );
     }
+
+    #[test]
+    fn test_module_opens_access_flags_in_declaration_order() {
+        assert_eq!(
+            ModuleOpensFlags::from_u16(0xFFFF),
+            vec![
+                ModuleOpensFlags::AccSynthetic,
+                ModuleOpensFlags::AccMandated,
+            ],
+            "Flags should be returned in declaration order"
+        );
+    }
 }
@@ -1,9 +1,7 @@
-use crate::utils::bitmask_matches;
-
 use super::Flags;
 
 /// Module requires flags
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ModuleRequiresFlags {
     /// Indicates that any module which depends on the current module, implicitly declares a
     /// dependence on the module indicated by this entry
@@ -25,27 +23,22 @@ pub enum ModuleRequiresFlags {
 impl Flags for ModuleRequiresFlags {
     type AccessFlagType = ModuleRequiresFlags;
 
-    fn from_u16(value: u16) -> Vec<Self::AccessFlagType> {
-        let mut flags = vec![];
-
-        if bitmask_matches(value, 0x0020) {
-            flags.push(Self::AccTransitive);
-        }
-
-        if bitmask_matches(value, 0x0040) {
-            flags.push(Self::AccStaticPhase);
-        }
-
-        if bitmask_matches(value, 0x1000) {
-            flags.push(Self::AccSynthetic);
-        }
+    fn all() -> &'static [(Self::AccessFlagType, u16)] {
+        &[
+            (Self::AccTransitive, 0x0020),
+            (Self::AccStaticPhase, 0x0040),
+            (Self::AccSynthetic, 0x1000),
+            (Self::AccMandated, 0x8000),
+        ]
+    }
 
-        if bitmask_matches(value, 0x8000) {
-            flags.push(Self::AccMandated);
+    fn spec_name(flag: Self::AccessFlagType) -> &'static str {
+        match flag {
+            Self::AccTransitive => "ACC_TRANSITIVE",
+            Self::AccStaticPhase => "ACC_STATIC_PHASE",
+            Self::AccSynthetic => "ACC_SYNTHETIC",
+            Self::AccMandated => "ACC_MANDATED",
         }
-
-        assert!(flags.len() > 0, "Bitmask does not specify ANY access flags");
-        flags
     }
 }
 
@@ -102,4 +95,18 @@ mod tests {
             "Incorrect access flags returned"
         );
     }
+
+    #[test]
+    fn test_module_requires_access_flags_in_declaration_order() {
+        assert_eq!(
+            ModuleRequiresFlags::from_u16(0xFFFF),
+            vec![
+                ModuleRequiresFlags::AccTransitive,
+                ModuleRequiresFlags::AccStaticPhase,
+                ModuleRequiresFlags::AccSynthetic,
+                ModuleRequiresFlags::AccMandated,
+            ],
+            "Flags should be returned in declaration order"
+        );
+    }
 }
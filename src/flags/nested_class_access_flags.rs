@@ -1,10 +1,8 @@
-use crate::utils::bitmask_matches;
-
 use super::Flags;
 
 /// Nested class access and property flags
 // TODO: remove debug directive
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum NestedClassAccessFlags {
     /// Marked or implicitly public in source
     AccPublic,
@@ -40,51 +38,34 @@ pub enum NestedClassAccessFlags {
 impl Flags for NestedClassAccessFlags {
     type AccessFlagType = NestedClassAccessFlags;
 
-    fn from_u16(value: u16) -> Vec<Self::AccessFlagType> {
-        let mut flags = vec![];
-
-        if bitmask_matches(value, 0x0001) {
-            flags.push(Self::AccPublic);
-        }
-
-        if bitmask_matches(value, 0x0002) {
-            flags.push(Self::AccPrivate);
-        }
-
-        if bitmask_matches(value, 0x0004) {
-            flags.push(Self::AccProtected);
-        }
-
-        if bitmask_matches(value, 0x0008) {
-            flags.push(Self::AccStatic);
-        }
-
-        if bitmask_matches(value, 0x0010) {
-            flags.push(Self::AccFinal);
-        }
-
-        if bitmask_matches(value, 0x0200) {
-            flags.push(Self::AccInterface);
-        }
-
-        if bitmask_matches(value, 0x0400) {
-            flags.push(Self::AccAbstract);
-        }
-
-        if bitmask_matches(value, 0x1000) {
-            flags.push(Self::AccSynthetic);
-        }
-
-        if bitmask_matches(value, 0x2000) {
-            flags.push(Self::AccAnnotation);
-        }
+    fn all() -> &'static [(Self::AccessFlagType, u16)] {
+        &[
+            (Self::AccPublic, 0x0001),
+            (Self::AccPrivate, 0x0002),
+            (Self::AccProtected, 0x0004),
+            (Self::AccStatic, 0x0008),
+            (Self::AccFinal, 0x0010),
+            (Self::AccInterface, 0x0200),
+            (Self::AccAbstract, 0x0400),
+            (Self::AccSynthetic, 0x1000),
+            (Self::AccAnnotation, 0x2000),
+            (Self::AccEnum, 0x4000),
+        ]
+    }
 
-        if bitmask_matches(value, 0x4000) {
-            flags.push(Self::AccEnum);
+    fn spec_name(flag: Self::AccessFlagType) -> &'static str {
+        match flag {
+            Self::AccPublic => "ACC_PUBLIC",
+            Self::AccPrivate => "ACC_PRIVATE",
+            Self::AccProtected => "ACC_PROTECTED",
+            Self::AccStatic => "ACC_STATIC",
+            Self::AccFinal => "ACC_FINAL",
+            Self::AccInterface => "ACC_INTERFACE",
+            Self::AccAbstract => "ACC_ABSTRACT",
+            Self::AccSynthetic => "ACC_SYNTHETIC",
+            Self::AccAnnotation => "ACC_ANNOTATION",
+            Self::AccEnum => "ACC_ENUM",
         }
-
-        assert!(flags.len() > 0, "Bitmask does not specify ANY access flags");
-        flags
     }
 }
 
@@ -200,4 +181,24 @@ mod tests {
             "Incorrect access flags returned"
         );
     }
+
+    #[test]
+    fn test_nested_class_access_flags_in_declaration_order() {
+        assert_eq!(
+            NestedClassAccessFlags::from_u16(0xFFFF),
+            vec![
+                NestedClassAccessFlags::AccPublic,
+                NestedClassAccessFlags::AccPrivate,
+                NestedClassAccessFlags::AccProtected,
+                NestedClassAccessFlags::AccStatic,
+                NestedClassAccessFlags::AccFinal,
+                NestedClassAccessFlags::AccInterface,
+                NestedClassAccessFlags::AccAbstract,
+                NestedClassAccessFlags::AccSynthetic,
+                NestedClassAccessFlags::AccAnnotation,
+                NestedClassAccessFlags::AccEnum,
+            ],
+            "Flags should be returned in declaration order"
+        );
+    }
 }
@@ -0,0 +1,236 @@
+//! Summarizes how a set of classes relate to each other through inheritance
+//!
+//! Only the names of each class, its superclass, and its interfaces are kept, which makes this a
+//! lightweight alternative to a full disassembly when reviewing the architecture of a codebase.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::classfile::{resolve_utf8, ClassFile, ClassKind};
+
+/// Direct supertypes of a single class
+#[derive(Debug, PartialEq)]
+pub struct TypeRelations {
+    /// Dotted name of the class
+    pub class_name: String,
+
+    /// Kind of type the class declares
+    pub kind: ClassKind,
+
+    /// Dotted name of the superclass, only `java.lang.Object` and modules do not have one
+    pub super_class: Option<String>,
+
+    /// Dotted names of the direct superinterfaces, in declaration order
+    pub interfaces: Vec<String>,
+}
+
+impl TypeRelations {
+    /// Fetch the supertypes that make up the inheritance tree
+    ///
+    /// Classes inherit from their superclass, interfaces from their superinterfaces. The
+    /// superclass of an interface is always `java.lang.Object`, which says nothing about it.
+    fn parents(&self) -> Vec<&str> {
+        match self.kind {
+            ClassKind::Interface | ClassKind::Annotation => {
+                self.interfaces.iter().map(String::as_str).collect()
+            }
+            _ => self.super_class.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl fmt::Display for TypeRelations {
+    /// Render the class like the start of its declaration, e.g. `class A extends B implements C`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.kind, self.class_name)?;
+
+        let interfaces_keyword = match self.kind {
+            ClassKind::Interface | ClassKind::Annotation => "extends",
+            _ => {
+                if let Some(super_class) = &self.super_class {
+                    write!(f, " extends {}", super_class)?;
+                }
+
+                "implements"
+            }
+        };
+
+        if !self.interfaces.is_empty() {
+            write!(f, " {} {}", interfaces_keyword, self.interfaces.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Type relations of a set of classes, ordered by class name
+#[derive(Default)]
+pub struct Hierarchy {
+    classes: BTreeMap<String, TypeRelations>,
+}
+
+impl Hierarchy {
+    /// Create an empty hierarchy
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the relations of a class file to the hierarchy, a class that was added before is
+    /// replaced
+    pub fn add(&mut self, class: &ClassFile) {
+        let name = |name_index| {
+            resolve_utf8(&class.constant_pool, name_index)
+                .unwrap_or("<invalid name>")
+                .replace('/', ".")
+        };
+
+        let relations = TypeRelations {
+            class_name: name(class.this_class.name_index),
+            kind: class.kind(),
+            super_class: class
+                .super_class
+                .as_ref()
+                .map(|super_class| name(super_class.name_index)),
+            interfaces: class
+                .interfaces
+                .iter()
+                .map(|interface| name(interface.name_index))
+                .collect(),
+        };
+
+        self.classes.insert(relations.class_name.clone(), relations);
+    }
+
+    /// Fetch the relations of every class in the hierarchy, ordered by class name
+    pub fn classes(&self) -> impl Iterator<Item = &TypeRelations> {
+        self.classes.values()
+    }
+
+    /// Render the inheritance relationships within the set of classes as a text tree
+    ///
+    /// Classes whose supertypes are not part of the set are the roots of the tree. An interface
+    /// that extends several interfaces of the set shows up under each of them.
+    pub fn tree(&self) -> HierarchyTree<'_> {
+        HierarchyTree { hierarchy: self }
+    }
+
+    /// Fetch the classes of the set that directly inherit from the specified class
+    fn children(&self, class_name: &str) -> Vec<&TypeRelations> {
+        self.classes
+            .values()
+            .filter(|relations| relations.parents().contains(&class_name))
+            .collect()
+    }
+}
+
+impl fmt::Display for Hierarchy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for relations in self.classes() {
+            writeln!(f, "{}", relations)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Inheritance tree of a hierarchy, created by [`Hierarchy::tree`]
+pub struct HierarchyTree<'a> {
+    hierarchy: &'a Hierarchy,
+}
+
+impl HierarchyTree<'_> {
+    /// Render the children of a class, each level is indented further than its parent
+    fn write_children(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        class_name: &str,
+        prefix: &str,
+    ) -> fmt::Result {
+        let children = self.hierarchy.children(class_name);
+
+        for (index, child) in children.iter().enumerate() {
+            let is_last = index + 1 == children.len();
+            let (branch, indent) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+
+            writeln!(f, "{}{}{}", prefix, branch, child.class_name)?;
+            self.write_children(f, &child.class_name, &format!("{}{}", prefix, indent))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for HierarchyTree<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let classes = &self.hierarchy.classes;
+
+        // Class files cannot form a cycle in a valid hierarchy, a crafted set could, but then none
+        // of its classes is a root and the cycle is left out
+        let roots = classes.values().filter(|relations| {
+            relations
+                .parents()
+                .iter()
+                .all(|parent| !classes.contains_key(*parent))
+        });
+
+        for root in roots {
+            writeln!(f, "{}", root.class_name)?;
+            self.write_children(f, &root.class_name, "")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Hierarchy;
+    use crate::classfile::parse;
+
+    #[test]
+    fn test_relations() {
+        let mut hierarchy = Hierarchy::new();
+        hierarchy.add(&parse(include_bytes!("../tests/fixtures/Bridged.class")).unwrap());
+        hierarchy.add(&parse(include_bytes!("../tests/fixtures/Shape.class")).unwrap());
+
+        assert_eq!(
+            hierarchy.to_string(),
+            "class Bridged extends java.lang.Object implements java.lang.Comparable\n\
+             interface Shape\n"
+        );
+    }
+
+    #[test]
+    fn test_tree() {
+        let mut hierarchy = Hierarchy::new();
+
+        for class in [
+            &include_bytes!("../tests/fixtures/Animal.class")[..],
+            include_bytes!("../tests/fixtures/Cat.class"),
+            include_bytes!("../tests/fixtures/Dog.class"),
+            include_bytes!("../tests/fixtures/Pet.class"),
+            include_bytes!("../tests/fixtures/Puppy.class"),
+            include_bytes!("../tests/fixtures/Trained.class"),
+        ] {
+            hierarchy.add(&parse(class).unwrap());
+        }
+
+        assert_eq!(
+            hierarchy.tree().to_string(),
+            "Animal\n\
+             ├── Cat\n\
+             └── Dog\n\
+             \x20   └── Puppy\n\
+             Pet\n\
+             └── Trained\n"
+        );
+
+        let relations = hierarchy.to_string();
+        assert!(relations.contains("class Puppy extends Dog implements Trained\n"));
+        assert!(relations.contains("interface Trained extends Pet\n"));
+    }
+}
@@ -0,0 +1,241 @@
+//! Resolves what to disassemble into the bytes of a class file
+//!
+//! A class file can be read from disk, from standard input, from an entry of a JAR archive, or by
+//! looking up a class by name on a class path. The parser only needs the bytes, so every kind of
+//! input ends up in the same place.
+
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::classpath::Classpath;
+use crate::jar::Jar;
+
+/// Argument that stands for standard input
+const STDIN_ARGUMENT: &str = "-";
+
+/// Separates the path of a JAR archive from the name of one of its entries
+const JAR_ENTRY_SEPARATOR: &str = "!/";
+
+/// Prefix of JAR URLs, e.g. `jar:file:/lib/app.jar!/com/example/Main.class`
+const JAR_URL_PREFIX: &str = "jar:file:";
+
+/// Extension used by class files
+const CLASS_EXTENSION: &str = "class";
+
+/// Extension used by JAR archives
+const JAR_EXTENSION: &str = "jar";
+
+/// Source of a class file, or of a directory of class files
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    /// Class file on disk, or a directory of class files
+    File(PathBuf),
+
+    /// Class file piped into standard input
+    Stdin,
+
+    /// Entry of a JAR archive, e.g. `com/example/Main.class`
+    JarEntry { jar: PathBuf, entry: String },
+
+    /// Class that is looked up on a class path by its fully qualified name, e.g. `java.lang.String`
+    ClassName {
+        classpath: OsString,
+        class_name: String,
+    },
+}
+
+impl Input {
+    /// Resolve a command-line argument into an input, the way `javap` does
+    ///
+    /// `-` stands for standard input and `app.jar!/com/example/Main.class` for an entry of a JAR
+    /// archive, optionally written as a `jar:file:` URL. Arguments that name an existing file or
+    /// directory, or that end in `.class`, are files. Anything else is the name of a class that is
+    /// looked up on the class path.
+    pub fn from_argument(argument: &str, classpath: &OsStr) -> Self {
+        if argument == STDIN_ARGUMENT {
+            return Self::Stdin;
+        }
+
+        let location = argument.strip_prefix(JAR_URL_PREFIX).unwrap_or(argument);
+
+        if let Some((jar, entry)) = location.split_once(JAR_ENTRY_SEPARATOR) {
+            return Self::JarEntry {
+                jar: PathBuf::from(jar),
+                entry: entry.to_owned(),
+            };
+        }
+
+        let path = Path::new(argument);
+
+        if path.exists() || path.extension() == Some(OsStr::new(CLASS_EXTENSION)) {
+            Self::File(path.to_path_buf())
+        } else {
+            Self::ClassName {
+                classpath: classpath.to_owned(),
+                class_name: argument.to_owned(),
+            }
+        }
+    }
+
+    /// Resolve the input the way the specified Java release sees a multi-release JAR
+    ///
+    /// An entry of a JAR archive is replaced by its copy in `META-INF/versions/M/` for the highest
+    /// `M` that does not exceed the release, if the archive has one. Other inputs, and archives
+    /// that cannot be read, are left as they are, reading them reports any error.
+    pub fn for_release(self, release: u16) -> Self {
+        match self {
+            Self::JarEntry { jar, entry } => {
+                let entry = match Jar::open(&jar) {
+                    Ok(archive) => archive.versioned_name(&entry, release).to_owned(),
+                    Err(_) => entry,
+                };
+
+                Self::JarEntry { jar, entry }
+            }
+            input => input,
+        }
+    }
+
+    /// Fetch the location of the input on disk, only files have one
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::File(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Fetch the location of the JAR archive on disk, if the input is one as a whole
+    pub fn jar_path(&self) -> Option<&Path> {
+        self.path()
+            .filter(|path| path.is_file() && path.extension() == Some(OsStr::new(JAR_EXTENSION)))
+    }
+
+    /// Read all bytes of the input
+    ///
+    /// Standard input can only be read once, later calls return whatever is left of it
+    pub fn open(&self) -> io::Result<Vec<u8>> {
+        match self {
+            Self::File(path) => std::fs::read(path),
+            Self::Stdin => {
+                let mut data = vec![];
+                io::stdin().lock().read_to_end(&mut data)?;
+                Ok(data)
+            }
+            Self::JarEntry { jar, entry } => Jar::open(jar)?.read(entry)?.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "no such entry in the archive")
+            }),
+            Self::ClassName {
+                classpath,
+                class_name,
+            } => Classpath::new(classpath).find(class_name).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "class not found on the class path")
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(path) => write!(f, "{}", path.display()),
+            Self::Stdin => write!(f, "<stdin>"),
+            Self::JarEntry { jar, entry } => {
+                write!(f, "{}{}{}", jar.display(), JAR_ENTRY_SEPARATOR, entry)
+            }
+            Self::ClassName { class_name, .. } => write!(f, "{}", class_name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::Input;
+
+    /// Location of the integration test fixtures
+    fn fixtures() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+    }
+
+    #[test]
+    fn test_from_argument() {
+        let classpath = fixtures().into_os_string();
+
+        assert_eq!(Input::from_argument("-", &classpath), Input::Stdin);
+        assert_eq!(
+            Input::from_argument("Missing.class", &classpath),
+            Input::File(PathBuf::from("Missing.class")),
+            "Class files are files even when they do not exist, so the error mentions the path"
+        );
+        assert_eq!(
+            Input::from_argument("jar:file:lib/app.jar!/com/example/Main.class", &classpath),
+            Input::JarEntry {
+                jar: PathBuf::from("lib/app.jar"),
+                entry: String::from("com/example/Main.class"),
+            }
+        );
+        assert_eq!(
+            Input::from_argument("java.lang.String", &classpath),
+            Input::ClassName {
+                classpath: classpath.clone(),
+                class_name: String::from("java.lang.String"),
+            }
+        );
+    }
+
+    #[test]
+    fn test_open() {
+        let plain = std::fs::read(fixtures().join("Plain.class")).unwrap();
+        let classpath = fixtures().into_os_string();
+        let jar = fixtures().join("greeter.jar");
+        let jar_entry = format!("{}!/com/example/greeter/Greeter.class", jar.display());
+
+        let file = fixtures().join("Plain.class");
+        let file = Input::from_argument(file.to_str().unwrap(), &classpath);
+        assert_eq!(file.open().unwrap(), plain);
+        assert_eq!(
+            Input::from_argument("Plain", &classpath).open().unwrap(),
+            plain
+        );
+
+        let entry = Input::from_argument(&jar_entry, &classpath);
+        assert_eq!(entry.to_string(), jar_entry);
+        assert_eq!(&entry.open().unwrap()[..4], &[0xCA, 0xFE, 0xBA, 0xBE]);
+
+        assert!(Input::from_argument("Missing", &classpath).open().is_err());
+        assert!(
+            Input::from_argument(&format!("{}!/Missing.class", jar.display()), &classpath)
+                .open()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_for_release() {
+        let classpath = fixtures().into_os_string();
+        let jar = fixtures().join("multi-release.jar");
+        let entry = |entry: &str| Input::JarEntry {
+            jar: jar.clone(),
+            entry: entry.to_owned(),
+        };
+
+        assert_eq!(
+            Input::from_argument(jar.to_str().unwrap(), &classpath).jar_path(),
+            Some(jar.as_path())
+        );
+        assert_eq!(entry("Release.class").jar_path(), None);
+
+        assert_eq!(
+            entry("Release.class").for_release(8),
+            entry("Release.class")
+        );
+        assert_eq!(
+            entry("Release.class").for_release(11),
+            entry("META-INF/versions/11/Release.class")
+        );
+        assert_eq!(Input::Stdin.for_release(11), Input::Stdin);
+    }
+}
@@ -0,0 +1,376 @@
+//! Reads class files straight from JAR archives
+//!
+//! A JAR is a ZIP archive, only the parts of the format that JAR tools produce are supported:
+//! stored and deflated entries without encryption. ZIP64 archives, which are only needed for
+//! archives of more than 4 GiB or 65535 entries, are rejected.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Signature of the end of central directory record
+const END_OF_CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x06054b50;
+
+/// Size of the end of central directory record, without the trailing comment
+const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+
+/// Largest comment that can follow the end of central directory record
+const MAX_COMMENT_SIZE: usize = 0xFFFF;
+
+/// Signature of a central directory file header
+const CENTRAL_DIRECTORY_SIGNATURE: u32 = 0x02014b50;
+
+/// Size of a central directory file header, without the name, extra field, and comment
+const CENTRAL_DIRECTORY_HEADER_SIZE: usize = 46;
+
+/// Signature of a local file header
+const LOCAL_HEADER_SIGNATURE: u32 = 0x04034b50;
+
+/// Size of a local file header, without the name and extra field
+const LOCAL_HEADER_SIZE: usize = 30;
+
+/// Compression method of an entry that is stored as-is
+const METHOD_STORED: u16 = 0;
+
+/// Compression method of an entry that is compressed with DEFLATE
+const METHOD_DEFLATED: u16 = 8;
+
+/// Directory of a multi-release JAR that holds the versioned entries, e.g.
+/// `META-INF/versions/11/com/example/Main.class`
+const VERSIONS_DIRECTORY: &str = "META-INF/versions/";
+
+/// Extension used by class files
+const CLASS_EXTENSION: &str = ".class";
+
+/// Location and compression of a single entry in the archive
+struct JarEntry {
+    /// Compression method, see `METHOD_STORED` and `METHOD_DEFLATED`
+    method: u16,
+
+    /// Number of bytes the entry takes up in the archive
+    compressed_size: u32,
+
+    /// Number of bytes the entry takes up once extracted
+    uncompressed_size: u32,
+
+    /// Offset of the entry's local file header from the start of the archive
+    local_header_offset: u32,
+}
+
+/// Index of the entries of a JAR archive, entries are only read when they are requested
+pub struct Jar {
+    /// Location of the archive
+    path: PathBuf,
+
+    /// Entries keyed by their name, e.g. `java/lang/Object.class`
+    entries: BTreeMap<String, JarEntry>,
+}
+
+impl Jar {
+    /// Index the entries of the JAR archive at the specified path
+    ///
+    /// Only the central directory at the end of the archive is read, which lists every entry
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let length = file.seek(SeekFrom::End(0))?;
+
+        // The end of central directory record is followed by a comment of unknown length
+        let tail_length = length.min((END_OF_CENTRAL_DIRECTORY_SIZE + MAX_COMMENT_SIZE) as u64);
+        let tail = read_at(&mut file, length - tail_length, tail_length as usize)?;
+
+        if tail.len() < END_OF_CENTRAL_DIRECTORY_SIZE {
+            return Err(invalid_data("too short to be an archive"));
+        }
+
+        let record = (0..=tail.len() - END_OF_CENTRAL_DIRECTORY_SIZE)
+            .rev()
+            .find(|&offset| u32_at(&tail, offset) == END_OF_CENTRAL_DIRECTORY_SIGNATURE)
+            .map(|offset| &tail[offset..])
+            .ok_or_else(|| invalid_data("no end of central directory record"))?;
+
+        let entry_count = u16_at(record, 10);
+        let directory_size = u32_at(record, 12);
+        let directory_offset = u32_at(record, 16);
+
+        if entry_count == 0xFFFF || directory_size == u32::MAX || directory_offset == u32::MAX {
+            return Err(invalid_data("ZIP64 archives are not supported"));
+        }
+
+        if u64::from(directory_offset) + u64::from(directory_size) > length {
+            return Err(invalid_data(
+                "central directory lies outside of the archive",
+            ));
+        }
+
+        let directory = read_at(
+            &mut file,
+            u64::from(directory_offset),
+            directory_size as usize,
+        )?;
+        let mut entries = BTreeMap::new();
+        let mut offset = 0;
+
+        for _ in 0..entry_count {
+            if directory.len() < offset + CENTRAL_DIRECTORY_HEADER_SIZE
+                || u32_at(&directory, offset) != CENTRAL_DIRECTORY_SIGNATURE
+            {
+                return Err(invalid_data("malformed central directory"));
+            }
+
+            let name_length = usize::from(u16_at(&directory, offset + 28));
+            let extra_length = usize::from(u16_at(&directory, offset + 30));
+            let comment_length = usize::from(u16_at(&directory, offset + 32));
+            let name_start = offset + CENTRAL_DIRECTORY_HEADER_SIZE;
+
+            let name = directory
+                .get(name_start..name_start + name_length)
+                .ok_or_else(|| invalid_data("malformed central directory"))?;
+
+            entries.insert(
+                String::from_utf8_lossy(name).into_owned(),
+                JarEntry {
+                    method: u16_at(&directory, offset + 10),
+                    compressed_size: u32_at(&directory, offset + 20),
+                    uncompressed_size: u32_at(&directory, offset + 24),
+                    local_header_offset: u32_at(&directory, offset + 42),
+                },
+            );
+
+            offset = name_start + name_length + extra_length + comment_length;
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    /// Fetch the location of the archive
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Fetch the names of all entries in the archive, sorted by name
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Check whether the archive has an entry with the specified name
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    /// Fetch the name of the entry that a multi-release JAR provides for the specified Java
+    /// release
+    ///
+    /// The copy in `META-INF/versions/M/` with the highest `M` that does not exceed the release is
+    /// preferred, falling back to the entry itself. Archives without versioned entries always
+    /// return the entry itself.
+    pub fn versioned_name<'a>(&'a self, name: &'a str, release: u16) -> &'a str {
+        self.names()
+            .filter_map(|versioned| match split_versioned_name(versioned) {
+                Some((version, unversioned)) if unversioned == name && version <= release => {
+                    Some((version, versioned))
+                }
+                _ => None,
+            })
+            .max_by_key(|(version, _)| *version)
+            .map_or(name, |(_, versioned)| versioned)
+    }
+
+    /// Fetch the names of the class file entries as seen by a Java release
+    ///
+    /// Without a release the versioned entries of a multi-release JAR are skipped, as they are
+    /// alternative implementations of classes in the root of the archive. With a release, each
+    /// class is the entry that [`Jar::versioned_name`] picks, sorted by its unversioned name.
+    pub fn class_names(&self, release: Option<u16>) -> Vec<&str> {
+        // Keyed by the unversioned name, so newer versions replace older ones
+        let mut class_names = BTreeMap::new();
+
+        for name in self.names().filter(|name| name.ends_with(CLASS_EXTENSION)) {
+            let (version, unversioned) = match split_versioned_name(name) {
+                Some((version, unversioned)) => (Some(version), unversioned),
+                None => (None, name),
+            };
+
+            // Entries in the root of the archive have no version, which orders before any version,
+            // and without a release every versioned entry is skipped
+            if version > release {
+                continue;
+            }
+
+            let class_name = class_names.entry(unversioned).or_insert((version, name));
+
+            if version > class_name.0 {
+                *class_name = (version, name);
+            }
+        }
+
+        class_names.into_values().map(|(_, name)| name).collect()
+    }
+
+    /// Extract the entry with the specified name, e.g. `java/lang/Object.class`
+    ///
+    /// Returns `Ok(None)` if there is no such entry, and an error if the entry could not be read
+    /// or uses a compression method other than stored or deflated
+    pub fn read(&self, name: &str) -> io::Result<Option<Vec<u8>>> {
+        let entry = match self.entries.get(name) {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        let mut file = File::open(&self.path)?;
+        let header_offset = u64::from(entry.local_header_offset);
+        let header = read_at(&mut file, header_offset, LOCAL_HEADER_SIZE)?;
+
+        if u32_at(&header, 0) != LOCAL_HEADER_SIGNATURE {
+            return Err(invalid_data("malformed local file header"));
+        }
+
+        // The local header may have a different extra field than the central directory
+        let data_offset = header_offset
+            + LOCAL_HEADER_SIZE as u64
+            + u64::from(u16_at(&header, 26))
+            + u64::from(u16_at(&header, 28));
+
+        if data_offset + u64::from(entry.compressed_size) > file.metadata()?.len() {
+            return Err(invalid_data("entry lies outside of the archive"));
+        }
+
+        let data = read_at(&mut file, data_offset, entry.compressed_size as usize)?;
+
+        match entry.method {
+            METHOD_STORED => Ok(Some(data)),
+            METHOD_DEFLATED => miniz_oxide::inflate::decompress_to_vec_with_limit(
+                &data,
+                entry.uncompressed_size as usize,
+            )
+            .map(Some)
+            .map_err(|error| invalid_data(&format!("cannot inflate {}: {}", name, error))),
+            method => Err(invalid_data(&format!(
+                "{} uses unsupported compression method {}",
+                name, method
+            ))),
+        }
+    }
+}
+
+/// Split the name of a versioned entry, e.g. `META-INF/versions/11/a/A.class`, into its version
+/// and the name of the entry that it replaces, e.g. `a/A.class`
+///
+/// Returns `None` for entries that are not versioned, including entries in a versions directory
+/// that is not named after a version.
+fn split_versioned_name(name: &str) -> Option<(u16, &str)> {
+    let (version, unversioned) = name.strip_prefix(VERSIONS_DIRECTORY)?.split_once('/')?;
+    Some((version.parse().ok()?, unversioned))
+}
+
+/// Read exactly the specified number of bytes at an offset from the start of the file
+fn read_at(file: &mut File, offset: u64, length: usize) -> io::Result<Vec<u8>> {
+    let mut buffer = vec![0; length];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Read a little-endian u16, the caller makes sure the bytes exist
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+/// Read a little-endian u32, the caller makes sure the bytes exist
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+/// Describe a malformed or unsupported archive
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::Jar;
+
+    #[test]
+    fn test_read_entries() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/greeter.jar");
+        let jar = Jar::open(&path).unwrap();
+
+        assert!(jar.contains("com/example/greeter/Greeter.class"));
+        assert!(jar.names().any(|name| name == "META-INF/MANIFEST.MF"));
+
+        let class = jar
+            .read("com/example/greeter/Greeter.class")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            &class[..4],
+            &[0xCA, 0xFE, 0xBA, 0xBE],
+            "Deflated entries are inflated"
+        );
+        assert_eq!(
+            class,
+            std::fs::read(
+                Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("tests/fixtures/modules/com.example.greeter/com/example/greeter")
+                    .join("Greeter.class")
+            )
+            .unwrap()
+        );
+        assert_eq!(jar.read("com/example/greeter/Missing.class").unwrap(), None);
+    }
+
+    #[test]
+    fn test_multi_release() {
+        // Release.class has been compiled for Java 8, with copies for Java 11 and 17 in
+        // META-INF/versions, see the sources in tests/fixtures/multi-release
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/multi-release.jar");
+        let jar = Jar::open(&path).unwrap();
+
+        assert_eq!(jar.versioned_name("Release.class", 10), "Release.class");
+        assert_eq!(
+            jar.versioned_name("Release.class", 16),
+            "META-INF/versions/11/Release.class"
+        );
+        assert_eq!(
+            jar.versioned_name("Release.class", 21),
+            "META-INF/versions/17/Release.class"
+        );
+        assert_eq!(jar.versioned_name("Helper.class", 21), "Helper.class");
+
+        assert_eq!(jar.class_names(None), vec!["Helper.class", "Release.class"]);
+        assert_eq!(
+            jar.class_names(Some(8)),
+            vec!["Helper.class", "Release.class"]
+        );
+        assert_eq!(
+            jar.class_names(Some(11)),
+            vec!["Helper.class", "META-INF/versions/11/Release.class"],
+            "Each class should be the copy for the highest applicable version"
+        );
+
+        let release = jar
+            .read("META-INF/versions/17/Release.class")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            release[7], 61,
+            "The Java 17 copy should have major version 61"
+        );
+    }
+
+    #[test]
+    fn test_not_a_jar() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/Plain.class");
+        assert!(Jar::open(&path).is_err());
+    }
+}
@@ -0,0 +1,18 @@
+//! Library interface of the Jadis disassembler
+//!
+//! The command-line application is a thin wrapper around these modules, exposing them as a library
+//! allows class files to be parsed from other crates and from the integration tests.
+
+pub mod byte_reader;
+pub mod bytecode;
+pub mod classfile;
+pub mod classpath;
+pub mod color;
+pub mod directory;
+pub mod disassembler;
+pub mod flags;
+pub mod hierarchy;
+pub mod input;
+pub mod jar;
+pub mod profile;
+pub mod utils;
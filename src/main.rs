@@ -7,40 +7,76 @@
 //! Please do note that the output format could be slightly different than the output you are used to.
 //! Having said that, though, the data in the output should be identical to the output of the disassembler that came with your JDK.
 //!
+//! The input argument is what to disassemble: a class file, a directory of class files, a JAR
+//! archive, `-` for standard input, an entry of a JAR archive (`app.jar!/com/example/Main.class`),
+//! or the name of a class that is looked up on the class path (`com.example.Main`).
+//!
 //! | option | description |
 //! | --- | --- |
 //! | --bootclasspath | Override location of bootstrap class files |
-//! | --class-path | Specify where to find user class files |
-//! | --classpath | Specify where to find user class files |
+//! | --check, --quiet | Only parse the class files and report the ones that fail to parse |
+//! | --class-path PATH | Specify where to find user class files |
+//! | --color=WHEN | Color the output: auto (only in a terminal), always, or never |
+//! | --classpath PATH | Specify where to find user class files |
 //! | -c | Disassemble the code |
 //! | --constants | Show final constants |
-//! | --cp | Specify where to find user class files |
+//! | --constants-only | Show only the constant pool and class information, skip fields and methods |
+//! | --cp PATH | Specify where to find user class files |
+//! | --jsonl | Write one JSON object per class per line, for streaming into other tools |
+//! | --fields-only | Show only fields, skip methods |
+//! | --diff OLD NEW | Show structural differences between two class files |
 //! | -h, --help | Print this help message |
+//! | --hexdump | Show the raw bytes of each class file as a hex dump before disassembling |
+//! | --internal-names | Write class names in internal form, e.g. `java/lang/Object` |
+//! | --indent SPACES | Indent nested lines with the specified number of spaces instead of a tab |
+//! | --hierarchy | Show only the superclass and interfaces of each class |
 //! | -J | Specify a VM option |
 //! | -l | Print line number and local variable tables |
-//! | -m, --module | Specify module containing classes to be disassembled |
-//! | --module-path | Specify where to find application modules |
-//! | --multi-release | Specify the version to use in multi-release JAR files |
+//! | --method NAME | Show only the methods with this name, optionally followed by a descriptor |
+//! | --methods-only | Show only methods, skip fields |
+//! | -m, --module NAME | Disassemble all classes of the module with this name, see --module-path |
+//! | --module-path PATH | Specify where to find application modules (exploded or modular JARs) |
+//! | --multi-release VERSION | Specify the version to use in multi-release JAR files |
 //! | --package | Show package/protected/public classes and members (default) |
+//! | -o, --output FILE | Write the output to a file instead of stdout |
+//! | --output-dir DIRECTORY | Write the output of each class to its own file, mirroring the packages |
 //! | -p, --private | Show all classes and members |
+//! | --pool-csv | Show only the constant pool as comma-separated values |
+//! | --pool-graph | Show only the constant pool as a Graphviz DOT graph of its references |
+//! | --profile | Report the largest methods and constant pools of the class files |
 //! | --protected | Show protected/public classes and members |
 //! | --public | Show only public classes and members |
 //! | -s | Print internal type signatures |
 //! | --sysinfo | Show system info (path, size, date, SHA-256 hash) of class being processed |
 //! | --system | Specify where to find system modules |
+//! | --tree | Show the inheritance relationships within the classes as a tree, see --hierarchy |
+//! | --timing | Report the time spent on each class file, and in total, on stderr |
+//! | --top COUNT | Number of rows in each table of the profile report (default 10) |
 //! | -V, --version | Version information |
 //! | -v, --verbose | Print additional information |
 
-use clap::{App, AppSettings, Arg};
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use byte_reader::ByteReader;
-use disassembler::{Disassembler, DisassemblerConfig, DisassemblerVisibility};
+use clap::{App, AppSettings, Arg};
 
-mod byte_reader;
-mod disassembler;
-mod flags;
-mod utils;
-mod classfile;
+use jadis::byte_reader::ByteReader;
+use jadis::classfile::{
+    check_field_descriptor, check_interface_super_class, check_method_descriptor, diff_class_files,
+    ClassFile,
+};
+use jadis::color::ColorMode;
+use jadis::directory::{find_class_files, find_class_files_for_release, find_module};
+use jadis::disassembler::{Disassembler, DisassemblerConfig, DisassemblerVisibility};
+use jadis::hierarchy::Hierarchy;
+use jadis::input::Input;
+use jadis::jar::Jar;
+use jadis::profile::Profile;
+use jadis::utils::hex_dump;
 
 /// Application entry point
 fn main() {
@@ -51,7 +87,12 @@ fn main() {
         .help_message("Print this help message")
         .setting(AppSettings::ArgRequiredElseHelp)
         .setting(AppSettings::DisableVersion)
-        .setting(AppSettings::AllowExternalSubcommands)
+        .arg(
+            Arg::with_name("INPUT")
+                .index(1)
+                .required_unless_one(&["diff", "module"])
+                .help("Class file, directory, JAR, JAR entry, or class name to disassemble, or -"),
+        )
         .arg(
             Arg::with_name("verbose")
                 .short("v")
@@ -109,17 +150,64 @@ fn main() {
                 .long("constants")
                 .help("Show final constants"),
         )
+        .arg(
+            Arg::with_name("color")
+                .long("color")
+                .takes_value(true)
+                .value_name("WHEN")
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+                .help("Color the output: auto (only in a terminal), always, or never"),
+        )
+        .arg(
+            Arg::with_name("constants-only")
+                .long("constants-only")
+                .help("Show only the constant pool and class information, skip fields and methods"),
+        )
+        .arg(
+            Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .takes_value(true)
+                .value_name("FILE")
+                .conflicts_with("output-dir")
+                .help("Write the output to a file instead of stdout"),
+        )
+        .arg(
+            Arg::with_name("output-dir")
+                .long("output-dir")
+                .takes_value(true)
+                .value_name("DIRECTORY")
+                .help("Write the output of each class to its own file, mirroring the packages"),
+        )
+        .arg(
+            Arg::with_name("fields-only")
+                .long("fields-only")
+                .conflicts_with("methods-only")
+                .help("Show only fields, skip methods"),
+        )
+        .arg(
+            Arg::with_name("methods-only")
+                .long("methods-only")
+                .help("Show only methods, skip fields"),
+        )
         .arg(
             Arg::with_name("module")
                 .short("m")
                 .long("module")
-                .help("Specify module containing classes to be disassembled"),
+                .takes_value(true)
+                .value_name("NAME")
+                .requires("module-path")
+                .conflicts_with("diff")
+                .help("Disassemble all classes of the module with this name, see --module-path"),
         )
         .arg(Arg::with_name("jvm").short("J").help("Specify a VM option"))
         .arg(
             Arg::with_name("module-path")
                 .long("module-path")
-                .help("Specify where to find application modules"),
+                .takes_value(true)
+                .value_name("PATH")
+                .help("Specify where to find application modules (exploded or modular JARs)"),
         )
         .arg(
             Arg::with_name("system")
@@ -129,18 +217,141 @@ fn main() {
         .arg(
             Arg::with_name("class-path")
                 .long("class-path")
+                .takes_value(true)
+                .value_name("PATH")
                 .help("Specify where to find user class files"),
         )
         .arg(
             Arg::with_name("classpath")
                 .long("classpath")
+                .takes_value(true)
+                .value_name("PATH")
                 .help("Specify where to find user class files"),
         )
         .arg(
             Arg::with_name("cp")
                 .long("cp")
+                .takes_value(true)
+                .value_name("PATH")
                 .help("Specify where to find user class files"),
         )
+        .arg(
+            Arg::with_name("diff")
+                .long("diff")
+                .takes_value(true)
+                .number_of_values(2)
+                .value_names(&["OLD", "NEW"])
+                .help("Show structural differences between two class files"),
+        )
+        .arg(
+            Arg::with_name("check")
+                .long("check")
+                .visible_alias("quiet")
+                .conflicts_with_all(&["diff", "output", "output-dir"])
+                .help("Only parse the class files and report the ones that fail to parse"),
+        )
+        .arg(
+            Arg::with_name("pool-csv")
+                .long("pool-csv")
+                .conflicts_with_all(&["diff", "check"])
+                .help("Show only the constant pool as comma-separated values"),
+        )
+        .arg(
+            Arg::with_name("pool-graph")
+                .long("pool-graph")
+                .conflicts_with_all(&[
+                    "diff",
+                    "check",
+                    "pool-csv",
+                    "jsonl",
+                    "hexdump",
+                    "profile",
+                    "hierarchy",
+                ])
+                .help("Show only the constant pool as a Graphviz DOT graph of its references"),
+        )
+        .arg(
+            Arg::with_name("jsonl")
+                .long("jsonl")
+                .conflicts_with_all(&["diff", "check", "pool-csv", "output-dir"])
+                .help("Write one JSON object per class per line, for streaming into other tools"),
+        )
+        .arg(
+            Arg::with_name("hexdump")
+                .long("hexdump")
+                .conflicts_with_all(&["diff", "check", "pool-csv", "jsonl", "profile", "hierarchy"])
+                .help("Show the raw bytes of each class file as a hex dump before disassembling"),
+        )
+        .arg(
+            Arg::with_name("profile")
+                .long("profile")
+                .conflicts_with_all(&["diff", "check", "pool-csv", "jsonl", "output", "output-dir"])
+                .help("Report the largest methods and constant pools of the class files"),
+        )
+        .arg(
+            Arg::with_name("hierarchy")
+                .long("hierarchy")
+                .conflicts_with_all(&[
+                    "diff",
+                    "check",
+                    "pool-csv",
+                    "jsonl",
+                    "profile",
+                    "output-dir",
+                ])
+                .help("Show only the superclass and interfaces of each class"),
+        )
+        .arg(
+            Arg::with_name("tree")
+                .long("tree")
+                .requires("hierarchy")
+                .help("Show the inheritance relationships within the classes as a tree"),
+        )
+        .arg(
+            Arg::with_name("top")
+                .long("top")
+                .takes_value(true)
+                .value_name("COUNT")
+                .requires("profile")
+                .validator(|count| {
+                    count
+                        .parse::<usize>()
+                        .map(|_| ())
+                        .map_err(|_| format!("\"{}\" is not a number of rows", count))
+                })
+                .help("Number of rows in each table of the profile report (default 10)"),
+        )
+        .arg(
+            Arg::with_name("timing")
+                .long("timing")
+                .conflicts_with("diff")
+                .help("Report the time spent on each class file, and in total, on stderr"),
+        )
+        .arg(
+            Arg::with_name("internal-names")
+                .long("internal-names")
+                .help("Write class names in internal form, e.g. java/lang/Object"),
+        )
+        .arg(
+            Arg::with_name("indent")
+                .long("indent")
+                .takes_value(true)
+                .value_name("SPACES")
+                .validator(|spaces| {
+                    spaces
+                        .parse::<usize>()
+                        .map(|_| ())
+                        .map_err(|_| format!("\"{}\" is not a number of spaces", spaces))
+                })
+                .help("Indent nested lines with the specified number of spaces instead of a tab"),
+        )
+        .arg(
+            Arg::with_name("method")
+                .long("method")
+                .takes_value(true)
+                .value_name("NAME")
+                .help("Show only the methods with this name, optionally followed by a descriptor"),
+        )
         .arg(
             Arg::with_name("bootclasspath")
                 .long("bootclasspath")
@@ -149,19 +360,26 @@ fn main() {
         .arg(
             Arg::with_name("multi-release")
                 .long("multi-release")
+                .takes_value(true)
+                .value_name("VERSION")
+                .validator(|version| {
+                    version
+                        .parse::<u16>()
+                        .map(|_| ())
+                        .map_err(|_| format!("\"{}\" is not a Java release", version))
+                })
                 .help("Specify the version to use in multi-release JAR files"),
         )
         .get_matches();
 
     let mut disassembler_config = DisassemblerConfig::new();
 
+    // Verbosity and visibility combine, e.g. `-p -v` also shows bridge and synthetic members
     if matches.is_present("verbose") {
-        //
-    } else if matches.is_present("version") {
-        //
-    } else if matches.is_present("line") {
-        disassembler_config.show_line_numbers();
-    } else if matches.is_present("public") {
+        disassembler_config.show_verbose_info();
+    }
+
+    if matches.is_present("public") {
         disassembler_config.with_visibility(DisassemblerVisibility::PUBLIC);
     } else if matches.is_present("protected") {
         disassembler_config.with_visibility(DisassemblerVisibility::PROTECTED);
@@ -169,6 +387,12 @@ fn main() {
         disassembler_config.with_visibility(DisassemblerVisibility::PACKAGE);
     } else if matches.is_present("private") {
         disassembler_config.with_visibility(DisassemblerVisibility::PRIVATE);
+    }
+
+    if matches.is_present("version") {
+        //
+    } else if matches.is_present("line") {
+        disassembler_config.show_line_numbers();
     } else if matches.is_present("code") {
         disassembler_config.show_assembly_instructions();
     } else if matches.is_present("signatures") {
@@ -177,29 +401,619 @@ fn main() {
         disassembler_config.show_system_info();
     } else if matches.is_present("constants") {
         disassembler_config.show_final_constants();
-    } else if matches.is_present("module") {
-        todo!();
     } else if matches.is_present("jvm") {
         todo!();
-    } else if matches.is_present("module-path") {
-        todo!();
     } else if matches.is_present("system") {
         todo!();
-    } else if matches.is_present("class-path") {
-        todo!();
-    } else if matches.is_present("classpath") {
-        todo!();
-    } else if matches.is_present("cp") {
-        todo!();
     } else if matches.is_present("bootclasspath") {
         todo!();
-    } else if matches.is_present("multi-release") {
-        todo!();
     }
 
-    // The last argument should always be the class to disassemble
-    if let Some(file_to_disassemble) = std::env::args().last().to_owned() {
-        let mut file = ByteReader::new(&file_to_disassemble);
-        Disassembler::new(&disassembler_config, &mut file);
+    if matches.is_present("constants-only") {
+        disassembler_config.show_constants_only();
+    }
+
+    if matches.is_present("fields-only") {
+        disassembler_config.show_fields_only();
+    }
+
+    if matches.is_present("methods-only") {
+        disassembler_config.show_methods_only();
+    }
+
+    // A descriptor starts with the opening parenthesis of the parameter list
+    if let Some(method) = matches.value_of("method") {
+        match method.find('(') {
+            Some(start) => {
+                disassembler_config.with_method_filter(&method[..start], Some(&method[start..]))
+            }
+            None => disassembler_config.with_method_filter(method, None),
+        };
+    }
+
+    if matches.is_present("internal-names") {
+        disassembler_config.show_internal_names();
+    }
+
+    if let Some(spaces) = matches.value_of("indent") {
+        disassembler_config.with_indent(&" ".repeat(spaces.parse().unwrap()));
+    }
+
+    if matches.is_present("pool-csv") {
+        disassembler_config.show_pool_csv();
+    }
+
+    if matches.is_present("pool-graph") {
+        disassembler_config.show_pool_graph();
+    }
+
+    // Always set, as the color mode has a default value
+    if let Some(color_mode) = matches.value_of("color").and_then(ColorMode::from_name) {
+        disassembler_config.with_color_mode(color_mode);
+    }
+
+    // Classes that are named rather than pointed to are looked up on the class path, which is the
+    // current directory unless specified otherwise
+    let classpath = matches
+        .value_of_os("class-path")
+        .or_else(|| matches.value_of_os("classpath"))
+        .or_else(|| matches.value_of_os("cp"))
+        .unwrap_or_else(|| OsStr::new("."));
+
+    // Comparing two class files replaces the regular disassembler output
+    if let Some(mut arguments) = matches.values_of("diff") {
+        let old = Input::from_argument(arguments.next().unwrap(), classpath);
+        let new = Input::from_argument(arguments.next().unwrap(), classpath);
+
+        let is_diffed = guard_panics(&new, || diff_files(&disassembler_config, &old, &new));
+
+        if is_diffed != Some(true) {
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
+    let output_file = matches.value_of("output").map(Path::new);
+    let output_directory = matches.value_of("output-dir").map(Path::new);
+
+    // Terminal detection only looks at stdout, which is not where the output goes to
+    if (output_file.is_some() || output_directory.is_some())
+        && matches.value_of("color") == Some("auto")
+    {
+        disassembler_config.with_color_mode(ColorMode::Never);
+    }
+
+    // A module is disassembled as the directory or JAR of classes it was found in, otherwise the
+    // input argument names the class (or directory of classes) to disassemble
+    let input = match matches.value_of("module") {
+        Some(module) => {
+            let module_path = matches.value_of_os("module-path").unwrap();
+
+            match find_module(module_path, module) {
+                Some(root) => Some(Input::File(root)),
+                None => {
+                    eprintln!("Error: module {} not found on the module path", module);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => matches
+            .value_of("INPUT")
+            .map(|argument| Input::from_argument(argument, classpath)),
+    };
+
+    if let Some(input) = input {
+        let directory = input
+            .path()
+            .filter(|path| path.is_dir())
+            .map(Path::to_path_buf);
+        let is_directory = directory.is_some() || input.jar_path().is_some();
+        let mut timing = Timing::new(matches.is_present("timing"));
+        let release = matches
+            .value_of("multi-release")
+            .map(|release| release.parse().unwrap());
+
+        // Multi-release JARs, extracted or not, only show the classes that apply to the requested
+        // release
+        let class_files: Vec<Input> = match (&directory, input.jar_path()) {
+            (Some(path), _) => match release {
+                Some(release) => find_class_files_for_release(path, release),
+                None => find_class_files(path),
+            }
+            .into_iter()
+            .map(Input::File)
+            .collect(),
+            (None, Some(path)) => match find_jar_class_files(path, release) {
+                Some(class_files) => class_files,
+                None => std::process::exit(1),
+            },
+            (None, None) => match release {
+                Some(release) => vec![input.for_release(release)],
+                None => vec![input],
+            },
+        };
+
+        // Validating class files replaces the regular disassembler output, valid class files are
+        // not mentioned at all
+        if matches.is_present("check") {
+            let failures = class_files
+                .iter()
+                .filter(|class_file| {
+                    let class = timing.measure(class_file, || {
+                        guard_panics(class_file, || parse_file(&disassembler_config, class_file))
+                    });
+
+                    class.flatten().is_none()
+                })
+                .count();
+
+            timing.report_total();
+
+            if failures > 0 {
+                std::process::exit(1);
+            }
+
+            return;
+        }
+
+        // Profiling aggregates all class files into a single report, class files that fail to
+        // parse are reported but do not end up in the report
+        if matches.is_present("profile") {
+            let mut profile = Profile::new();
+            let mut has_failures = false;
+
+            for class_file in &class_files {
+                let class = timing.measure(class_file, || {
+                    guard_panics(class_file, || parse_file(&disassembler_config, class_file))
+                });
+
+                match class {
+                    Some(Some(class)) => profile.add(&class),
+                    _ => has_failures = true,
+                }
+            }
+
+            timing.report_total();
+
+            let count = matches
+                .value_of("top")
+                .map_or(10, |count| count.parse().unwrap());
+            print!("{}", profile.report(count));
+
+            if has_failures {
+                std::process::exit(1);
+            }
+
+            return;
+        }
+
+        // The hierarchy is a summary of all class files, like the profile, but it is small enough
+        // to be written to an output file as well
+        if matches.is_present("hierarchy") {
+            let mut hierarchy = Hierarchy::new();
+            let mut has_failures = false;
+
+            for class_file in &class_files {
+                let class = timing.measure(class_file, || {
+                    guard_panics(class_file, || parse_file(&disassembler_config, class_file))
+                });
+
+                match class {
+                    Some(Some(class)) => hierarchy.add(&class),
+                    _ => has_failures = true,
+                }
+            }
+
+            timing.report_total();
+
+            let summary = if matches.is_present("tree") {
+                hierarchy.tree().to_string()
+            } else {
+                hierarchy.to_string()
+            };
+
+            let result = match output_file {
+                Some(output_file) => create_output_file(output_file)
+                    .and_then(|mut file| file.write_all(summary.as_bytes()).and(file.flush())),
+                None => io::stdout().write_all(summary.as_bytes()),
+            };
+
+            if let Err(error) = result {
+                let target = output_file.unwrap_or_else(|| Path::new("stdout"));
+                eprintln!("Error: {}: {}", target.display(), error);
+                has_failures = true;
+            }
+
+            if has_failures {
+                std::process::exit(1);
+            }
+
+            return;
+        }
+
+        // Output that is not split up per class is written to a single sink
+        let mut shared_output: Box<dyn Write> = match output_file {
+            Some(output_file) => match create_output_file(output_file) {
+                Ok(file) => Box::new(file),
+                Err(error) => {
+                    eprintln!("Error: {}: {}", output_file.display(), error);
+                    std::process::exit(1);
+                }
+            },
+            None => Box::new(io::stdout()),
+        };
+
+        let show_hexdump = matches.is_present("hexdump");
+        let mut has_failures = false;
+
+        // A broken class file should not prevent the remaining classes from being disassembled
+        for class_file in class_files {
+            let start = timing.start();
+            let outcome = guard_panics(&class_file, || match output_directory {
+                Some(output_directory) => {
+                    let relative = output_name(&class_file, directory.as_deref());
+                    let target = output_directory.join(relative).with_extension("txt");
+                    let result = create_output_file(&target).and_then(|mut file| {
+                        let is_disassembled = disassemble_file(
+                            &disassembler_config,
+                            &class_file,
+                            false,
+                            show_hexdump,
+                            &mut file,
+                        )?;
+
+                        file.flush().map(|_| is_disassembled)
+                    });
+
+                    (target, result)
+                }
+                None if matches.is_present("jsonl") => (
+                    output_file
+                        .unwrap_or_else(|| Path::new("stdout"))
+                        .to_path_buf(),
+                    write_json_record(&disassembler_config, &class_file, &mut shared_output),
+                ),
+                None => (
+                    output_file
+                        .unwrap_or_else(|| Path::new("stdout"))
+                        .to_path_buf(),
+                    disassemble_file(
+                        &disassembler_config,
+                        &class_file,
+                        is_directory,
+                        show_hexdump,
+                        &mut shared_output,
+                    ),
+                ),
+            });
+
+            timing.stop(&class_file, start);
+
+            let (target, result) = match outcome {
+                Some(outcome) => outcome,
+                None => {
+                    has_failures = true;
+                    continue;
+                }
+            };
+
+            match result {
+                Ok(is_disassembled) => has_failures |= !is_disassembled,
+                Err(error) => {
+                    eprintln!("Error: {}: {}", target.display(), error);
+                    has_failures = true;
+                }
+            }
+        }
+
+        if let Err(error) = shared_output.flush() {
+            let target = output_file.unwrap_or_else(|| Path::new("stdout"));
+            eprintln!("Error: {}: {}", target.display(), error);
+            has_failures = true;
+        }
+
+        timing.report_total();
+
+        if has_failures {
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Wall-clock time spent on each class file, reported on stderr when `--timing` is set
+///
+/// The time of a class covers reading, parsing, and writing its output, so slow inputs stand out
+/// regardless of which step is slow. Reporting on stderr keeps the regular output intact.
+struct Timing {
+    /// Indicates whether anything is measured at all
+    is_enabled: bool,
+
+    /// Number of class files measured so far
+    classes: usize,
+
+    /// Time spent on all class files measured so far
+    total: Duration,
+}
+
+impl Timing {
+    /// Create a timer that only measures anything if it is enabled
+    fn new(is_enabled: bool) -> Self {
+        Self {
+            is_enabled,
+            classes: 0,
+            total: Duration::ZERO,
+        }
+    }
+
+    /// Start measuring a class file, the measurement ends with [`Timing::stop`]
+    fn start(&self) -> Instant {
+        Instant::now()
+    }
+
+    /// Report the time spent on a class file since the measurement started
+    fn stop(&mut self, input: &Input, start: Instant) {
+        if !self.is_enabled {
+            return;
+        }
+
+        let elapsed = start.elapsed();
+        self.classes += 1;
+        self.total += elapsed;
+
+        eprintln!("Timing: {}: {}", input, format_duration(elapsed));
+    }
+
+    /// Measure the time spent on processing a single class file
+    fn measure<T>(&mut self, input: &Input, work: impl FnOnce() -> T) -> T {
+        let start = self.start();
+        let result = work();
+        self.stop(input, start);
+        result
+    }
+
+    /// Report the number of class files measured and the time spent on all of them
+    fn report_total(&self) {
+        if self.is_enabled {
+            eprintln!(
+                "Timing: total: {} in {} class file(s)",
+                format_duration(self.total),
+                self.classes
+            );
+        }
+    }
+}
+
+/// Render a duration in milliseconds with microsecond precision, e.g. `1.250 ms`
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Run the processing of a single class file, turning a panic into an error reported on stderr
+///
+/// Malformed input should be reported as a `ClassFileError`, a panic means a bug was hit instead.
+/// The message of the panic is reported like any other error, so the remaining class files can
+/// still be processed. Set `RUST_BACKTRACE` to see where the panic happened.
+fn guard_panics<T>(input: &Input, work: impl FnOnce() -> T) -> Option<T> {
+    // The message is reported below, the default hook would print it a second time
+    let result = if std::env::var_os("RUST_BACKTRACE").is_some() {
+        panic::catch_unwind(AssertUnwindSafe(work))
+    } else {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = panic::catch_unwind(AssertUnwindSafe(work));
+        panic::set_hook(hook);
+        result
+    };
+
+    match result {
+        Ok(value) => Some(value),
+        Err(payload) => {
+            let message = match payload.downcast_ref::<&str>() {
+                Some(message) => message.to_string(),
+                None => payload
+                    .downcast_ref::<String>()
+                    .cloned()
+                    .unwrap_or_else(|| String::from("unknown panic")),
+            };
+
+            eprintln!("Error: {}: internal error: {}", input, message);
+            None
+        }
+    }
+}
+
+/// Disassemble a single class file into the output, class file errors are reported on stderr
+///
+/// The hex dump is written before the class file is parsed, so it is still shown for files that
+/// fail to parse, or that are not class files at all. Returns `Ok(false)` if the class file could
+/// not be read or disassembled, and an error if the output could not be written
+fn disassemble_file(
+    config: &DisassemblerConfig,
+    input: &Input,
+    show_header: bool,
+    show_hexdump: bool,
+    out: &mut dyn Write,
+) -> io::Result<bool> {
+    if show_header {
+        writeln!(out, "Classfile {}", input)?;
+    }
+
+    let is_disassembled = match read_input(input) {
+        Some(data) => {
+            if show_hexdump {
+                writeln!(out, "{}", hex_dump(&data))?;
+            }
+
+            match parse_disassembler(config, input, &data) {
+                Some(disassembler) => {
+                    disassembler.write_to(out)?;
+                    true
+                }
+                None => false,
+            }
+        }
+        None => false,
+    };
+
+    if show_header {
+        writeln!(out)?;
+    }
+
+    Ok(is_disassembled)
+}
+
+/// Write a class file as a single JSON record, class file errors are reported on stderr
+///
+/// The output is flushed after every record, so a consumer can process each class as soon as it
+/// has been disassembled. Returns `Ok(false)` if the class file could not be read or parsed.
+fn write_json_record(
+    config: &DisassemblerConfig,
+    input: &Input,
+    out: &mut dyn Write,
+) -> io::Result<bool> {
+    let disassembler = read_input(input).and_then(|data| parse_disassembler(config, input, &data));
+
+    match disassembler {
+        Some(disassembler) => {
+            disassembler.write_json_line(out, &input.to_string())?;
+            out.flush()?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Read all bytes of an input, errors are reported on stderr
+fn read_input(input: &Input) -> Option<Vec<u8>> {
+    match input.open() {
+        Ok(data) => Some(data),
+        Err(error) => {
+            eprintln!("Error: {}: {}", input, error);
+            None
+        }
+    }
+}
+
+/// Parse a class file for the disassembler, errors are reported on stderr
+fn parse_disassembler<'a>(
+    config: &'a DisassemblerConfig,
+    input: &Input,
+    data: &[u8],
+) -> Option<Disassembler<'a>> {
+    match Disassembler::new(config, &mut ByteReader::from_bytes(data)) {
+        Ok(disassembler) => {
+            warn_structural_issues(input, disassembler.class_file());
+            Some(disassembler)
+        }
+        Err(error) => {
+            eprintln!("Error: {}: {}", input, error);
+            None
+        }
+    }
+}
+
+/// Report JVMS invariants that a successfully parsed class file violates on stderr
+///
+/// The class file is still processed, a hand-crafted or corrupt class file is worth a look but not
+/// worth refusing to disassemble
+fn warn_structural_issues(input: &Input, class: &ClassFile) {
+    if let Err(error) = check_interface_super_class(class) {
+        eprintln!("Warning: {}: {}", input, error);
+    }
+
+    let descriptor_errors = class
+        .fields
+        .iter()
+        .filter_map(|field| check_field_descriptor(field, &class.constant_pool).err())
+        .chain(
+            class
+                .methods
+                .iter()
+                .filter_map(|method| check_method_descriptor(method, &class.constant_pool).err()),
+        );
+
+    for error in descriptor_errors {
+        eprintln!("Warning: {}: {}", input, error);
+    }
+}
+
+/// Find the class files of a JAR archive as seen by a Java release, errors are reported on stderr
+fn find_jar_class_files(path: &Path, release: Option<u16>) -> Option<Vec<Input>> {
+    match Jar::open(path) {
+        Ok(jar) => Some(
+            jar.class_names(release)
+                .into_iter()
+                .map(|entry| Input::JarEntry {
+                    jar: path.to_path_buf(),
+                    entry: entry.to_owned(),
+                })
+                .collect(),
+        ),
+        Err(error) => {
+            eprintln!("Error: {}: {}", path.display(), error);
+            None
+        }
+    }
+}
+
+/// Determine where the output of a class goes, relative to the output directory
+///
+/// Classes found in a directory mirror their location within it, other classes are named after
+/// the file, archive entry, or class they were read from.
+fn output_name(input: &Input, directory: Option<&Path>) -> PathBuf {
+    match input {
+        Input::File(path) => match directory {
+            Some(directory) => path.strip_prefix(directory).unwrap_or(path).to_path_buf(),
+            None => PathBuf::from(path.file_name().unwrap_or_default()),
+        },
+        Input::Stdin => PathBuf::from("stdin"),
+        Input::JarEntry { entry, .. } => PathBuf::from(entry),
+        Input::ClassName { class_name, .. } => PathBuf::from(class_name.replace('.', "/")),
+    }
+}
+
+/// Create (or truncate) an output file, including any missing parent directories
+fn create_output_file(path: &Path) -> io::Result<BufWriter<File>> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    Ok(BufWriter::new(File::create(path)?))
+}
+
+/// Print the structural differences between two class files, errors are reported on stderr
+///
+/// Returns `false` if either class file could not be read or parsed
+fn diff_files(config: &DisassemblerConfig, old: &Input, new: &Input) -> bool {
+    let (old_class, new_class) = match (parse_file(config, old), parse_file(config, new)) {
+        (Some(old_class), Some(new_class)) => (old_class, new_class),
+        _ => return false,
+    };
+
+    let diff = diff_class_files(&old_class, &new_class);
+
+    if diff.is_empty() {
+        println!("No structural differences");
+    } else {
+        print!("{}", diff);
+    }
+
+    true
+}
+
+/// Parse a single class file without printing it, errors are reported on stderr
+fn parse_file(config: &DisassemblerConfig, input: &Input) -> Option<ClassFile> {
+    let data = read_input(input)?;
+
+    match ClassFile::new(
+        &mut ByteReader::from_bytes(&data),
+        config.class_file_config(),
+    ) {
+        Ok(class) => Some(class),
+        Err(error) => {
+            eprintln!("Error: {}: {}", input, error);
+            None
+        }
     }
 }
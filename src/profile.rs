@@ -0,0 +1,167 @@
+//! Aggregates size statistics over many class files to find bloat in a codebase
+//!
+//! Only the numbers needed for the reports are kept, so a profile can be built from any number of
+//! class files without keeping them all in memory.
+
+use std::cmp::Reverse;
+use std::fmt;
+
+use crate::classfile::{resolve_utf8, ClassFile};
+
+/// Bytecode length of a single method
+#[derive(Debug, PartialEq)]
+pub struct MethodSize {
+    /// Dotted name of the class that declares the method
+    pub class_name: String,
+
+    /// Name and descriptor of the method, e.g. `main([Ljava/lang/String;)V`
+    pub method: String,
+
+    /// Number of bytes in the method's code array
+    pub code_length: usize,
+}
+
+/// Number of constant pool entries of a single class
+#[derive(Debug, PartialEq)]
+pub struct PoolSize {
+    /// Dotted name of the class
+    pub class_name: String,
+
+    /// Number of entries in the constant pool, see `ClassFile::constant_pool_len`
+    pub entries: usize,
+}
+
+/// Size statistics of a set of class files
+#[derive(Default)]
+pub struct Profile {
+    methods: Vec<MethodSize>,
+    pools: Vec<PoolSize>,
+}
+
+impl Profile {
+    /// Create an empty profile
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the methods and constant pool of a class file to the profile
+    ///
+    /// Methods without a code attribute, such as abstract and native methods, are skipped
+    pub fn add(&mut self, class: &ClassFile) {
+        let class_name = resolve_utf8(&class.constant_pool, class.this_class.name_index)
+            .unwrap_or("<invalid name>")
+            .replace('/', ".");
+
+        for method in &class.methods {
+            if let Some(code) = method.code() {
+                self.methods.push(MethodSize {
+                    class_name: class_name.clone(),
+                    method: format!(
+                        "{}{}",
+                        resolve_utf8(&class.constant_pool, method.name_index)
+                            .unwrap_or("<invalid name>"),
+                        resolve_utf8(&class.constant_pool, method.descriptor_index)
+                            .unwrap_or("<invalid descriptor>")
+                    ),
+                    code_length: code.code.len(),
+                });
+            }
+        }
+
+        self.pools.push(PoolSize {
+            class_name,
+            entries: class.constant_pool_len(),
+        });
+    }
+
+    /// Fetch the methods with the most bytecode, largest first
+    pub fn largest_methods(&self, count: usize) -> Vec<&MethodSize> {
+        let mut methods: Vec<&MethodSize> = self.methods.iter().collect();
+        methods.sort_by_key(|method| Reverse(method.code_length));
+        methods.truncate(count);
+        methods
+    }
+
+    /// Fetch the classes with the most constant pool entries, largest first
+    pub fn largest_pools(&self, count: usize) -> Vec<&PoolSize> {
+        let mut pools: Vec<&PoolSize> = self.pools.iter().collect();
+        pools.sort_by_key(|pool| Reverse(pool.entries));
+        pools.truncate(count);
+        pools
+    }
+
+    /// Render both top lists as tables, limited to the specified number of rows each
+    pub fn report(&self, count: usize) -> ProfileReport<'_> {
+        ProfileReport {
+            profile: self,
+            count,
+        }
+    }
+}
+
+/// Top lists of a profile, created by [`Profile::report`]
+pub struct ProfileReport<'a> {
+    profile: &'a Profile,
+    count: usize,
+}
+
+impl fmt::Display for ProfileReport<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Largest methods by bytecode length:")?;
+
+        for method in self.profile.largest_methods(self.count) {
+            writeln!(
+                f,
+                "\t{:>6}  {}.{}",
+                method.code_length, method.class_name, method.method
+            )?;
+        }
+
+        writeln!(f, "Largest constant pools:")?;
+
+        for pool in self.profile.largest_pools(self.count) {
+            writeln!(f, "\t{:>6}  {}", pool.entries, pool.class_name)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Profile;
+    use crate::classfile::parse;
+
+    #[test]
+    fn test_largest_methods_and_pools() {
+        let mut profile = Profile::new();
+        profile.add(&parse(include_bytes!("../tests/fixtures/Plain.class")).unwrap());
+        profile.add(&parse(include_bytes!("../tests/fixtures/Numbers.class")).unwrap());
+
+        let methods = profile.largest_methods(2);
+        assert_eq!(methods.len(), 2);
+        assert_eq!(methods[0].class_name, "Plain");
+        assert_eq!(methods[0].method, "main([Ljava/lang/String;)V");
+        assert_eq!(methods[0].code_length, 19);
+        assert!(methods[0].code_length >= methods[1].code_length);
+
+        let pools = profile.largest_pools(10);
+        assert_eq!(pools.len(), 2, "Each class contributes one pool");
+        assert!(pools[0].entries >= pools[1].entries);
+    }
+
+    #[test]
+    fn test_report() {
+        let mut profile = Profile::new();
+        profile.add(&parse(include_bytes!("../tests/fixtures/Plain.class")).unwrap());
+
+        let report = profile.report(1).to_string();
+        assert_eq!(
+            report,
+            "Largest methods by bytecode length:\n\
+             \t    19  Plain.main([Ljava/lang/String;)V\n\
+             Largest constant pools:\n\
+             \t    36  Plain\n"
+        );
+    }
+}
@@ -24,6 +24,23 @@ pub fn to_u32(bytes: &Vec<u8>) -> u32 {
     u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
 }
 
+/// Reinterpret a single byte as a signed (two's complement) i8
+pub fn to_i8(byte: u8) -> i8 {
+    i8::from_be_bytes([byte])
+}
+
+/// Create a new i16 from two bytes
+/// Byte order is assumed to be big-endian
+pub fn to_i16(bytes: &[u8]) -> i16 {
+    assert!(
+        bytes.len() == 2,
+        "Expected 2 bytes, got {} bytes",
+        bytes.len()
+    );
+
+    i16::from_be_bytes([bytes[0], bytes[1]])
+}
+
 /// Create a new i32 from four bytes
 /// Byte order is assumed to be big-endian
 pub fn to_i32(bytes: &Vec<u8>) -> i32 {
@@ -81,9 +98,54 @@ pub fn bitmask_matches(value: u16, bitmask: u16) -> bool {
     value & bitmask == bitmask
 }
 
+/// Number of bytes shown on a single line of a hex dump
+const HEX_DUMP_WIDTH: usize = 16;
+
+/// Render bytes as a classic hex dump, one line per 16 bytes
+///
+/// Each line shows the offset of its first byte, the bytes in hexadecimal, and the bytes as ASCII
+/// with unprintable bytes replaced by a dot, e.g.
+/// `00000000  ca fe ba be 00 00 00 3d  00 1d 0a 00 02 00 03 07  |.......=........|`
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+
+    for (line, chunk) in bytes.chunks(HEX_DUMP_WIDTH).enumerate() {
+        dump.push_str(&format!("{:08x} ", line * HEX_DUMP_WIDTH));
+
+        for index in 0..HEX_DUMP_WIDTH {
+            // The halves of a line are separated by an extra space, like `hexdump -C` does
+            if index % (HEX_DUMP_WIDTH / 2) == 0 {
+                dump.push(' ');
+            }
+
+            match chunk.get(index) {
+                Some(byte) => dump.push_str(&format!("{:02x} ", byte)),
+                None => dump.push_str("   "),
+            }
+        }
+
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    char::from(byte)
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+
+        dump.push_str(&format!(" |{}|\n", ascii));
+    }
+
+    dump
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{bitmask_matches, to_f32, to_f64, to_i32, to_i64, to_u16, to_u32};
+    use super::{
+        bitmask_matches, hex_dump, to_f32, to_f64, to_i16, to_i32, to_i64, to_i8, to_u16, to_u32,
+    };
 
     #[test]
     fn test_to_u16_valid_args() {
@@ -264,4 +326,25 @@ mod tests {
             "Bits 0, 1, 5, 9, and 15 should be set"
         );
     }
+
+    #[test]
+    fn test_to_i8_and_i16_are_signed() {
+        assert_eq!(to_i8(0xff), -1);
+        assert_eq!(to_i8(0x7f), 127);
+        assert_eq!(to_i16(&[0xfc, 0x18]), -1000);
+        assert_eq!(to_i16(&[0x7f, 0xff]), 32767);
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0x00, 0x00, 0x00, 0x3D];
+        bytes.extend(b"Hello, world!\n");
+
+        assert_eq!(
+            hex_dump(&bytes),
+            "00000000  ca fe ba be 00 00 00 3d  48 65 6c 6c 6f 2c 20 77  |.......=Hello, w|\n\
+             00000010  6f 72 6c 64 21 0a                                 |orld!.|\n"
+        );
+        assert_eq!(hex_dump(&[]), "", "Empty input has no lines");
+    }
 }
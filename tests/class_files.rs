@@ -0,0 +1,686 @@
+//! Parses compiled class files end-to-end
+//!
+//! The fixtures have been compiled with `javac --release 17` from the Java sources that live next to
+//! them in the `fixtures` directory.
+
+use jadis::byte_reader::ByteReader;
+use jadis::bytecode::DecodeError;
+use jadis::classfile::{
+    check_field_descriptor, check_interface_super_class, check_max_locals, check_method_descriptor,
+    diff_class_files, parse_all, parse_all_with_progress, resolve_class_name_binary,
+    resolve_class_name_internal, resolve_member_ref, resolve_optional_class, resolve_optional_utf8,
+    resolve_utf8, AttributeType, ClassFile, ClassFileConfig, ClassKind, ConstantPoolInfo,
+    FieldType, MemberChange, PoolError, Tag, VerifyError,
+};
+
+const GUARDED: &[u8] = include_bytes!("fixtures/Guarded.class");
+const LEGACY: &[u8] = include_bytes!("fixtures/Legacy.class");
+const LEGACY_INNER: &[u8] = include_bytes!("fixtures/Legacy$Inner.class");
+const NUMBERS: &[u8] = include_bytes!("fixtures/Numbers.class");
+const PLAIN: &[u8] = include_bytes!("fixtures/Plain.class");
+const SHAPE: &[u8] = include_bytes!("fixtures/Shape.class");
+const SUIT: &[u8] = include_bytes!("fixtures/Suit.class");
+const VEHICLE: &[u8] = include_bytes!("fixtures/Vehicle.class");
+
+/// Parse a fixture using the default class file configuration
+fn parse(bytes: &[u8]) -> ClassFile {
+    let mut reader = ByteReader::from_bytes(bytes);
+    ClassFile::new(&mut reader, &ClassFileConfig::new()).expect("Unable to parse fixture")
+}
+
+/// Resolve the name of the class declared by the class file
+fn class_name(class: &ClassFile) -> &str {
+    resolve_utf8(&class.constant_pool, class.this_class.name_index)
+        .expect("Unable to resolve the class name")
+}
+
+#[test]
+fn test_parse_plain_class() {
+    let class = parse(PLAIN);
+
+    assert_eq!(class.major_version, 61, "Incorrect major version");
+    assert_eq!(class.minor_version, 0, "Incorrect minor version");
+    assert_eq!(class_name(&class), "Plain", "Incorrect class name");
+    assert_eq!(class.kind(), ClassKind::Class, "Incorrect class kind");
+    assert_eq!(
+        class.access_flags_raw, 0x0021,
+        "Public class with ACC_SUPER set"
+    );
+    assert_eq!(class.fields.len(), 1, "Incorrect field count");
+    assert_eq!(class.methods.len(), 3, "Incorrect method count");
+    assert_eq!(
+        class.constant_pool_count as usize,
+        class.constant_pool_len() + 1,
+        "A pool without long or double constants has exactly one unusable index"
+    );
+}
+
+#[test]
+fn test_parse_interface() {
+    let class = parse(SHAPE);
+
+    assert_eq!(class.major_version, 61, "Incorrect major version");
+    assert_eq!(class_name(&class), "Shape", "Incorrect class name");
+    assert_eq!(class.kind(), ClassKind::Interface, "Incorrect class kind");
+    assert_eq!(class.methods.len(), 2, "Incorrect method count");
+    assert!(
+        class.bootstrap_methods().is_some(),
+        "String concatenation should produce a bootstrap method"
+    );
+}
+
+#[test]
+fn test_parse_enum() {
+    let class = parse(SUIT);
+
+    assert_eq!(class.major_version, 61, "Incorrect major version");
+    assert_eq!(class_name(&class), "Suit", "Incorrect class name");
+    assert_eq!(class.kind(), ClassKind::Enum, "Incorrect class kind");
+
+    // Four constants and the synthetic $VALUES array
+    assert_eq!(class.fields.len(), 5, "Incorrect field count");
+
+    // values(), valueOf(String), the constructor, $values(), and the static initializer
+    assert_eq!(class.methods.len(), 5, "Incorrect method count");
+    assert!(
+        class.signature().is_some(),
+        "Enums have a generic signature"
+    );
+}
+
+#[test]
+fn test_parse_sealed_interface() {
+    let class = parse(VEHICLE);
+    let permitted_subclasses = class
+        .permitted_subclasses()
+        .expect("Sealed interfaces have a permitted subclasses attribute");
+
+    let names: Vec<&str> = permitted_subclasses
+        .classes
+        .iter()
+        .map(|index| {
+            let subclass = class.constant_pool[index]
+                .try_cast_into_class()
+                .expect("Permitted subclasses refer to class entries");
+
+            resolve_utf8(&class.constant_pool, subclass.name_index).unwrap()
+        })
+        .collect();
+
+    assert_eq!(names, vec!["Vehicle$Car", "Vehicle$Truck"]);
+    assert!(
+        parse(PLAIN).permitted_subclasses().is_none(),
+        "Classes that are not sealed have no permitted subclasses"
+    );
+}
+
+#[test]
+fn test_resolve_member_ref() {
+    let class = parse(PLAIN);
+    let method_refs: Vec<(&str, &str, &str)> = class
+        .constant_pool
+        .iter()
+        .filter(|(_, entry)| entry.tag == Tag::ConstantMethodRef)
+        .map(|(index, _)| resolve_member_ref(&class.constant_pool, *index).unwrap())
+        .collect();
+
+    assert!(
+        method_refs.contains(&("java/lang/Object", "<init>", "()V")),
+        "The constructor should call the constructor of its super class"
+    );
+    assert!(method_refs.contains(&("Plain", "increment", "()I")));
+
+    let this_class = class.this_class.constant_pool_index;
+    assert_eq!(
+        resolve_member_ref(&class.constant_pool, this_class),
+        Err(PoolError::WrongType {
+            index: this_class,
+            expected: "Fieldref, Methodref, or InterfaceMethodref",
+            found: Tag::ConstantClass,
+        }),
+        "Class entries are not member references"
+    );
+    assert_eq!(
+        resolve_member_ref(&class.constant_pool, 0),
+        Err(PoolError::MissingEntry { index: 0 })
+    );
+}
+
+#[test]
+fn test_method_instructions() {
+    let class = parse(PLAIN);
+    let main = class
+        .main_method()
+        .expect("Plain should have a main method");
+    let instructions = main
+        .instructions()
+        .unwrap()
+        .expect("main should have a body");
+
+    assert_eq!(
+        instructions.first().map(|instruction| instruction.opcode),
+        Some(0xb2)
+    );
+    assert_eq!(
+        instructions.last().map(|instruction| instruction.opcode),
+        Some(0xb1)
+    );
+    assert_eq!(
+        parse(SHAPE).methods[0].instructions(),
+        Ok(None),
+        "Abstract methods have no body"
+    );
+
+    // Replace the ireturn that ends increment() with a sipush that lacks its operands
+    let mut truncated = PLAIN.to_vec();
+    let putfield = truncated
+        .windows(5)
+        .position(|window| window == [0x5a, 0xb5, 0x00, 0x07, 0xac])
+        .expect("increment() should end with `dup_x1`, `putfield #7`, and `ireturn`");
+    truncated[putfield + 4] = 0x11;
+
+    let class = parse(&truncated);
+    assert_eq!(
+        class.methods[1].instructions(),
+        Err(DecodeError::Truncated { offset: 11 })
+    );
+}
+
+#[test]
+fn test_preview_minor_version() {
+    assert!(!parse(PLAIN).is_preview());
+
+    let mut preview = PLAIN.to_vec();
+    preview[4] = 0xFF;
+    preview[5] = 0xFF;
+    let class = parse(&preview);
+
+    assert!(class.is_preview());
+    assert_eq!(class.minor_version, 0xFFFF);
+
+    // Preview features were introduced in Java SE 12, older class files cannot depend on them
+    preview[6..8].copy_from_slice(&43u16.to_be_bytes());
+    let mut config = ClassFileConfig::new();
+    config.with_min_major_version(43);
+    let class = ClassFile::new(&mut ByteReader::from_bytes(&preview), &config).unwrap();
+
+    assert!(!class.is_preview());
+}
+
+#[test]
+fn test_member_descriptors() {
+    let class = parse(PLAIN);
+
+    assert_eq!(
+        class.fields[0].descriptor(&class.constant_pool),
+        Some(FieldType::Int)
+    );
+
+    let main = class
+        .methods
+        .iter()
+        .find(|method| resolve_utf8(&class.constant_pool, method.name_index) == Some("main"))
+        .unwrap();
+    let descriptor = main.descriptor(&class.constant_pool).unwrap();
+
+    assert_eq!(
+        descriptor.parameters,
+        vec![FieldType::Array(Box::new(FieldType::Object(String::from(
+            "java/lang/String"
+        ))))]
+    );
+    assert_eq!(descriptor.return_type, None);
+}
+
+#[test]
+fn test_resolve_optional_entries() {
+    let class = parse(PLAIN);
+    let this_class = class.this_class.constant_pool_index;
+
+    assert!(resolve_optional_class(&class.constant_pool, 0)
+        .unwrap()
+        .is_none());
+    assert_eq!(resolve_optional_utf8(&class.constant_pool, 0), Ok(None));
+    assert_eq!(
+        resolve_optional_class(&class.constant_pool, this_class)
+            .unwrap()
+            .map(|class_info| class_info.name_index),
+        Some(class.this_class.name_index)
+    );
+    assert_eq!(
+        resolve_optional_utf8(&class.constant_pool, this_class),
+        Err(PoolError::WrongType {
+            index: this_class,
+            expected: "Utf8",
+            found: Tag::ConstantClass,
+        }),
+        "Only index 0 means there is no entry"
+    );
+}
+
+#[test]
+fn test_resolve_class_names() {
+    let class = parse(PLAIN);
+    let super_class = class.super_class.as_ref().unwrap().constant_pool_index;
+
+    assert_eq!(
+        resolve_class_name_internal(&class.constant_pool, super_class),
+        Ok("java/lang/Object")
+    );
+    assert_eq!(
+        resolve_class_name_binary(&class.constant_pool, super_class),
+        Ok("java.lang.Object".to_owned())
+    );
+    assert_eq!(
+        resolve_class_name_binary(&class.constant_pool, class.this_class.name_index),
+        Err(PoolError::WrongType {
+            index: class.this_class.name_index,
+            expected: "Class",
+            found: Tag::ConstantUtf8,
+        })
+    );
+}
+
+#[test]
+fn test_attributes_of_type() {
+    let class = parse(PLAIN);
+
+    assert_eq!(
+        class.attributes_of_type(&AttributeType::Code).len(),
+        3,
+        "Every method has a code attribute"
+    );
+    assert_eq!(
+        class
+            .attributes_of_type(&AttributeType::LineNumberTable)
+            .len(),
+        3,
+        "Line number tables nested in code attributes should be included"
+    );
+    assert_eq!(
+        class.attributes_of_type(&AttributeType::SourceFile).len(),
+        1
+    );
+    assert!(class.attributes_of_type(&AttributeType::Record).is_empty());
+}
+
+#[test]
+fn test_diff_class_files() {
+    assert!(
+        diff_class_files(&parse(PLAIN), &parse(PLAIN)).is_empty(),
+        "A class file does not differ from itself"
+    );
+
+    let diff = diff_class_files(&parse(PLAIN), &parse(SUIT));
+
+    assert_eq!(diff.version, None, "Both fixtures target the same version");
+    assert!(diff.access_flags.is_some(), "Enums are final");
+    assert!(diff.fields.contains(&MemberChange::Removed {
+        name: "counter".to_owned(),
+        descriptor: "I".to_owned(),
+    }));
+    assert!(diff.fields.contains(&MemberChange::Added {
+        name: "CLUBS".to_owned(),
+        descriptor: "LSuit;".to_owned(),
+    }));
+    assert!(
+        diff.methods.contains(&MemberChange::Removed {
+            name: "<init>".to_owned(),
+            descriptor: "(I)V".to_owned(),
+        }),
+        "Members are matched by name and descriptor"
+    );
+}
+
+#[test]
+fn test_constant_pool_gaps() {
+    let class = parse(NUMBERS);
+    let wide_entries: Vec<u16> = class
+        .constant_pool
+        .iter()
+        .filter(|(_, entry)| matches!(entry.tag, Tag::ConstantLong | Tag::ConstantDouble))
+        .map(|(index, _)| *index)
+        .collect();
+
+    assert_eq!(wide_entries.len(), 3, "One long and two double constants");
+
+    for index in wide_entries {
+        assert!(
+            !class.constant_pool.contains_key(&(index + 1)),
+            "The index after #{} is unusable",
+            index
+        );
+        assert!(
+            class.constant_pool.contains_key(&(index + 2)),
+            "Numbering should continue two indices after #{}",
+            index
+        );
+    }
+
+    assert_eq!(
+        class.constant_pool_count as usize,
+        class.constant_pool_len() + 1 + 3,
+        "Every long and double constant leaves a gap in the numbering"
+    );
+}
+
+#[test]
+fn test_deprecated_and_synthetic_members() {
+    let class = parse(LEGACY);
+
+    assert!(
+        class.fields[0].is_deprecated(),
+        "Deprecated through a javadoc tag"
+    );
+    assert!(!class.fields[0].is_synthetic());
+    assert!(
+        !class.methods[0].is_deprecated(),
+        "The constructor is not deprecated"
+    );
+    assert!(class.methods[1].is_deprecated());
+
+    let inner = parse(LEGACY_INNER);
+    let outer_instance = &inner.fields[0];
+
+    assert_eq!(
+        resolve_utf8(&inner.constant_pool, outer_instance.name_index),
+        Some("this$0")
+    );
+    assert_eq!(
+        outer_instance.access_flags_raw, 0x1010,
+        "Final and synthetic"
+    );
+    assert!(
+        outer_instance.is_synthetic(),
+        "The reference to the enclosing instance is generated by the compiler"
+    );
+    assert!(
+        !inner.methods[0].is_synthetic(),
+        "Implicit constructors are mandated, not synthetic"
+    );
+}
+
+#[test]
+fn test_truncated_and_corrupted_input_never_panics() {
+    for fixture in [LEGACY, NUMBERS, SHAPE, SUIT] {
+        for length in 0..fixture.len() {
+            assert!(
+                jadis::classfile::parse(&fixture[..length]).is_err(),
+                "A class file truncated to {} bytes cannot be complete",
+                length
+            );
+        }
+
+        for index in 0..fixture.len() {
+            for value in [0x00, 0x7F, 0xFF] {
+                let mut corrupted = fixture.to_vec();
+                corrupted[index] = value;
+
+                // Any outcome is fine, as long as it is not a panic
+                let _ = jadis::classfile::parse(&corrupted);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_source_line_for_offset() {
+    let class = parse(PLAIN);
+    let main = &class.methods[2];
+
+    assert_eq!(main.source_line_for_offset(0), Some(13));
+    assert_eq!(main.source_line_for_offset(15), Some(13));
+    assert_eq!(main.source_line_for_offset(18), Some(14));
+
+    let interface = parse(SHAPE);
+    let abstract_method = interface
+        .methods
+        .iter()
+        .find(|method| method.code().is_none())
+        .expect("Shape declares abstract methods");
+
+    assert_eq!(
+        abstract_method.source_line_for_offset(0),
+        None,
+        "Methods without code have no line numbers"
+    );
+}
+
+#[test]
+fn test_code_attribute_getters() {
+    let class = parse(PLAIN);
+    let code = class.methods[2].code().unwrap();
+    let line_numbers = code
+        .line_number_table()
+        .expect("Fixtures have line numbers");
+
+    assert_eq!(line_numbers.line_number_table[0].start_pc, 0);
+    assert_eq!(line_numbers.line_number_table[0].line_number, 13);
+    assert!(
+        code.local_variable_table().is_none(),
+        "Fixtures are compiled without local variable information"
+    );
+    assert!(
+        code.stack_map_table().is_none(),
+        "Straight-line code has no stack map"
+    );
+
+    let guarded = parse(GUARDED);
+    assert!(
+        guarded
+            .methods
+            .iter()
+            .filter_map(|method| method.code())
+            .any(|code| code.stack_map_table().is_some()),
+        "Exception handlers need a stack map"
+    );
+}
+
+#[test]
+fn test_referenced_classes() {
+    let class = parse(PLAIN);
+
+    assert_eq!(
+        class.referenced_classes(),
+        vec![
+            "java.io.PrintStream",
+            "java.lang.Object",
+            "java.lang.String",
+            "java.lang.System"
+        ],
+        "Pool classes and descriptor classes, without the class itself"
+    );
+}
+
+#[test]
+fn test_string_literals() {
+    assert_eq!(parse(GUARDED).string_literals(), vec!["done"]);
+    assert!(
+        parse(PLAIN).string_literals().is_empty(),
+        "Names and descriptors are not string literals"
+    );
+}
+
+#[test]
+fn test_main_method() {
+    let class = parse(PLAIN);
+    let main = class.main_method().expect("Plain declares a main method");
+
+    assert_eq!(
+        resolve_utf8(&class.constant_pool, main.name_index),
+        Some("main")
+    );
+    assert!(parse(SHAPE).main_method().is_none());
+    assert!(parse(GUARDED).main_method().is_none());
+}
+
+#[test]
+fn test_module_name() {
+    let module_info = parse(include_bytes!(
+        "fixtures/modules/com.example.greeter/module-info.class"
+    ));
+
+    assert_eq!(module_info.kind(), ClassKind::Module);
+    assert_eq!(module_info.module_name(), Some("com.example.greeter"));
+    assert_eq!(
+        parse(PLAIN).module_name(),
+        None,
+        "Only module-info declares a module"
+    );
+}
+
+#[test]
+fn test_interface_super_class() {
+    assert_eq!(check_interface_super_class(&parse(SHAPE)), Ok(()));
+    assert_eq!(
+        check_interface_super_class(&parse(PLAIN)),
+        Ok(()),
+        "Only interfaces are checked"
+    );
+
+    let mut shape = parse(SHAPE);
+    shape.super_class = Some(shape.this_class.clone());
+    assert_eq!(
+        check_interface_super_class(&shape),
+        Err(VerifyError::InterfaceSuperClass {
+            found: Some("Shape".to_owned())
+        })
+    );
+
+    shape.super_class = None;
+    assert_eq!(
+        check_interface_super_class(&shape),
+        Err(VerifyError::InterfaceSuperClass { found: None })
+    );
+}
+
+#[test]
+fn test_malformed_descriptors() {
+    let mut plain = parse(PLAIN);
+
+    for field in &plain.fields {
+        assert_eq!(check_field_descriptor(field, &plain.constant_pool), Ok(()));
+    }
+
+    for method in &plain.methods {
+        assert_eq!(
+            check_method_descriptor(method, &plain.constant_pool),
+            Ok(())
+        );
+    }
+
+    // Drop the semicolon that terminates the class name of `main`'s parameter
+    let descriptor = b"([Ljava/lang/String)V";
+    let mut bytes = vec![1, 0, descriptor.len() as u8];
+    bytes.extend_from_slice(descriptor);
+
+    let main = plain.main_method().unwrap();
+    let descriptor_index = main.descriptor_index;
+    let name_index = main.name_index;
+    let entry =
+        ConstantPoolInfo::new(&mut ByteReader::from_bytes(&bytes), descriptor_index).unwrap();
+    plain.constant_pool.insert(descriptor_index, entry);
+
+    let main = plain
+        .methods
+        .iter()
+        .find(|method| method.name_index == name_index)
+        .unwrap();
+    let error = check_method_descriptor(main, &plain.constant_pool).unwrap_err();
+    assert_eq!(
+        error,
+        VerifyError::MalformedDescriptor {
+            member: "method main".to_owned(),
+            descriptor: "([Ljava/lang/String)V".to_owned(),
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "method main has a malformed descriptor \"([Ljava/lang/String)V\""
+    );
+    assert_eq!(
+        check_field_descriptor(&plain.fields[0], &plain.constant_pool),
+        Ok(()),
+        "Only the descriptor of main has been corrupted"
+    );
+}
+
+#[test]
+fn test_too_many_parameter_slots() {
+    let mut plain = parse(PLAIN);
+    let main = plain
+        .methods
+        .iter()
+        .position(|method| resolve_utf8(&plain.constant_pool, method.name_index) == Some("main"))
+        .unwrap();
+    let descriptor_index = plain.methods[main].descriptor_index;
+
+    assert_eq!(
+        check_max_locals(&plain.methods[main], &plain.constant_pool),
+        Ok(())
+    );
+
+    // 128 longs need 256 slots, 40000 longs need more slots than fit in a u16
+    for parameters in [128, 40_000] {
+        let descriptor = format!("({})V", "J".repeat(parameters));
+        let mut bytes = vec![1];
+        bytes.extend_from_slice(&(descriptor.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(descriptor.as_bytes());
+
+        let entry =
+            ConstantPoolInfo::new(&mut ByteReader::from_bytes(&bytes), descriptor_index).unwrap();
+        plain.constant_pool.insert(descriptor_index, entry);
+
+        assert_eq!(
+            check_method_descriptor(&plain.methods[main], &plain.constant_pool),
+            Err(VerifyError::TooManyParameterSlots {
+                member: "method main".to_owned(),
+            })
+        );
+    }
+
+    let error = check_max_locals(&plain.methods[main], &plain.constant_pool).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "the parameters of method main occupy more than 255 local variable slots"
+    );
+}
+
+#[test]
+fn test_parse_all() {
+    let blobs = vec![
+        PLAIN.to_vec(),
+        PLAIN[..PLAIN.len() / 2].to_vec(),
+        SHAPE.to_vec(),
+    ];
+    let classes = parse_all(&blobs);
+
+    assert_eq!(classes.len(), 3);
+    assert_eq!(class_name(classes[0].as_ref().unwrap()), "Plain");
+    assert!(
+        classes[1].is_err(),
+        "A truncated class file should not stop the others"
+    );
+    assert_eq!(class_name(classes[2].as_ref().unwrap()), "Shape");
+
+    assert!(parse_all([GUARDED, SUIT]).iter().all(Result::is_ok));
+}
+
+#[test]
+fn test_parse_all_with_progress() {
+    let mut reports = vec![];
+    let classes = parse_all_with_progress([PLAIN, &[0xCA, 0xFE], SHAPE], |classes, bytes| {
+        reports.push((classes, bytes))
+    });
+
+    assert_eq!(classes.len(), 3);
+    assert_eq!(
+        reports,
+        [
+            (1, PLAIN.len()),
+            (2, PLAIN.len() + 2),
+            (3, PLAIN.len() + 2 + SHAPE.len())
+        ],
+        "Progress is reported after every blob, including the ones that fail to parse"
+    );
+}
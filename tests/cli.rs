@@ -0,0 +1,71 @@
+//! Tests that run the command-line interface on fixture class files
+
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+const PET: &[u8] = include_bytes!("fixtures/Pet.class");
+
+/// Run jadis with a class file on stdin
+fn run(args: &[&str], class: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jadis"))
+        .args(args)
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Unable to run jadis");
+
+    child.stdin.take().unwrap().write_all(class).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// An interface that extends itself rather than java.lang.Object, which parses but is invalid
+fn self_extending_interface() -> Vec<u8> {
+    let mut pet = PET.to_vec();
+
+    // Pet ends with this_class, super_class, and 16 bytes of empty tables and its SourceFile
+    let super_class = pet.len() - 18;
+    assert_eq!(pet[super_class..super_class + 2], [0x00, 0x03]);
+    pet[super_class + 1] = 0x01;
+    pet
+}
+
+#[test]
+fn test_check_ignores_warnings() {
+    let class = self_extending_interface();
+
+    let output = run(&[], &class);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("Warning: <stdin>: "));
+
+    let output = run(&["--check"], &class);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(
+        output.stderr.is_empty(),
+        "--check only reports class files that fail to parse"
+    );
+}
+
+#[test]
+fn test_module_in_modular_jar() {
+    let module_jars = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/module-jars");
+    let output = Command::new(env!("CARGO_BIN_EXE_jadis"))
+        .args(["--module", "com.example.greeter", "--module-path"])
+        .arg(&module_jars)
+        .output()
+        .expect("Unable to run jadis");
+
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("public class com.example.greeter.Greeter extends java.lang.Object\n"));
+    assert!(stdout.contains("module com.example.greeter\n"));
+}
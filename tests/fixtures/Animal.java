@@ -0,0 +1,17 @@
+public class Animal {
+}
+
+class Dog extends Animal {
+}
+
+class Puppy extends Dog implements Trained {
+}
+
+class Cat extends Animal {
+}
+
+interface Pet {
+}
+
+interface Trained extends Pet {
+}
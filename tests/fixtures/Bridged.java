@@ -0,0 +1,12 @@
+public class Bridged implements Comparable<Bridged> {
+    private final int rank;
+
+    public Bridged(int rank) {
+        this.rank = rank;
+    }
+
+    @Override
+    public int compareTo(Bridged other) {
+        return Integer.compare(rank, other.rank);
+    }
+}
@@ -0,0 +1,9 @@
+public class Casts {
+    public String[] names(Object value) {
+        return (String[]) value;
+    }
+
+    public int[][] grid(Object value) {
+        return (int[][]) value;
+    }
+}
@@ -0,0 +1,5 @@
+public class Concat {
+    public static String greet(String name, int count) {
+        return "Hello, " + name + " x" + count;
+    }
+}
@@ -0,0 +1,11 @@
+import java.util.List;
+
+public class Constants {
+    public static final int VERSION = 3;
+    public static final String NAME = "jadis \"disassembler\"";
+    public static final boolean ENABLED = true;
+    public static final char SEPARATOR = '/';
+    public static final double RATIO = 0.5;
+    protected static volatile long counter;
+    private List<String> list;
+}
@@ -0,0 +1,11 @@
+public class Guarded {
+    public int parse(String text) {
+        try {
+            return Integer.parseInt(text);
+        } catch (NumberFormatException e) {
+            return -1;
+        } finally {
+            System.out.println("done");
+        }
+    }
+}
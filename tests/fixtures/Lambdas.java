@@ -0,0 +1,8 @@
+import java.util.List;
+import java.util.function.Function;
+
+public class Lambdas {
+    public Function<List<String>, Integer> size() {
+        return List::size;
+    }
+}
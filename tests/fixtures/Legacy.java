@@ -0,0 +1,11 @@
+public class Legacy {
+    /** @deprecated Use a newer field instead */
+    public int old;
+
+    /** @deprecated Use a newer method instead */
+    public void retire() {
+    }
+
+    class Inner {
+    }
+}
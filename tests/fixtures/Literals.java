@@ -0,0 +1,7 @@
+public class Literals {
+    public static Object[] load() {
+        return new Object[] {
+            "hello", 100000, 1.5f, 1234567890123L, 0.25, Object.class, int[].class
+        };
+    }
+}
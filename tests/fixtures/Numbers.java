@@ -0,0 +1,7 @@
+public class Numbers {
+    public static final int ANSWER = 100000;
+    public static final long BIG = 1234567890123L;
+    public static final float SCALE = 1.5f;
+    public static final double RATIO = 0.25;
+    public static final double NOTHING = Double.NaN;
+}
@@ -0,0 +1,15 @@
+public class Plain {
+    private int counter;
+
+    public Plain(int counter) {
+        this.counter = counter;
+    }
+
+    public int increment() {
+        return ++counter;
+    }
+
+    public static void main(String[] args) {
+        System.out.println(new Plain(41).increment());
+    }
+}
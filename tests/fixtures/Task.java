@@ -0,0 +1,6 @@
+import java.io.Serializable;
+
+public class Task implements Runnable, Serializable {
+    public void run() {
+    }
+}
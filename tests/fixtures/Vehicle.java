@@ -0,0 +1,7 @@
+public sealed interface Vehicle permits Vehicle.Car, Vehicle.Truck {
+    final class Car implements Vehicle {
+    }
+
+    non-sealed class Truck implements Vehicle {
+    }
+}
@@ -0,0 +1,3 @@
+module com.example.greeter {
+    exports com.example.greeter;
+}
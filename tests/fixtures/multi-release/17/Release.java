@@ -0,0 +1,5 @@
+public class Release {
+    public static String describe() {
+        return "Java 17";
+    }
+}